@@ -8,6 +8,7 @@ extern crate pretty_env_logger;
 #[macro_use]
 extern crate log;
 
+use kafka_threadpool::metadata::log_kafka_cluster_metadata::log_kafka_cluster_metadata;
 use kafka_threadpool::start_threadpool::start_threadpool;
 
 /// main
@@ -18,7 +19,13 @@ use kafka_threadpool::start_threadpool::start_threadpool;
 async fn main() {
     pretty_env_logger::init_timed();
     let label = "get-all-metadata";
-    let kafka_publisher = start_threadpool(Some(label)).await;
+    let kafka_publisher = match start_threadpool(Some(label)).await {
+        Ok(kafka_publisher) => kafka_publisher,
+        Err(e) => {
+            error!("{label} - failed to start threadpool with err={e}");
+            return;
+        }
+    };
 
     info!(
         "{label} \
@@ -27,7 +34,10 @@ async fn main() {
         kafka_publisher.config
     );
 
-    kafka_publisher.get_metadata(true, None).await;
+    match kafka_publisher.get_metadata(true, None).await {
+        Ok(metadata) => log_kafka_cluster_metadata(&metadata),
+        Err(e) => error!("{label} - failed to get metadata with err={e}"),
+    }
 
     info!("shutting down");
     // send shutdown message to all worker threads in the pool
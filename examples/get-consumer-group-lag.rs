@@ -0,0 +1,79 @@
+//! # Build the debug version
+//!
+//! ```bash
+//! cargo build --example get-consumer-group-lag && export RUST_BACKTRACE=1 && export RUST_LOG=info,kafka_threadpool=info && export KAFKA_CONSUMER_GROUP=rust-consumer-testing && ./target/debug/examples/get-consumer-group-lag
+//! ```
+//!
+extern crate pretty_env_logger;
+#[macro_use]
+extern crate log;
+
+use kafka_threadpool::start_threadpool::start_threadpool;
+
+/// main
+///
+/// Get the committed offsets and lag for the consumer group stored in
+/// the environment variable: ``KAFKA_CONSUMER_GROUP``. Set
+/// ``KAFKA_TOPIC`` to report every partition of a single topic. If
+/// there is no ``KAFKA_CONSUMER_GROUP`` set, the process will exit.
+///
+#[tokio::main]
+async fn main() {
+    pretty_env_logger::init_timed();
+    let label = "get-consumer-group-lag";
+    let kafka_publisher = match start_threadpool(Some(label)).await {
+        Ok(kafka_publisher) => kafka_publisher,
+        Err(e) => {
+            error!("{label} - failed to start threadpool with err={e}");
+            return;
+        }
+    };
+
+    let group_id = std::env::var("KAFKA_CONSUMER_GROUP")
+        .unwrap_or_else(|_| "".to_string());
+    let topic = std::env::var("KAFKA_TOPIC").unwrap_or_else(|_| "".to_string());
+    if group_id.is_empty() {
+        error!(
+            "please set a consumer group with: \
+            export KAFKA_CONSUMER_GROUP=GROUP_ID"
+        )
+    } else {
+        info!(
+            "{label} \
+            config={} \
+            getting lag for group={group_id}",
+            kafka_publisher.config
+        );
+        let topics = [topic.as_str()];
+        let topics: Option<&[&str]> = if topic.is_empty() {
+            None
+        } else {
+            Some(&topics)
+        };
+        match kafka_publisher
+            .get_consumer_group_lag(&group_id, topics)
+            .await
+        {
+            Ok(group_lag) => {
+                for partition in group_lag.partitions.iter() {
+                    info!("{label} - {partition}");
+                }
+                info!("{label} - {}", group_lag.summary());
+            }
+            Err(e) => {
+                error!(
+                    "{label} - failed to get lag for {group_id} with err={e}"
+                )
+            }
+        }
+    }
+
+    info!("shutting down");
+    // send shutdown message to all worker threads in the pool
+    match kafka_publisher.shutdown().await {
+        Ok(msg) => trace!("{msg}"),
+        Err(err_msg) => {
+            error!("publisher shutdown failed with err='{err_msg}'")
+        }
+    }
+}
@@ -9,6 +9,7 @@ extern crate pretty_env_logger;
 #[macro_use]
 extern crate log;
 
+use kafka_threadpool::metadata::log_kafka_cluster_metadata::log_kafka_cluster_metadata;
 use kafka_threadpool::start_threadpool::start_threadpool;
 
 /// main
@@ -20,7 +21,13 @@ use kafka_threadpool::start_threadpool::start_threadpool;
 async fn main() {
     pretty_env_logger::init_timed();
     let label = "get-metadata-for-topic";
-    let kafka_publisher = start_threadpool(Some(label)).await;
+    let kafka_publisher = match start_threadpool(Some(label)).await {
+        Ok(kafka_publisher) => kafka_publisher,
+        Err(e) => {
+            error!("{label} - failed to start threadpool with err={e}");
+            return;
+        }
+    };
 
     let topic = std::env::var("KAFKA_TOPIC").unwrap_or_else(|_| "".to_string());
     if topic.is_empty() {
@@ -33,7 +40,10 @@ async fn main() {
             kafka_publisher.config
         );
 
-        kafka_publisher.get_metadata(true, Some(&topic)).await;
+        match kafka_publisher.get_metadata(true, Some(&topic)).await {
+            Ok(metadata) => log_kafka_cluster_metadata(&metadata),
+            Err(e) => error!("{label} - failed to get metadata with err={e}"),
+        }
     }
 
     info!("shutting down");
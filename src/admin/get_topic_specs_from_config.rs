@@ -0,0 +1,39 @@
+//! Build the
+//! [`KafkaTopicSpec`](crate::admin::kafka_topic_spec::KafkaTopicSpec)
+//! list for the configured topics
+//!
+use crate::admin::kafka_topic_spec::KafkaTopicSpec;
+use crate::config::kafka_client_config::KafkaClientConfig;
+
+/// get_topic_specs_from_config
+///
+/// Combine the declared ``config.topic_specs`` with a default spec
+/// for every ``KAFKA_TOPICS`` topic that is not declared. Default
+/// specs use ``config.default_num_partitions`` and
+/// ``config.default_replication_factor``.
+///
+/// # Arguments
+///
+/// * `config` - initialized [`KafkaClientConfig`]
+///
+/// # Returns
+///
+/// ``Vec<KafkaTopicSpec>`` sorted by topic name
+///
+pub fn get_topic_specs_from_config(
+    config: &KafkaClientConfig,
+) -> Vec<KafkaTopicSpec> {
+    let mut specs: Vec<KafkaTopicSpec> = config.topic_specs.clone();
+    for topic in config.publish_topics.keys() {
+        if topic.is_empty() || specs.iter().any(|spec| &spec.name == topic) {
+            continue;
+        }
+        specs.push(
+            KafkaTopicSpec::new(topic)
+                .with_partitions(config.default_num_partitions)
+                .with_replication_factor(config.default_replication_factor),
+        );
+    }
+    specs.sort_by(|a, b| a.name.cmp(&b.name));
+    specs
+}
@@ -0,0 +1,325 @@
+//! Topic administration client for creating, deleting and changing
+//! topics
+//!
+use std::time::Duration;
+
+use log::error;
+use log::info;
+
+use rdkafka::admin::AdminClient;
+use rdkafka::admin::AdminOptions;
+use rdkafka::admin::AlterConfig;
+use rdkafka::admin::NewPartitions;
+use rdkafka::admin::NewTopic;
+use rdkafka::admin::ResourceSpecifier;
+use rdkafka::admin::TopicReplication;
+
+use crate::admin::kafka_admin_result::KafkaAdminResult;
+use crate::admin::kafka_topic_spec::KafkaTopicSpec;
+use crate::api::build_rdkafka_client_config::build_rdkafka_client_config;
+use crate::api::kafka_client_context::KafkaClientContext;
+use crate::api::kafka_oauth_client_context::KafkaOAuthClientContext;
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
+use crate::config::kafka_client_config::KafkaClientConfig;
+
+/// RdkafkaAdminClient
+///
+/// Internal ``rdkafka`` admin client with the
+/// [`KafkaOAuthClientContext`] when ``config.oauth_token_provider``
+/// is set
+///
+enum RdkafkaAdminClient {
+    Default(AdminClient<KafkaClientContext>),
+    OAuth(AdminClient<KafkaOAuthClientContext>),
+}
+
+/// KafkaAdminClient
+///
+/// Wraps the ``rdkafka``
+/// [`AdminClient`](rdkafka::admin::AdminClient) with the
+/// connectivity and security settings from a
+/// [`KafkaClientConfig`](crate::config::kafka_client_config::KafkaClientConfig)
+///
+/// Every operation returns one
+/// [`KafkaAdminResult`](crate::admin::kafka_admin_result::KafkaAdminResult)
+/// per topic. A ``KafkaThreadpoolError`` means the request itself
+/// failed (timeout, broker unavailable, etc.).
+///
+/// # Examples
+///
+/// ```rust
+/// use kafka_threadpool::admin::kafka_admin_client::KafkaAdminClient;
+/// use kafka_threadpool::admin::kafka_topic_spec::KafkaTopicSpec;
+/// let admin = KafkaAdminClient::new(&my_kafka_publisher.config).unwrap();
+/// let results = admin
+///     .ensure_topics(&[KafkaTopicSpec::new("orders").with_partitions(12)])
+///     .await
+///     .unwrap();
+/// for result in results.iter() {
+///     println!("{result}");
+/// }
+/// ```
+///
+pub struct KafkaAdminClient {
+    label: String,
+    client: RdkafkaAdminClient,
+    timeout: Duration,
+}
+
+impl KafkaAdminClient {
+    /// new
+    ///
+    /// Create an admin client that waits up to
+    /// ``config.admin_timeout`` for each operation
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - initialized [`KafkaClientConfig`]
+    ///
+    /// # Errors
+    ///
+    /// [`KafkaThreadpoolError::Broker`] if ``rdkafka`` fails to
+    /// create the admin client
+    ///
+    pub fn new(
+        config: &KafkaClientConfig,
+    ) -> Result<Self, KafkaThreadpoolError> {
+        let client_config = build_rdkafka_client_config(config);
+        let client = match &config.oauth_token_provider {
+            Some(token_provider) => {
+                RdkafkaAdminClient::OAuth(client_config.create_with_context(
+                    KafkaOAuthClientContext::new(
+                        KafkaClientContext::default(),
+                        token_provider.clone(),
+                    ),
+                )?)
+            }
+            None => RdkafkaAdminClient::Default(
+                client_config
+                    .create_with_context(KafkaClientContext::default())?,
+            ),
+        };
+        Ok(KafkaAdminClient {
+            label: config.label.clone(),
+            client,
+            timeout: config.admin_timeout,
+        })
+    }
+
+    /// admin_options
+    ///
+    /// Internal helper for the operation and request timeouts
+    ///
+    fn admin_options(&self) -> AdminOptions {
+        AdminOptions::new()
+            .operation_timeout(Some(self.timeout))
+            .request_timeout(Some(self.timeout))
+    }
+
+    /// create_topics
+    ///
+    /// Create topics from their specs
+    ///
+    /// # Arguments
+    ///
+    /// * `specs` - topics to create
+    ///
+    /// # Returns
+    ///
+    /// ``Vec<KafkaAdminResult>`` with ``AlreadyExists`` for topics
+    /// that exist
+    ///
+    /// # Errors
+    ///
+    /// [`KafkaThreadpoolError::Broker`] if the request fails
+    ///
+    pub async fn create_topics(
+        &self,
+        specs: &[KafkaTopicSpec],
+    ) -> Result<Vec<KafkaAdminResult>, KafkaThreadpoolError> {
+        if specs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let new_topics: Vec<NewTopic> = specs
+            .iter()
+            .map(|spec| {
+                spec.configs.iter().fold(
+                    NewTopic::new(
+                        &spec.name,
+                        spec.num_partitions,
+                        TopicReplication::Fixed(spec.replication_factor),
+                    ),
+                    |new_topic, (key, value)| new_topic.set(key, value),
+                )
+            })
+            .collect();
+        let results = match &self.client {
+            RdkafkaAdminClient::Default(client) => {
+                client
+                    .create_topics(new_topics.iter(), &self.admin_options())
+                    .await?
+            }
+            RdkafkaAdminClient::OAuth(client) => {
+                client
+                    .create_topics(new_topics.iter(), &self.admin_options())
+                    .await?
+            }
+        };
+        Ok(results.into_iter().map(KafkaAdminResult::from).collect())
+    }
+
+    /// ensure_topics
+    ///
+    /// Create any topics that do not exist. Existing topics are
+    /// not changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `specs` - topics that must exist
+    ///
+    /// # Returns
+    ///
+    /// ``Vec<KafkaAdminResult>`` where every result is
+    /// ``Succeeded`` or ``AlreadyExists``
+    ///
+    /// # Errors
+    ///
+    /// [`KafkaThreadpoolError::Broker`] if the request fails or
+    /// [`KafkaThreadpoolError::Admin`] for the first topic that
+    /// could not be created
+    ///
+    pub async fn ensure_topics(
+        &self,
+        specs: &[KafkaTopicSpec],
+    ) -> Result<Vec<KafkaAdminResult>, KafkaThreadpoolError> {
+        let results = self.create_topics(specs).await?;
+        for result in results.iter() {
+            info!("{} - ensure topic {result}", self.label);
+        }
+        match results.iter().find(|result| !result.is_ok()) {
+            Some(failed) => {
+                error!("{} - failed to ensure topic {failed}", self.label);
+                Err(KafkaThreadpoolError::Admin {
+                    name: failed.name.clone(),
+                    reason: failed.to_string(),
+                })
+            }
+            None => Ok(results),
+        }
+    }
+
+    /// delete_topics
+    ///
+    /// Delete topics and all of their messages
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - topics to delete
+    ///
+    /// # Errors
+    ///
+    /// [`KafkaThreadpoolError::Broker`] if the request fails
+    ///
+    pub async fn delete_topics(
+        &self,
+        names: &[&str],
+    ) -> Result<Vec<KafkaAdminResult>, KafkaThreadpoolError> {
+        if names.is_empty() {
+            return Ok(Vec::new());
+        }
+        let results = match &self.client {
+            RdkafkaAdminClient::Default(client) => {
+                client.delete_topics(names, &self.admin_options()).await?
+            }
+            RdkafkaAdminClient::OAuth(client) => {
+                client.delete_topics(names, &self.admin_options()).await?
+            }
+        };
+        Ok(results.into_iter().map(KafkaAdminResult::from).collect())
+    }
+
+    /// add_partitions
+    ///
+    /// Grow topics to a new total number of partitions (kafka
+    /// cannot remove partitions)
+    ///
+    /// # Arguments
+    ///
+    /// * `partitions` - ``(topic, new total number of partitions)``
+    /// pairs
+    ///
+    /// # Errors
+    ///
+    /// [`KafkaThreadpoolError::Broker`] if the request fails
+    ///
+    pub async fn add_partitions(
+        &self,
+        partitions: &[(&str, usize)],
+    ) -> Result<Vec<KafkaAdminResult>, KafkaThreadpoolError> {
+        if partitions.is_empty() {
+            return Ok(Vec::new());
+        }
+        let new_partitions: Vec<NewPartitions> = partitions
+            .iter()
+            .map(|(name, total)| NewPartitions::new(name, *total))
+            .collect();
+        let results = match &self.client {
+            RdkafkaAdminClient::Default(client) => {
+                client
+                    .create_partitions(
+                        new_partitions.iter(),
+                        &self.admin_options(),
+                    )
+                    .await?
+            }
+            RdkafkaAdminClient::OAuth(client) => {
+                client
+                    .create_partitions(
+                        new_partitions.iter(),
+                        &self.admin_options(),
+                    )
+                    .await?
+            }
+        };
+        Ok(results.into_iter().map(KafkaAdminResult::from).collect())
+    }
+
+    /// alter_topic_configs
+    ///
+    /// Set topic configs. Kafka's ``AlterConfigs`` is not
+    /// incremental so any config that is not in ``configs`` goes
+    /// back to the broker default.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - topic name
+    /// * `configs` - ``(config name, value)`` pairs
+    ///
+    /// # Errors
+    ///
+    /// [`KafkaThreadpoolError::Broker`] if the request fails
+    ///
+    pub async fn alter_topic_configs(
+        &self,
+        name: &str,
+        configs: &[(&str, &str)],
+    ) -> Result<Vec<KafkaAdminResult>, KafkaThreadpoolError> {
+        let alter_config = configs.iter().fold(
+            AlterConfig::new(ResourceSpecifier::Topic(name)),
+            |alter_config, (key, value)| alter_config.set(key, value),
+        );
+        let results = match &self.client {
+            RdkafkaAdminClient::Default(client) => {
+                client
+                    .alter_configs([&alter_config], &self.admin_options())
+                    .await?
+            }
+            RdkafkaAdminClient::OAuth(client) => {
+                client
+                    .alter_configs([&alter_config], &self.admin_options())
+                    .await?
+            }
+        };
+        Ok(results.into_iter().map(KafkaAdminResult::from).collect())
+    }
+}
@@ -0,0 +1,125 @@
+//! class definitions for the per-resource outcome of a
+//! [`KafkaAdminClient`](crate::admin::kafka_admin_client::KafkaAdminClient)
+//! operation
+//!
+use rdkafka::admin::AlterConfigsResult;
+use rdkafka::admin::OwnedResourceSpecifier;
+use rdkafka::admin::TopicResult;
+use rdkafka::types::RDKafkaErrorCode;
+
+/// KafkaAdminStatus
+///
+/// Outcome for one topic (or config resource)
+///
+/// - ``Succeeded`` - kafka applied the operation
+/// - ``AlreadyExists`` - the topic already exists so nothing was
+/// created
+/// - ``Failed`` - kafka rejected the operation with the error code
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KafkaAdminStatus {
+    Succeeded,
+    AlreadyExists,
+    Failed {
+        code: RDKafkaErrorCode,
+        reason: String,
+    },
+}
+
+/// KafkaAdminResult
+///
+/// Outcome of an admin operation for one resource
+///
+/// * `name` - topic name (or broker id for broker configs)
+/// * `status` - [`KafkaAdminStatus`] for the resource
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KafkaAdminResult {
+    pub name: String,
+    pub status: KafkaAdminStatus,
+}
+
+impl KafkaAdminResult {
+    /// is_ok
+    ///
+    /// # Returns
+    ///
+    /// ``true`` if the operation succeeded or the topic already
+    /// exists
+    ///
+    pub fn is_ok(&self) -> bool {
+        !matches!(self.status, KafkaAdminStatus::Failed { .. })
+    }
+
+    /// from_error_code
+    ///
+    /// Internal helper for mapping an ``rdkafka`` error code
+    ///
+    fn from_error_code(name: String, code: RDKafkaErrorCode) -> Self {
+        let status = if code == RDKafkaErrorCode::TopicAlreadyExists {
+            KafkaAdminStatus::AlreadyExists
+        } else {
+            KafkaAdminStatus::Failed {
+                code,
+                reason: code.to_string(),
+            }
+        };
+        KafkaAdminResult { name, status }
+    }
+}
+
+/// resource_name
+///
+/// Internal helper for naming an ``rdkafka`` config resource
+///
+fn resource_name(resource: OwnedResourceSpecifier) -> String {
+    match resource {
+        OwnedResourceSpecifier::Topic(name) => name,
+        OwnedResourceSpecifier::Group(name) => name,
+        OwnedResourceSpecifier::Broker(id) => id.to_string(),
+    }
+}
+
+impl From<TopicResult> for KafkaAdminResult {
+    fn from(result: TopicResult) -> Self {
+        match result {
+            Ok(name) => KafkaAdminResult {
+                name,
+                status: KafkaAdminStatus::Succeeded,
+            },
+            Err((name, code)) => KafkaAdminResult::from_error_code(name, code),
+        }
+    }
+}
+
+impl From<AlterConfigsResult> for KafkaAdminResult {
+    fn from(result: AlterConfigsResult) -> Self {
+        match result {
+            Ok(resource) => KafkaAdminResult {
+                name: resource_name(resource),
+                status: KafkaAdminStatus::Succeeded,
+            },
+            Err((resource, code)) => {
+                KafkaAdminResult::from_error_code(resource_name(resource), code)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for KafkaAdminResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.status {
+            KafkaAdminStatus::Succeeded => {
+                write!(f, "name={} status=succeeded", self.name)
+            }
+            KafkaAdminStatus::AlreadyExists => {
+                write!(f, "name={} status=already_exists", self.name)
+            }
+            KafkaAdminStatus::Failed { code, reason } => write!(
+                f,
+                "name={} status=failed code={code:?} reason={reason}",
+                self.name
+            ),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! class definition for a topic declaration used by the
+//! [`KafkaAdminClient`](crate::admin::kafka_admin_client::KafkaAdminClient)
+//!
+use std::collections::BTreeMap;
+
+/// KafkaTopicSpec
+///
+/// Declares how a topic is created
+///
+/// * `name` - topic name
+/// * `num_partitions` - number of partitions (``-1`` uses the
+/// broker's ``num.partitions`` default)
+/// * `replication_factor` - number of replicas per partition
+/// (``-1`` uses the broker's ``default.replication.factor``)
+/// * `configs` - topic configs (``cleanup.policy``,
+/// ``retention.ms``, etc.)
+///
+/// # Examples
+///
+/// ```rust
+/// use kafka_threadpool::admin::kafka_topic_spec::KafkaTopicSpec;
+/// let spec = KafkaTopicSpec::new("orders")
+///     .with_partitions(12)
+///     .with_replication_factor(3)
+///     .with_config("cleanup.policy", "compact");
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KafkaTopicSpec {
+    pub name: String,
+    pub num_partitions: i32,
+    pub replication_factor: i32,
+    pub configs: BTreeMap<String, String>,
+}
+
+impl KafkaTopicSpec {
+    /// new
+    ///
+    /// Create a spec that uses the broker's partition and
+    /// replication defaults
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - topic name
+    ///
+    pub fn new(name: &str) -> Self {
+        KafkaTopicSpec {
+            name: name.to_string(),
+            num_partitions: -1,
+            replication_factor: -1,
+            configs: BTreeMap::new(),
+        }
+    }
+
+    /// with_partitions
+    ///
+    /// # Arguments
+    ///
+    /// * `num_partitions` - number of partitions
+    ///
+    pub fn with_partitions(mut self, num_partitions: i32) -> Self {
+        self.num_partitions = num_partitions;
+        self
+    }
+
+    /// with_replication_factor
+    ///
+    /// # Arguments
+    ///
+    /// * `replication_factor` - number of replicas per partition
+    ///
+    pub fn with_replication_factor(mut self, replication_factor: i32) -> Self {
+        self.replication_factor = replication_factor;
+        self
+    }
+
+    /// with_config
+    ///
+    /// Set a topic config (a repeated ``key`` replaces the value)
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - topic config name
+    /// * `value` - topic config value
+    ///
+    pub fn with_config(mut self, key: &str, value: &str) -> Self {
+        self.configs.insert(key.to_string(), value.to_string());
+        self
+    }
+}
+
+impl std::fmt::Display for KafkaTopicSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "KafkaTopicSpec name={} partitions={} replication_factor={} \
+            configs={:?}",
+            self.name,
+            self.num_partitions,
+            self.replication_factor,
+            self.configs
+        )
+    }
+}
@@ -0,0 +1,15 @@
+//! Topic administration built on the ``rdkafka``
+//! [`AdminClient`](rdkafka::admin::AdminClient)
+//!
+//! Supported operations:
+//!
+//! - ensure the configured topics exist (at pool startup when
+//! ``KAFKA_ENSURE_TOPICS=true``)
+//! - create and delete topics
+//! - add partitions to a topic
+//! - alter topic configs
+//!
+pub mod get_topic_specs_from_config;
+pub mod kafka_admin_client;
+pub mod kafka_admin_result;
+pub mod kafka_topic_spec;
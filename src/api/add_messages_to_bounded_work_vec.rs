@@ -0,0 +1,700 @@
+//! Helper for locking the work Vec and adding messages while
+//! enforcing the configured capacity
+//!
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use log::error;
+
+use crate::api::kafka_delivery_error::KafkaDeliveryError;
+use crate::api::kafka_publish_message::KafkaPublishMessage;
+use crate::api::kafka_publish_message_type::KafkaPublishMessageType;
+use crate::api::kafka_queue_add_status::KafkaQueueAddStatus;
+use crate::api::kafka_queue_overflow_policy::KafkaQueueOverflowPolicy;
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
+use crate::config::kafka_client_config::KafkaClientConfig;
+#[cfg(feature = "metrics")]
+use crate::metrics::record_metrics::count_msgs_by_topic;
+#[cfg(feature = "metrics")]
+use crate::metrics::record_metrics::record_dropped;
+#[cfg(feature = "metrics")]
+use crate::metrics::record_metrics::record_enqueued;
+
+/// is_droppable
+///
+/// Internal helper for checking if an overflow policy may drop a
+/// message (control messages like ``Shutdown`` and whole
+/// transactions are never dropped)
+///
+fn is_droppable(msg: &KafkaPublishMessage) -> bool {
+    msg.msg_type == KafkaPublishMessageType::Data
+        || msg.msg_type == KafkaPublishMessageType::Sensitive
+}
+
+/// drop_message
+///
+/// Internal helper for telling any waiting caller that the
+/// overflow policy dropped the message
+///
+fn drop_message(msg: KafkaPublishMessage) {
+    if let Some(delivery) = &msg.delivery {
+        delivery.notify(Err(KafkaDeliveryError::Dropped));
+    }
+    if let Some(transaction) = &msg.transaction {
+        transaction.notify_dropped();
+    }
+}
+
+/// check_work_vec_capacity
+///
+/// Check that messages can ever fit in a work vec with the
+/// configured ``config.max_queue_msgs`` and ``config.max_queue_bytes``
+/// capacity (``0`` means unbounded)
+///
+/// # Arguments
+///
+/// * `msgs` - messages to add
+/// * `config` - initialized [`KafkaClientConfig`] with the
+/// capacity
+///
+/// # Errors
+///
+/// ``Err(KafkaThreadpoolError::NoMessages)`` or
+/// ``Err(KafkaThreadpoolError::QueueFull)`` if ``msgs`` are larger
+/// than the capacity
+///
+pub fn check_work_vec_capacity(
+    msgs: &[KafkaPublishMessage],
+    config: &KafkaClientConfig,
+) -> Result<(), KafkaThreadpoolError> {
+    let num_to_add = msgs.len();
+    if num_to_add == 0 {
+        let err = KafkaThreadpoolError::NoMessages;
+        error!("{err}");
+        return Err(err);
+    }
+    let max_msgs = config.max_queue_msgs;
+    let max_bytes = config.max_queue_bytes;
+    let bytes_to_add: usize = msgs.iter().map(|m| m.size_in_bytes()).sum();
+    if (max_msgs > 0 && num_to_add > max_msgs)
+        || (max_bytes > 0 && bytes_to_add > max_bytes)
+    {
+        error!(
+            "unable to add {num_to_add} msgs with bytes={bytes_to_add} \
+            larger than the work vec capacity \
+            max_msgs={max_msgs} max_bytes={max_bytes}"
+        );
+        return Err(KafkaThreadpoolError::QueueFull {
+            num_msgs: num_to_add,
+            max_msgs,
+            max_bytes,
+        });
+    }
+    Ok(())
+}
+
+/// has_space_in_work_vec
+///
+/// Check if the overflow policy adds the messages to a locked work
+/// vec instead of handing them back
+///
+/// # Arguments
+///
+/// * `work_vec` - the locked work vec
+/// * `msgs` - messages to add
+/// * `config` - initialized [`KafkaClientConfig`] with the
+/// capacity and overflow policy
+///
+/// # Returns
+///
+/// ``true`` if
+/// [`add_messages_to_locked_bounded_work_vec`] returns
+/// ``KafkaQueueAddStatus::Added`` (``DropOldest`` and
+/// ``DropNewest`` may drop messages to get there)
+///
+pub fn has_space_in_work_vec(
+    work_vec: &[KafkaPublishMessage],
+    msgs: &[KafkaPublishMessage],
+    config: &KafkaClientConfig,
+) -> bool {
+    let max_msgs = config.max_queue_msgs;
+    let max_bytes = config.max_queue_bytes;
+    let kept_msgs: Vec<&KafkaPublishMessage> =
+        match config.queue_overflow_policy {
+            KafkaQueueOverflowPolicy::DropNewest => return true,
+            // only the messages it cannot drop stay in the work vec
+            KafkaQueueOverflowPolicy::DropOldest => {
+                work_vec.iter().filter(|m| !is_droppable(m)).collect()
+            }
+            KafkaQueueOverflowPolicy::Block
+            | KafkaQueueOverflowPolicy::Reject => work_vec.iter().collect(),
+        };
+    let num_msgs = kept_msgs.len() + msgs.len();
+    let num_bytes: usize = if max_bytes > 0 {
+        kept_msgs
+            .iter()
+            .map(|m| m.size_in_bytes())
+            .chain(msgs.iter().map(|m| m.size_in_bytes()))
+            .sum()
+    } else {
+        0
+    };
+    (max_msgs == 0 || num_msgs <= max_msgs)
+        && (max_bytes == 0 || num_bytes <= max_bytes)
+}
+
+/// add_messages_to_bounded_work_vec
+///
+/// API for adding many messages into the ``lockable_work_vec`` Vec
+/// while the ``Mutex`` is locked without growing past the
+/// configured ``config.max_queue_msgs`` or ``config.max_queue_bytes``
+/// capacity. A capacity of ``0`` means unbounded.
+///
+/// # Returns
+///
+/// ``Result<KafkaQueueAddStatus, KafkaThreadpoolError>``
+///
+/// where:
+///
+/// Ok - [`KafkaQueueAddStatus`] with the updated number of messages
+/// in the work vec or the messages handed back to the caller if the
+/// work vec is full and the overflow policy is ``Block`` or ``Reject``
+/// (or ``DropOldest`` could not drop enough messages to make room)
+/// Error - ``Err(KafkaThreadpoolError::NoMessages)``,
+/// ``Err(KafkaThreadpoolError::QueueFull)`` if ``msgs`` can never fit
+/// in the work vec or ``Err(KafkaThreadpoolError::LockPoisoned)``
+///
+/// # Arguments
+///
+/// * `lockable_work_vec` - shared work vec of
+/// [`KafkaPublishMessage`] messages to process within a lockable
+/// [`Arc<Mutex<lockable_work_vec>>`] thread-safe object
+/// * `msgs` - Vec of [`KafkaPublishMessage`] messages to add
+/// to the locked ``lockable_work_vec``
+/// * `config` - initialized [`KafkaClientConfig`] with the
+/// capacity and overflow policy
+///
+pub fn add_messages_to_bounded_work_vec(
+    lockable_work_vec: &Arc<Mutex<Vec<KafkaPublishMessage>>>,
+    msgs: Vec<KafkaPublishMessage>,
+    config: &KafkaClientConfig,
+) -> Result<KafkaQueueAddStatus, KafkaThreadpoolError> {
+    check_work_vec_capacity(&msgs, config)?;
+    // CRITICAL SECTION - start - lock the mutex
+    match lockable_work_vec.lock() {
+        Ok(mut local_access_to_work_vec) => {
+            Ok(add_messages_to_locked_bounded_work_vec(
+                &mut local_access_to_work_vec,
+                msgs,
+                config,
+            ))
+        }
+        Err(e) => {
+            let err = KafkaThreadpoolError::LockPoisoned(e.to_string());
+            error!("{err}");
+            Err(err)
+        }
+    }
+    // CRITICAL SECTION - end - unlock the mutex
+}
+
+/// add_messages_to_locked_bounded_work_vec
+///
+/// Add messages to an already locked work vec with the overflow
+/// policy. Callers check the messages with
+/// [`check_work_vec_capacity`] first.
+///
+/// # Returns
+///
+/// [`KafkaQueueAddStatus`] with the updated number of messages
+/// in the work vec or the messages handed back to the caller if the
+/// work vec is full and the overflow policy is ``Block`` or ``Reject``
+/// (or ``DropOldest`` could not drop enough messages to make room)
+///
+/// # Arguments
+///
+/// * `local_access_to_work_vec` - the locked work vec
+/// * `msgs` - Vec of [`KafkaPublishMessage`] messages to add
+/// * `config` - initialized [`KafkaClientConfig`] with the
+/// capacity and overflow policy
+///
+pub fn add_messages_to_locked_bounded_work_vec(
+    local_access_to_work_vec: &mut Vec<KafkaPublishMessage>,
+    mut msgs: Vec<KafkaPublishMessage>,
+    config: &KafkaClientConfig,
+) -> KafkaQueueAddStatus {
+    let num_to_add = msgs.len();
+    let max_msgs = config.max_queue_msgs;
+    let max_bytes = config.max_queue_bytes;
+    let bytes_to_add: usize = msgs.iter().map(|m| m.size_in_bytes()).sum();
+    #[cfg(feature = "metrics")]
+    let mut topic_counts = count_msgs_by_topic(&msgs);
+    let mut num_in_vec = local_access_to_work_vec.len();
+    let mut bytes_in_vec: usize = 0;
+    if max_bytes > 0 {
+        bytes_in_vec = local_access_to_work_vec
+            .iter()
+            .map(|m| m.size_in_bytes())
+            .sum();
+    }
+    let has_space = |num_msgs: usize, num_bytes: usize| {
+        (max_msgs == 0 || num_msgs <= max_msgs)
+            && (max_bytes == 0 || num_bytes <= max_bytes)
+    };
+    if has_space(num_in_vec + num_to_add, bytes_in_vec + bytes_to_add) {
+        local_access_to_work_vec.append(&mut msgs);
+        #[cfg(feature = "metrics")]
+        record_enqueued(&config.label, &topic_counts);
+        return KafkaQueueAddStatus::Added {
+            num_in_vec: local_access_to_work_vec.len(),
+            num_dropped: 0,
+        };
+    }
+    let mut num_dropped: usize = 0;
+    match config.queue_overflow_policy {
+        KafkaQueueOverflowPolicy::Block | KafkaQueueOverflowPolicy::Reject => {
+            return KafkaQueueAddStatus::Full(msgs);
+        }
+        KafkaQueueOverflowPolicy::DropOldest => {
+            // pick the oldest messages to drop before changing
+            // the work vec (never drop control messages like
+            // Shutdown or whole transactions)
+            let mut to_drop: Vec<bool> =
+                vec![false; local_access_to_work_vec.len()];
+            for (idx, old_msg) in local_access_to_work_vec.iter().enumerate() {
+                if has_space(
+                    num_in_vec + num_to_add,
+                    bytes_in_vec + bytes_to_add,
+                ) {
+                    break;
+                }
+                if is_droppable(old_msg) {
+                    to_drop[idx] = true;
+                    num_in_vec -= 1;
+                    if max_bytes > 0 {
+                        bytes_in_vec -= old_msg.size_in_bytes();
+                    }
+                    num_dropped += 1;
+                }
+            }
+            if !has_space(num_in_vec + num_to_add, bytes_in_vec + bytes_to_add)
+            {
+                // not enough droppable messages to make room
+                return KafkaQueueAddStatus::Full(msgs);
+            }
+            // remove the dropped messages in a single pass
+            let old_msgs: Vec<KafkaPublishMessage> =
+                local_access_to_work_vec.drain(..).collect();
+            for (old_msg, drop) in old_msgs.into_iter().zip(to_drop) {
+                if drop {
+                    drop_message(old_msg);
+                } else {
+                    local_access_to_work_vec.push(old_msg);
+                }
+            }
+            local_access_to_work_vec.append(&mut msgs);
+        }
+        KafkaQueueOverflowPolicy::DropNewest => {
+            for msg in msgs.drain(..) {
+                let msg_bytes = msg.size_in_bytes();
+                // control messages and transactions are
+                // always added
+                if !is_droppable(&msg)
+                    || has_space(num_in_vec + 1, bytes_in_vec + msg_bytes)
+                {
+                    num_in_vec += 1;
+                    if max_bytes > 0 {
+                        bytes_in_vec += msg_bytes;
+                    }
+                    local_access_to_work_vec.push(msg);
+                } else {
+                    // only count the messages that were enqueued
+                    #[cfg(feature = "metrics")]
+                    for (topic, count) in
+                        count_msgs_by_topic(std::slice::from_ref(&msg))
+                    {
+                        if let Some(total) = topic_counts.get_mut(&topic) {
+                            *total -= count;
+                        }
+                    }
+                    num_dropped += 1;
+                    drop_message(msg);
+                }
+            }
+        }
+    }
+    #[cfg(feature = "metrics")]
+    {
+        record_enqueued(&config.label, &topic_counts);
+        record_dropped(&config.label, num_dropped);
+    }
+    KafkaQueueAddStatus::Added {
+        num_in_vec: local_access_to_work_vec.len(),
+        num_dropped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::kafka_delivery_notifier::KafkaDeliveryFuture;
+    use crate::api::kafka_delivery_notifier::KafkaDeliveryNotifier;
+    use crate::api::kafka_transaction::KafkaTransaction;
+    use crate::api::kafka_transaction_notifier::KafkaTransactionNotifier;
+
+    // every data msg uses 10 bytes: topic "t" + 9 byte payload
+    fn data_msg(payload: &str) -> KafkaPublishMessage {
+        KafkaPublishMessage::new_from_bytes(
+            KafkaPublishMessageType::Data,
+            "t",
+            None,
+            Vec::new(),
+            Some(format!("{payload:<9}").into_bytes()),
+        )
+    }
+
+    fn tracked_msg(
+        payload: &str,
+    ) -> (KafkaPublishMessage, KafkaDeliveryFuture) {
+        let (notifier, delivery) = KafkaDeliveryNotifier::new();
+        let mut msg = data_msg(payload);
+        msg.delivery = Some(notifier);
+        (msg, delivery)
+    }
+
+    fn shutdown_msg() -> KafkaPublishMessage {
+        let mut msg = KafkaPublishMessage::new();
+        msg.msg_type = KafkaPublishMessageType::Shutdown;
+        msg
+    }
+
+    fn build_config(
+        max_queue_msgs: usize,
+        max_queue_bytes: usize,
+        queue_overflow_policy: KafkaQueueOverflowPolicy,
+    ) -> KafkaClientConfig {
+        let mut config = KafkaClientConfig::default();
+        config.max_queue_msgs = max_queue_msgs;
+        config.max_queue_bytes = max_queue_bytes;
+        config.queue_overflow_policy = queue_overflow_policy;
+        config
+    }
+
+    fn new_work_vec(
+        msgs: Vec<KafkaPublishMessage>,
+    ) -> Arc<Mutex<Vec<KafkaPublishMessage>>> {
+        Arc::new(Mutex::new(msgs))
+    }
+
+    fn payloads(
+        work_vec: &Arc<Mutex<Vec<KafkaPublishMessage>>>,
+    ) -> Vec<String> {
+        work_vec
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|msg| {
+                msg.payload_as_string()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    fn expect_added(
+        res: Result<KafkaQueueAddStatus, KafkaThreadpoolError>,
+    ) -> (usize, usize) {
+        match res {
+            Ok(KafkaQueueAddStatus::Added {
+                num_in_vec,
+                num_dropped,
+            }) => (num_in_vec, num_dropped),
+            Ok(KafkaQueueAddStatus::Full(msgs)) => {
+                panic!("expected Added got Full({})", msgs.len())
+            }
+            Err(e) => panic!("expected Added got {e}"),
+        }
+    }
+
+    fn expect_full(
+        res: Result<KafkaQueueAddStatus, KafkaThreadpoolError>,
+    ) -> Vec<KafkaPublishMessage> {
+        match res {
+            Ok(KafkaQueueAddStatus::Full(msgs)) => msgs,
+            Ok(KafkaQueueAddStatus::Added { num_in_vec, .. }) => {
+                panic!("expected Full got Added num_in_vec={num_in_vec}")
+            }
+            Err(e) => panic!("expected Full got {e}"),
+        }
+    }
+
+    #[test]
+    fn unbounded_work_vec_adds_everything() {
+        let config = build_config(0, 0, KafkaQueueOverflowPolicy::Reject);
+        let work_vec =
+            new_work_vec((0..100).map(|_| data_msg("old")).collect());
+        let res = add_messages_to_bounded_work_vec(
+            &work_vec,
+            vec![data_msg("a"), data_msg("b")],
+            &config,
+        );
+        assert_eq!(expect_added(res), (102, 0));
+    }
+
+    #[test]
+    fn no_messages_is_an_error() {
+        let config = build_config(2, 0, KafkaQueueOverflowPolicy::Block);
+        let res = add_messages_to_bounded_work_vec(
+            &new_work_vec(Vec::new()),
+            Vec::new(),
+            &config,
+        );
+        assert!(matches!(res, Err(KafkaThreadpoolError::NoMessages)));
+    }
+
+    #[test]
+    fn messages_larger_than_the_capacity_are_queue_full() {
+        let config = build_config(2, 25, KafkaQueueOverflowPolicy::DropOldest);
+        let work_vec = new_work_vec(Vec::new());
+        let res = add_messages_to_bounded_work_vec(
+            &work_vec,
+            vec![data_msg("a"), data_msg("b"), data_msg("c")],
+            &config,
+        );
+        assert!(matches!(
+            res,
+            Err(KafkaThreadpoolError::QueueFull { num_msgs: 3, .. })
+        ));
+        let config = build_config(0, 15, KafkaQueueOverflowPolicy::DropNewest);
+        let res = add_messages_to_bounded_work_vec(
+            &work_vec,
+            vec![data_msg("a"), data_msg("b")],
+            &config,
+        );
+        assert!(matches!(
+            res,
+            Err(KafkaThreadpoolError::QueueFull { num_msgs: 2, .. })
+        ));
+        assert!(work_vec.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn block_and_reject_return_the_messages_when_full() {
+        for policy in [
+            KafkaQueueOverflowPolicy::Block,
+            KafkaQueueOverflowPolicy::Reject,
+        ] {
+            let config = build_config(3, 0, policy);
+            let work_vec = new_work_vec(vec![data_msg("a"), data_msg("b")]);
+            let full = expect_full(add_messages_to_bounded_work_vec(
+                &work_vec,
+                vec![data_msg("c"), data_msg("d")],
+                &config,
+            ));
+            assert_eq!(full.len(), 2);
+            assert_eq!(full[0].payload_as_string().unwrap().trim(), "c");
+            assert_eq!(payloads(&work_vec), vec!["a", "b"]);
+            // the last free slot still accepts a message
+            let res = add_messages_to_bounded_work_vec(
+                &work_vec,
+                vec![data_msg("c")],
+                &config,
+            );
+            assert_eq!(expect_added(res), (3, 0));
+        }
+    }
+
+    #[test]
+    fn reject_counts_bytes() {
+        let config = build_config(0, 25, KafkaQueueOverflowPolicy::Reject);
+        let work_vec = new_work_vec(vec![data_msg("a"), data_msg("b")]);
+        let full = expect_full(add_messages_to_bounded_work_vec(
+            &work_vec,
+            vec![data_msg("c")],
+            &config,
+        ));
+        assert_eq!(full.len(), 1);
+        assert_eq!(payloads(&work_vec), vec!["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_frees_enough_bytes() {
+        let config = build_config(0, 35, KafkaQueueOverflowPolicy::DropOldest);
+        let (oldest, oldest_delivery) = tracked_msg("a");
+        let (second, second_delivery) = tracked_msg("b");
+        let work_vec = new_work_vec(vec![oldest, second, data_msg("c")]);
+        let res = add_messages_to_bounded_work_vec(
+            &work_vec,
+            vec![data_msg("d"), data_msg("e")],
+            &config,
+        );
+        // 30 + 20 bytes needs two 10 byte messages dropped
+        assert_eq!(expect_added(res), (3, 2));
+        assert_eq!(payloads(&work_vec), vec!["c", "d", "e"]);
+        assert_eq!(oldest_delivery.await, Err(KafkaDeliveryError::Dropped));
+        assert_eq!(second_delivery.await, Err(KafkaDeliveryError::Dropped));
+    }
+
+    #[test]
+    fn drop_oldest_without_a_byte_limit() {
+        // regression test for the byte accounting underflow when only
+        // max_queue_msgs is set
+        let config = build_config(2, 0, KafkaQueueOverflowPolicy::DropOldest);
+        let work_vec = new_work_vec(vec![data_msg("a"), data_msg("b")]);
+        let res = add_messages_to_bounded_work_vec(
+            &work_vec,
+            vec![data_msg("c")],
+            &config,
+        );
+        assert_eq!(expect_added(res), (2, 1));
+        assert_eq!(payloads(&work_vec), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn drop_oldest_never_drops_control_messages() {
+        let config = build_config(2, 0, KafkaQueueOverflowPolicy::DropOldest);
+        let work_vec = new_work_vec(vec![shutdown_msg(), data_msg("a")]);
+        let res = add_messages_to_bounded_work_vec(
+            &work_vec,
+            vec![data_msg("b")],
+            &config,
+        );
+        assert_eq!(expect_added(res), (2, 1));
+        {
+            let locked = work_vec.lock().unwrap();
+            assert_eq!(locked[0].msg_type, KafkaPublishMessageType::Shutdown);
+        }
+        assert_eq!(payloads(&work_vec)[1], "b");
+        // nothing left to drop
+        let work_vec = new_work_vec(vec![shutdown_msg(), shutdown_msg()]);
+        let full = expect_full(add_messages_to_bounded_work_vec(
+            &work_vec,
+            vec![data_msg("c")],
+            &config,
+        ));
+        assert_eq!(full.len(), 1);
+        assert_eq!(work_vec.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn drop_newest_adds_what_fits() {
+        let config = build_config(3, 0, KafkaQueueOverflowPolicy::DropNewest);
+        let work_vec = new_work_vec(vec![data_msg("a")]);
+        let (dropped, dropped_delivery) = tracked_msg("d");
+        let res = add_messages_to_bounded_work_vec(
+            &work_vec,
+            vec![data_msg("b"), data_msg("c"), dropped],
+            &config,
+        );
+        assert_eq!(expect_added(res), (3, 1));
+        assert_eq!(payloads(&work_vec), vec!["a", "b", "c"]);
+        assert_eq!(dropped_delivery.await, Err(KafkaDeliveryError::Dropped));
+    }
+
+    #[test]
+    fn drop_newest_counts_bytes() {
+        let config = build_config(0, 25, KafkaQueueOverflowPolicy::DropNewest);
+        let work_vec = new_work_vec(vec![data_msg("a")]);
+        let mut small = data_msg("b");
+        small.payload = Some(b"b".to_vec());
+        let res = add_messages_to_bounded_work_vec(
+            &work_vec,
+            vec![data_msg("c"), data_msg("d"), small],
+            &config,
+        );
+        // "d" does not fit but the 2 byte "b" still does
+        assert_eq!(expect_added(res), (3, 1));
+        assert_eq!(payloads(&work_vec), vec!["a", "c", "b"]);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn records_enqueued_and_dropped_without_setting_queue_depth() {
+        use crate::metrics::kafka_metric_names::METRIC_MSGS_DROPPED;
+        use crate::metrics::kafka_metric_names::METRIC_MSGS_ENQUEUED;
+        use crate::metrics::kafka_metric_names::METRIC_QUEUE_DEPTH;
+        use crate::metrics::kafka_metrics_registry::global_metrics;
+
+        let mut config =
+            build_config(3, 0, KafkaQueueOverflowPolicy::DropNewest);
+        config.label = "add-msgs-metrics".to_string();
+        let work_vec = new_work_vec(vec![data_msg("a")]);
+        let res = add_messages_to_bounded_work_vec(
+            &work_vec,
+            vec![data_msg("b"), data_msg("c"), data_msg("d")],
+            &config,
+        );
+        assert_eq!(expect_added(res), (3, 1));
+        let pool = [("pool", config.label.as_str())];
+        assert_eq!(
+            global_metrics().counter_value(
+                METRIC_MSGS_ENQUEUED,
+                &[("pool", config.label.as_str()), ("topic", "t")]
+            ),
+            Some(2)
+        );
+        assert_eq!(
+            global_metrics().counter_value(METRIC_MSGS_DROPPED, &pool),
+            Some(1)
+        );
+        // a shard's length is not the queue depth so only the
+        // callers that see every shard set the gauge
+        assert_eq!(
+            global_metrics().gauge_value(METRIC_QUEUE_DEPTH, &pool),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn drop_newest_always_adds_control_and_transaction_messages() {
+        let config = build_config(3, 0, KafkaQueueOverflowPolicy::DropNewest);
+        let work_vec =
+            new_work_vec(vec![data_msg("a"), data_msg("b"), data_msg("c")]);
+        let (notifier, mut txn_future) = KafkaTransactionNotifier::new();
+        let txn_msg = KafkaPublishMessage::new_transaction(
+            KafkaTransaction::new(vec![data_msg("t")], Some(notifier)),
+        );
+        let res = add_messages_to_bounded_work_vec(
+            &work_vec,
+            vec![shutdown_msg(), txn_msg, data_msg("d")],
+            &config,
+        );
+        assert_eq!(expect_added(res), (5, 1));
+        let msg_types: Vec<KafkaPublishMessageType> = work_vec
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|msg| msg.msg_type.clone())
+            .collect();
+        assert_eq!(
+            msg_types,
+            vec![
+                KafkaPublishMessageType::Data,
+                KafkaPublishMessageType::Data,
+                KafkaPublishMessageType::Data,
+                KafkaPublishMessageType::Shutdown,
+                KafkaPublishMessageType::Transaction,
+            ]
+        );
+        // the queued transaction is still waiting on its outcome
+        assert!(is_pending(&mut txn_future).await);
+    }
+
+    #[tokio::test]
+    async fn drop_message_fails_the_transaction() {
+        let (notifier, txn_future) = KafkaTransactionNotifier::new();
+        let (txn_data, txn_data_delivery) = tracked_msg("t");
+        drop_message(KafkaPublishMessage::new_transaction(
+            KafkaTransaction::new(vec![txn_data], Some(notifier)),
+        ));
+        assert_eq!(txn_future.await, Err(KafkaDeliveryError::Dropped));
+        assert_eq!(txn_data_delivery.await, Err(KafkaDeliveryError::Dropped));
+    }
+
+    async fn is_pending<F: std::future::Future + Unpin>(fut: &mut F) -> bool {
+        tokio::time::timeout(std::time::Duration::from_millis(10), fut)
+            .await
+            .is_err()
+    }
+}
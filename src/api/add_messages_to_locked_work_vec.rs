@@ -6,6 +6,7 @@ use std::sync::Mutex;
 use log::error;
 
 use crate::api::kafka_publish_message::KafkaPublishMessage;
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
 
 /// add_messages_to_locked_work_vec
 ///
@@ -14,12 +15,13 @@ use crate::api::kafka_publish_message::KafkaPublishMessage;
 ///
 /// # Returns
 ///
-/// ``Result<usize, String>``
+/// ``Result<usize, KafkaThreadpoolError>``
 ///
 /// where:
 ///
 /// Ok - ``Ok(num_messages_in_lockable_work_vec_after_append)``
-/// Error - ``Err(reason_for_error_as_string)``
+/// Error - ``Err(KafkaThreadpoolError::NoMessages)`` or
+/// ``Err(KafkaThreadpoolError::LockPoisoned)``
 ///
 /// # Arguments
 ///
@@ -32,12 +34,12 @@ use crate::api::kafka_publish_message::KafkaPublishMessage;
 pub fn add_messages_to_locked_work_vec(
     lockable_work_vec: &Arc<Mutex<Vec<KafkaPublishMessage>>>,
     mut msgs: Vec<KafkaPublishMessage>,
-) -> Result<usize, String> {
+) -> Result<usize, KafkaThreadpoolError> {
     let num_to_add = msgs.len();
     if num_to_add == 0 {
-        let err_msg = "no msgs to add";
-        error!("{err_msg}");
-        Err(err_msg.to_string())
+        let err = KafkaThreadpoolError::NoMessages;
+        error!("{err}");
+        Err(err)
     } else {
         // CRITICAL SECTION - start - lock the mutex
         match lockable_work_vec.lock() {
@@ -47,10 +49,9 @@ pub fn add_messages_to_locked_work_vec(
                 Ok(local_access_to_work_vec.len())
             }
             Err(e) => {
-                let err_msg =
-                    format!("failed to get lock on work vec with err={e}");
-                error!("{err_msg}");
-                Err(err_msg)
+                let err = KafkaThreadpoolError::LockPoisoned(e.to_string());
+                error!("{err}");
+                Err(err)
             }
         }
         // CRITICAL SECTION - start - unlock the mutex
@@ -9,15 +9,49 @@
 //! | KAFKA_LOG_LABEL                  | tracking label that shows up in all crate logs |
 //! | KAFKA_BROKERS                    | comma-delimited list of brokers (``host1:port,host2:port,host3:port``) |
 //! | KAFKA_TOPICS                     | comma-delimited list of supported topics |
-//! | KAFKA_PUBLISH_RETRY_INTERVAL_SEC | number of seconds to sleep before each publish retry |
-//! | KAFKA_PUBLISH_IDLE_INTERVAL_SEC  | number of seconds to sleep if there are no message to process |
+//! | KAFKA_ENSURE_TOPICS              | optional - set to ``true`` or ``1`` to create any missing ``KAFKA_TOPICS`` (and declared ``KafkaTopicSpec`` topics) when the threadpool starts (defaults to ``false``) |
+//! | KAFKA_TOPIC_NUM_PARTITIONS       | optional - number of partitions for created topics without a ``KafkaTopicSpec`` (defaults to ``-1`` which uses the broker default) |
+//! | KAFKA_TOPIC_REPLICATION_FACTOR   | optional - replication factor for created topics without a ``KafkaTopicSpec`` (defaults to ``-1`` which uses the broker default) |
+//! | KAFKA_ADMIN_TIMEOUT_SEC          | optional - max number of seconds to wait for each topic admin operation (defaults to ``30``) |
+//! | KAFKA_PUBLISH_RETRY_INTERVAL_SEC | number of seconds to sleep before the first publish retry (the backoff grows with each retry) |
+//! | KAFKA_PUBLISH_RETRY_MAX_INTERVAL_SEC | optional - max number of seconds to sleep between publish retries (defaults to ``30``) |
+//! | KAFKA_PUBLISH_RETRY_MULTIPLIER   | optional - backoff growth factor applied after each publish retry (defaults to ``2.0``) |
+//! | KAFKA_PUBLISH_RETRY_JITTER       | optional - fraction between ``0.0`` and ``1.0`` of random variation applied to each backoff (defaults to ``0.2``) |
+//! | KAFKA_PUBLISH_RETRY_MAX_ATTEMPTS | optional - max number of publish attempts per message before giving up (defaults to ``10`` and ``0`` retries until the max elapsed time) |
+//! | KAFKA_PUBLISH_RETRY_MAX_ELAPSED_SEC | optional - max number of seconds spent publishing a message before giving up (defaults to ``0`` which is unbounded) |
+//! | KAFKA_DLQ_TOPIC                  | optional - dead-letter topic for messages that fail with a fatal error or exhaust the retry policy (the original topic, error and attempt count are added as headers) |
+//! | KAFKA_DLQ_FILE                   | optional - append-only file for messages that fail with a fatal error or exhaust the retry policy (``Sensitive`` keys, headers and payloads are redacted) |
+//! | KAFKA_PUBLISH_IDLE_INTERVAL_SEC  | max number of seconds an idle thread waits before rechecking the work vec (threads wake immediately when messages are added) |
 //! | KAFKA_NUM_THREADS                | number of threads for the threadpool |
+//! | KAFKA_PUBLISH_BATCH_SIZE         | optional - max number of messages each thread drains from the work vec and publishes concurrently (defaults to ``10``) |
 //! | KAFKA_TLS_CLIENT_KEY             | optional - path to the kafka mTLS key |
 //! | KAFKA_TLS_CLIENT_CERT            | optional - path to the kafka mTLS certificate |
 //! | KAFKA_TLS_CLIENT_CA              | optional - path to the kafka mTLS certificate authority (CA) |
+//! | KAFKA_SECURITY_PROTOCOL          | optional - ``PLAINTEXT``, ``SSL``, ``SASL_PLAINTEXT`` or ``SASL_SSL`` (defaults to ``SSL`` if any tls asset is set otherwise ``PLAINTEXT``) |
+//! | KAFKA_SASL_MECHANISM             | optional - ``PLAIN``, ``SCRAM-SHA-256``, ``SCRAM-SHA-512`` or ``OAUTHBEARER`` for the ``SASL_*`` security protocols (``OAUTHBEARER`` without a ``KafkaOAuthTokenProvider`` uses ``librdkafka``'s own tokens, e.g. ``KAFKA_RDKAFKA_SASL_OAUTHBEARER_METHOD=oidc``) |
+//! | KAFKA_SASL_USERNAME              | optional - username for the ``PLAIN`` and ``SCRAM`` SASL mechanisms |
+//! | KAFKA_SASL_PASSWORD              | optional - password for the ``PLAIN`` and ``SCRAM`` SASL mechanisms |
+//! | KAFKA_SASL_OAUTHBEARER_CONFIG    | optional - ``sasl.oauthbearer.config`` passed to the ``OAUTHBEARER`` token provider or ``librdkafka``'s unsecured JWT builder |
 //! | KAFKA_METADATA_COUNT_MSG_OFFSETS | optional - set to anything but ``true`` to bypass counting the offsets |
+//! | KAFKA_METADATA_TIMEOUT_SEC       | optional - max number of seconds to wait for the cluster metadata (defaults to ``30``) |
+//! | KAFKA_METADATA_WATERMARK_TIMEOUT_SEC | optional - max number of seconds to wait for each partition's watermarks when counting messages (defaults to ``1``) |
+//! | KAFKA_METADATA_WATERMARK_CONCURRENCY | optional - max number of partition watermarks fetched at the same time when counting messages (defaults to ``1``) |
+//! | KAFKA_QUEUE_MAX_MSGS             | optional - max number of messages in the work vec (``0`` is unbounded, split evenly across the worker threads' work vecs with the ``key`` and ``topic_partition`` ordering modes) |
+//! | KAFKA_QUEUE_MAX_BYTES            | optional - max number of bytes in the work vec (``0`` is unbounded, split evenly across the worker threads' work vecs with the ``key`` and ``topic_partition`` ordering modes) |
+//! | KAFKA_QUEUE_OVERFLOW_POLICY      | optional - ``block``, ``reject``, ``drop_oldest`` or ``drop_newest`` when the work vec is full |
+//! | KAFKA_ORDERING_MODE              | optional - ``unordered``, ``key`` or ``topic_partition`` where ``key`` and ``topic_partition`` shard messages to per-worker work vecs so each key (or partition) is published in enqueue order (a transaction follows its first keyed message) (defaults to ``unordered``) |
+//! | KAFKA_ENABLE_IDEMPOTENCE         | optional - set to ``true`` or ``1`` to start each producer with ``enable.idempotence=true`` so retries cannot duplicate or reorder messages (defaults to ``false``) |
+//! | KAFKA_TRANSACTIONAL_ID_PREFIX    | optional - enables the transactional mode for ``KafkaPublisher::add_transaction_msgs`` where each thread uses the unique ``transactional.id``: ``<prefix>-<thread number>`` (implies idempotence) |
+//! | KAFKA_STATISTICS_INTERVAL_MS     | optional - how often in milliseconds ``librdkafka`` emits statistics for each worker thread's producer that are available with ``KafkaPublisher::stats()`` (defaults to ``0`` which disables statistics) |
+//! | KAFKA_HEALTH_DEGRADED_QUEUE_DEPTH | optional - number of queued messages that makes ``KafkaPublisher::health()`` report ``Degraded`` (defaults to ``0`` which disables the check) |
+//! | KAFKA_HEALTH_UNHEALTHY_QUEUE_DEPTH | optional - number of queued messages that makes ``KafkaPublisher::health()`` report ``Unhealthy`` (defaults to ``0`` which disables the check) |
+//! | KAFKA_HEALTH_DEGRADED_DELIVERY_SEC | optional - seconds without a successful delivery while there are messages to publish that makes ``KafkaPublisher::health()`` report ``Degraded`` (defaults to ``60``, ``0`` disables the check) |
+//! | KAFKA_HEALTH_UNHEALTHY_DELIVERY_SEC | optional - seconds without a successful delivery while there are messages to publish that makes ``KafkaPublisher::health()`` report ``Unhealthy`` (defaults to ``300``, ``0`` disables the check) |
+//! | KAFKA_HEALTH_BROKER_DOWN_SEC     | optional - seconds an all brokers down event from ``librdkafka`` counts in ``KafkaPublisher::health()`` without another connection failure (defaults to ``60``) |
+//! | KAFKA_RDKAFKA_<PROP>             | optional - pass-through ``librdkafka`` property for the producer and consumer where ``<PROP>`` is lowercased and ``_`` becomes ``.`` (``KAFKA_RDKAFKA_LINGER_MS=5`` sets ``linger.ms=5`` and ``KAFKA_RDKAFKA_ENABLE_IDEMPOTENCE`` works like ``KAFKA_ENABLE_IDEMPOTENCE``) |
 //!
 
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
 use crate::config::kafka_client_config::KafkaClientConfig;
 
 /// build_kafka_client_config
@@ -28,13 +62,20 @@ use crate::config::kafka_client_config::KafkaClientConfig;
 ///
 /// * `label` - tracking label for logs
 ///
+/// # Errors
+///
+/// [`KafkaThreadpoolError`] if an environment variable is invalid
+///
 /// # Examples
 ///
 /// ```rust
 /// use kafka_threadpool::config::kafka_client_config::build_kafka_client_config;
-/// let kafka_config: KafkaClientConfig = build_kafka_client_config("ktp")
+/// let kafka_config: KafkaClientConfig =
+///     build_kafka_client_config("ktp").unwrap();
 /// ```
 ///
-pub fn build_kafka_client_config(label: &str) -> KafkaClientConfig {
+pub fn build_kafka_client_config(
+    label: &str,
+) -> Result<KafkaClientConfig, KafkaThreadpoolError> {
     KafkaClientConfig::new(label)
 }
@@ -16,7 +16,8 @@ use crate::api::kafka_publish_message_type::KafkaPublishMessageType;
 ///
 /// * `msg_type` - request type of message [`KafkaPublishMessageType`]
 /// * `topic` - kafka topic to publish the message into
-/// * `key` - kafka partition key
+/// * `key` - kafka partition key (an empty ``key`` publishes
+/// a null key)
 /// * `headers` - optional - headers for the kafka message
 /// * `payload` - data within the kafka message
 ///
@@ -0,0 +1,297 @@
+//! Build the [`rdkafka::config::ClientConfig`](rdkafka::config::ClientConfig)
+//! shared by the producer and consumer from a
+//! [`KafkaClientConfig`](crate::config::kafka_client_config::KafkaClientConfig)
+//!
+//! The ``security.protocol`` comes from ``config.security_protocol``.
+//! ``PLAINTEXT`` and ``SASL_PLAINTEXT`` mean no encryption in transit
+//! (aka - this is not safe to use with kafka connections that go over the WAN / internet).
+//!
+//! Any ``config.rdkafka_properties`` are applied last so callers can
+//! tune ``librdkafka`` (``linger.ms``, ``compression.type``, ``acks``,
+//! etc.) except for the
+//! [`MANAGED_RDKAFKA_PROPERTIES`](crate::api::build_rdkafka_client_config::MANAGED_RDKAFKA_PROPERTIES)
+//! that the ``kafka_threadpool`` sets itself.
+//!
+use log::info;
+
+use rdkafka::config::ClientConfig;
+
+use crate::api::kafka_client_context::KafkaClientContext;
+use crate::api::kafka_security_protocol::KafkaSaslMechanism;
+use crate::config::kafka_client_config::KafkaClientConfig;
+use crate::pool::kafka_pool_state::KafkaPoolState;
+
+/// MANAGED_RDKAFKA_PROPERTIES
+///
+/// ``librdkafka`` properties derived from the
+/// [`KafkaClientConfig`](crate::config::kafka_client_config::KafkaClientConfig)
+/// that cannot be overridden with ``config.rdkafka_properties``
+/// (the builder maps a pass-through ``enable.idempotence`` onto
+/// ``config.enable_idempotence`` instead)
+///
+pub const MANAGED_RDKAFKA_PROPERTIES: &[&str] = &[
+    "bootstrap.servers",
+    "metadata.broker.list",
+    "security.protocol",
+    "ssl.ca.location",
+    "ssl.key.location",
+    "ssl.certificate.location",
+    "enable.ssl.certificate.verification",
+    "sasl.mechanism",
+    "sasl.mechanisms",
+    "sasl.username",
+    "sasl.password",
+    "sasl.oauthbearer.config",
+    "enable.idempotence",
+    "transactional.id",
+    "statistics.interval.ms",
+];
+
+/// is_managed_rdkafka_property
+///
+/// Check if a ``librdkafka`` property is managed by the
+/// ``kafka_threadpool``
+///
+/// # Arguments
+///
+/// * `key` - ``librdkafka`` property name
+///
+pub fn is_managed_rdkafka_property(key: &str) -> bool {
+    MANAGED_RDKAFKA_PROPERTIES.contains(&key)
+}
+
+/// build_rdkafka_client_config
+///
+/// # Returns
+///
+/// An [`rdkafka::config::ClientConfig`](rdkafka::config::ClientConfig)
+/// with the connectivity, security and pass-through properties
+///
+/// # Arguments
+///
+/// * `config` - existing [`KafkaClientConfig`] for
+/// configurable static connectivity values
+///
+pub fn build_rdkafka_client_config(config: &KafkaClientConfig) -> ClientConfig {
+    let mut client_config = ClientConfig::new();
+    client_config
+        .set("bootstrap.servers", config.broker_list.join(","))
+        .set(
+            "security.protocol",
+            config.security_protocol.as_rdkafka_str(),
+        );
+    if !config.security_protocol.uses_ssl() {
+        info!("connecting with {}", config.security_protocol);
+    } else {
+        client_config.set("enable.ssl.certificate.verification", "true");
+        if !config.tls_ca.is_empty() {
+            client_config.set("ssl.ca.location", config.tls_ca.clone());
+        }
+        if !config.tls_key.is_empty() {
+            client_config.set("ssl.key.location", config.tls_key.clone());
+        }
+        if !config.tls_cert.is_empty() {
+            client_config
+                .set("ssl.certificate.location", config.tls_cert.clone());
+        }
+    }
+    if config.security_protocol.uses_sasl() {
+        if let Some(sasl_mechanism) = &config.sasl_mechanism {
+            client_config
+                .set("sasl.mechanism", sasl_mechanism.as_rdkafka_str());
+            if *sasl_mechanism == KafkaSaslMechanism::OAuthBearer {
+                if !config.sasl_oauthbearer_config.is_empty() {
+                    client_config.set(
+                        "sasl.oauthbearer.config",
+                        config.sasl_oauthbearer_config.clone(),
+                    );
+                }
+            } else {
+                client_config
+                    .set("sasl.username", config.sasl_username.clone())
+                    .set("sasl.password", config.sasl_password.clone());
+            }
+        }
+    }
+    for (key, value) in config.rdkafka_properties.iter() {
+        client_config.set(key, value);
+    }
+    client_config
+}
+
+/// build_rdkafka_producer_config
+///
+/// # Returns
+///
+/// The [`build_rdkafka_client_config`] settings plus the settings
+/// every ``kafka_threadpool`` producer shares:
+///
+/// - ``message.timeout.ms`` defaults to ``5000`` and can be changed
+/// with ``config.rdkafka_properties``
+/// - ``statistics.interval.ms`` is set from
+/// ``config.statistics_interval_ms`` when it is positive
+///
+/// # Arguments
+///
+/// * `config` - existing [`KafkaClientConfig`] for
+/// configurable static connectivity values
+///
+pub fn build_rdkafka_producer_config(
+    config: &KafkaClientConfig,
+) -> ClientConfig {
+    let mut client_config = build_rdkafka_client_config(config);
+    if !config.rdkafka_properties.contains_key("message.timeout.ms") {
+        client_config.set("message.timeout.ms", "5000");
+    }
+    if config.statistics_interval_ms > 0 {
+        client_config.set(
+            "statistics.interval.ms",
+            config.statistics_interval_ms.to_string(),
+        );
+    }
+    client_config
+}
+
+/// build_kafka_worker_context
+///
+/// # Returns
+///
+/// The [`KafkaClientContext`] for a worker thread's producer that
+/// also stores the worker's ``librdkafka`` statistics in the pool's
+/// [`KafkaStatsStore`](crate::stats::kafka_stats_store::KafkaStatsStore),
+/// passes them to the optional
+/// [`KafkaStatsHandler`](crate::stats::kafka_stats_handler::KafkaStatsHandler)
+/// and records broker connectivity in the pool's
+/// [`KafkaHealthTracker`](crate::health::kafka_health_tracker::KafkaHealthTracker)
+///
+/// # Arguments
+///
+/// * `config` - existing [`KafkaClientConfig`] for
+/// configurable static connectivity values
+/// * `worker_label` - producer log label the statistics are stored
+/// under
+/// * `health_label` - worker thread log label the broker
+/// connectivity is recorded under
+/// * `pool_state` - shared [`KafkaPoolState`] for the threadpool
+///
+pub fn build_kafka_worker_context(
+    config: &KafkaClientConfig,
+    worker_label: &str,
+    health_label: &str,
+    pool_state: &KafkaPoolState,
+) -> KafkaClientContext {
+    KafkaClientContext {
+        worker_label: worker_label.to_string(),
+        stats_store: Some(pool_state.stats.clone()),
+        stats_handler: config.stats_handler.clone(),
+        health_label: health_label.to_string(),
+        health_tracker: Some(pool_state.health.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::kafka_security_protocol::KafkaSecurityProtocol;
+
+    fn build_config() -> KafkaClientConfig {
+        let mut config = KafkaClientConfig::default();
+        config.broker_list =
+            vec!["host1:9092".to_string(), "host2:9092".to_string()];
+        config
+    }
+
+    #[test]
+    fn plaintext_sets_the_brokers_and_protocol() {
+        let client_config = build_rdkafka_client_config(&build_config());
+        assert_eq!(
+            client_config.get("bootstrap.servers"),
+            Some("host1:9092,host2:9092")
+        );
+        assert_eq!(client_config.get("security.protocol"), Some("PLAINTEXT"));
+        assert_eq!(client_config.get("ssl.ca.location"), None);
+        assert_eq!(client_config.get("sasl.mechanism"), None);
+    }
+
+    #[test]
+    fn ssl_sets_the_tls_assets() {
+        let mut config = build_config();
+        config.security_protocol = KafkaSecurityProtocol::Ssl;
+        config.tls_ca = "/certs/ca.pem".to_string();
+        config.tls_key = "/certs/client.key".to_string();
+        let client_config = build_rdkafka_client_config(&config);
+        assert_eq!(client_config.get("security.protocol"), Some("SSL"));
+        assert_eq!(
+            client_config.get("enable.ssl.certificate.verification"),
+            Some("true")
+        );
+        assert_eq!(client_config.get("ssl.ca.location"), Some("/certs/ca.pem"));
+        assert_eq!(
+            client_config.get("ssl.key.location"),
+            Some("/certs/client.key")
+        );
+        // unset tls assets are not passed to librdkafka
+        assert_eq!(client_config.get("ssl.certificate.location"), None);
+    }
+
+    #[test]
+    fn sasl_sets_the_mechanism_and_credentials() {
+        let mut config = build_config();
+        config.security_protocol = KafkaSecurityProtocol::SaslSsl;
+        config.sasl_mechanism = Some(KafkaSaslMechanism::ScramSha512);
+        config.sasl_username = "user".to_string();
+        config.sasl_password = "secret".to_string();
+        let client_config = build_rdkafka_client_config(&config);
+        assert_eq!(client_config.get("security.protocol"), Some("SASL_SSL"));
+        assert_eq!(client_config.get("sasl.mechanism"), Some("SCRAM-SHA-512"));
+        assert_eq!(client_config.get("sasl.username"), Some("user"));
+        assert_eq!(client_config.get("sasl.password"), Some("secret"));
+
+        config.sasl_mechanism = Some(KafkaSaslMechanism::OAuthBearer);
+        config.sasl_oauthbearer_config = "principal=svc".to_string();
+        let client_config = build_rdkafka_client_config(&config);
+        assert_eq!(client_config.get("sasl.mechanism"), Some("OAUTHBEARER"));
+        assert_eq!(
+            client_config.get("sasl.oauthbearer.config"),
+            Some("principal=svc")
+        );
+        assert_eq!(client_config.get("sasl.username"), None);
+    }
+
+    #[test]
+    fn pass_through_properties_are_applied() {
+        let mut config = build_config();
+        config
+            .rdkafka_properties
+            .insert("linger.ms".to_string(), "5".to_string());
+        config
+            .rdkafka_properties
+            .insert("compression.type".to_string(), "zstd".to_string());
+        let client_config = build_rdkafka_client_config(&config);
+        assert_eq!(client_config.get("linger.ms"), Some("5"));
+        assert_eq!(client_config.get("compression.type"), Some("zstd"));
+    }
+
+    #[test]
+    fn producer_config_defaults_the_message_timeout() {
+        let mut config = build_config();
+        let client_config = build_rdkafka_producer_config(&config);
+        assert_eq!(client_config.get("message.timeout.ms"), Some("5000"));
+        assert_eq!(client_config.get("statistics.interval.ms"), None);
+
+        config
+            .rdkafka_properties
+            .insert("message.timeout.ms".to_string(), "30000".to_string());
+        config.statistics_interval_ms = 1000;
+        let client_config = build_rdkafka_producer_config(&config);
+        assert_eq!(client_config.get("message.timeout.ms"), Some("30000"));
+        assert_eq!(client_config.get("statistics.interval.ms"), Some("1000"));
+    }
+
+    #[test]
+    fn managed_properties() {
+        assert!(is_managed_rdkafka_property("bootstrap.servers"));
+        assert!(is_managed_rdkafka_property("transactional.id"));
+        assert!(!is_managed_rdkafka_property("linger.ms"));
+    }
+}
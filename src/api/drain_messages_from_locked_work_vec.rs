@@ -14,7 +14,8 @@ use crate::api::kafka_publish_message::KafkaPublishMessage;
 ///
 /// # Returns
 ///
-/// All messages are drained from the ``lockable_work_vec`` and returned in a:
+/// Up to ``max_msgs`` messages are drained from the
+/// ``lockable_work_vec`` and returned in a:
 /// ``Vec<KafkaPublishMessage>``
 ///
 /// # Arguments
@@ -22,17 +23,20 @@ use crate::api::kafka_publish_message::KafkaPublishMessage;
 /// * `lockable_work_vec` - shared work vec of
 /// [`KafkaPublishMessage`] messages to process within a lockable
 /// [`Arc<Mutex<lockable_work_vec>>`] thread-safe object
+/// * `max_msgs` - max number of messages to drain (``0`` drains
+/// all messages)
 ///
 pub fn drain_messages_from_locked_work_vec(
     lockable_work_vec: &Arc<Mutex<Vec<KafkaPublishMessage>>>,
+    max_msgs: usize,
 ) -> Vec<KafkaPublishMessage> {
     // CRITICAL SECTION - start - lock the mutex
     match lockable_work_vec.lock() {
         Ok(mut local_access_to_work_vec) => {
             // drain messages while locked
             let num_msgs = local_access_to_work_vec.len();
-            if num_msgs > 10 {
-                local_access_to_work_vec.drain(0..10).collect()
+            if max_msgs > 0 && num_msgs > max_msgs {
+                local_access_to_work_vec.drain(0..max_msgs).collect()
             } else {
                 local_access_to_work_vec.drain(0..num_msgs).collect()
             }
@@ -44,3 +48,72 @@ pub fn drain_messages_from_locked_work_vec(
     }
     // CRITICAL SECTION - start - unlock the mutex
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::kafka_publish_message_type::KafkaPublishMessageType;
+
+    fn new_work_vec(num_msgs: usize) -> Arc<Mutex<Vec<KafkaPublishMessage>>> {
+        Arc::new(Mutex::new(
+            (0..num_msgs)
+                .map(|idx| {
+                    KafkaPublishMessage::new_from_bytes(
+                        KafkaPublishMessageType::Data,
+                        "orders",
+                        None,
+                        Vec::new(),
+                        Some(idx.to_string().into_bytes()),
+                    )
+                })
+                .collect(),
+        ))
+    }
+
+    fn payloads(msgs: &[KafkaPublishMessage]) -> Vec<String> {
+        msgs.iter()
+            .map(|msg| msg.payload_as_string().unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn drains_batches_of_max_msgs_in_order() {
+        let work_vec = new_work_vec(5);
+        let batch = drain_messages_from_locked_work_vec(&work_vec, 2);
+        assert_eq!(payloads(&batch), vec!["0", "1"]);
+        let batch = drain_messages_from_locked_work_vec(&work_vec, 2);
+        assert_eq!(payloads(&batch), vec!["2", "3"]);
+        // the last batch is smaller than max_msgs
+        let batch = drain_messages_from_locked_work_vec(&work_vec, 2);
+        assert_eq!(payloads(&batch), vec!["4"]);
+        assert!(drain_messages_from_locked_work_vec(&work_vec, 2).is_empty());
+    }
+
+    #[test]
+    fn drains_every_message_with_max_msgs_zero() {
+        let work_vec = new_work_vec(3);
+        let batch = drain_messages_from_locked_work_vec(&work_vec, 0);
+        assert_eq!(payloads(&batch), vec!["0", "1", "2"]);
+        assert!(work_vec.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn drains_exactly_max_msgs() {
+        let work_vec = new_work_vec(3);
+        let batch = drain_messages_from_locked_work_vec(&work_vec, 3);
+        assert_eq!(batch.len(), 3);
+        assert!(work_vec.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn poisoned_lock_drains_nothing() {
+        let work_vec = new_work_vec(2);
+        let poisoned = work_vec.clone();
+        let _ = std::thread::spawn(move || {
+            let _locked = poisoned.lock().unwrap();
+            panic!("poison the work vec lock");
+        })
+        .join();
+        assert!(drain_messages_from_locked_work_vec(&work_vec, 0).is_empty());
+    }
+}
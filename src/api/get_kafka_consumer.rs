@@ -1,15 +1,16 @@
 //! Create a [`rdkafka::consumer::BaseConsumer`](rdkafka::consumer::BaseConsumer) from
 //! a [`KafkaClientConfig`](crate::config::kafka_client_config::KafkaClientConfig)
 //!
-//! If the tls CA, key and cert are not set, then the consumer will use ``PLAINTEXT`` instead
-//! of SSL. ``PLAINTEXT`` means no encryption in transit
+//! The consumer uses the ``security.protocol`` from ``config.security_protocol``.
+//! ``PLAINTEXT`` means no encryption in transit
 //! (aka - this is not safe to use with kafka connections that go over the WAN / internet).
 //!
-use log::info;
-
-use rdkafka::config::ClientConfig;
 use rdkafka::consumer::BaseConsumer;
+use rdkafka::consumer::ConsumerContext;
 
+use crate::api::build_rdkafka_client_config::build_rdkafka_client_config;
+use crate::api::kafka_client_context::KafkaClientContext;
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
 use crate::config::kafka_client_config::KafkaClientConfig;
 
 /// get_kafka_consumer
@@ -17,32 +18,60 @@ use crate::config::kafka_client_config::KafkaClientConfig;
 /// # Returns
 ///
 /// An intialized: [`rdkafka::consumer::BaseConsumer`](rdkafka::consumer::BaseConsumer)
+/// or a [`KafkaThreadpoolError::Broker`] if ``rdkafka`` fails to
+/// create the consumer
+///
+/// The consumer leaves ``OAUTHBEARER`` to ``librdkafka``. Use
+/// [`get_kafka_consumer_with_context`] with a
+/// [`KafkaOAuthClientContext`](crate::api::kafka_oauth_client_context::KafkaOAuthClientContext)
+/// for a ``config.oauth_token_provider``.
 ///
 /// # Arguments
 ///
 /// * `config` - existing [`KafkaClientConfig`] for
 /// configurable static connectivity values
 ///
-pub fn get_kafka_consumer(config: &KafkaClientConfig) -> BaseConsumer {
-    if config.tls_key.is_empty()
-        && config.tls_cert.is_empty()
-        && config.tls_ca.is_empty()
-    {
-        info!("connecting with PLAINTEXT");
-        ClientConfig::new()
-            .set("bootstrap.servers", config.broker_list.join(","))
-            .set("security.protocol", "PLAINTEXT")
-            .create()
-            .expect("Consumer creation error")
-    } else {
-        ClientConfig::new()
-            .set("bootstrap.servers", config.broker_list.join(","))
-            .set("security.protocol", "SSL")
-            .set("ssl.ca.location", config.tls_ca.clone())
-            .set("ssl.key.location", config.tls_key.clone())
-            .set("ssl.certificate.location", config.tls_cert.clone())
-            .set("enable.ssl.certificate.verification", "true")
-            .create()
-            .expect("Consumer creation error")
+/// # Errors
+///
+/// [`KafkaThreadpoolError::ConfigInvalid`] if
+/// ``config.oauth_token_provider`` is set
+///
+pub fn get_kafka_consumer(
+    config: &KafkaClientConfig,
+) -> Result<BaseConsumer<KafkaClientContext>, KafkaThreadpoolError> {
+    if config.oauth_token_provider.is_some() {
+        return Err(KafkaThreadpoolError::config_invalid(
+            "KAFKA_SASL_MECHANISM",
+            "a KafkaOAuthTokenProvider needs \
+            get_kafka_consumer_with_context with a KafkaOAuthClientContext",
+        ));
     }
+    get_kafka_consumer_with_context(config, KafkaClientContext::default())
+}
+
+/// get_kafka_consumer_with_context
+///
+/// Same as [`get_kafka_consumer`] with a caller-built
+/// [`KafkaClientContext`] or
+/// [`KafkaOAuthClientContext`](crate::api::kafka_oauth_client_context::KafkaOAuthClientContext)
+///
+/// # Returns
+///
+/// An intialized: [`rdkafka::consumer::BaseConsumer`](rdkafka::consumer::BaseConsumer)
+/// or a [`KafkaThreadpoolError::Broker`] if ``rdkafka`` fails to
+/// create the consumer
+///
+/// # Arguments
+///
+/// * `config` - existing [`KafkaClientConfig`] for
+/// configurable static connectivity values
+/// * `context` - client context for the consumer
+///
+pub fn get_kafka_consumer_with_context<C: ConsumerContext + 'static>(
+    config: &KafkaClientConfig,
+    context: C,
+) -> Result<BaseConsumer<C>, KafkaThreadpoolError> {
+    build_rdkafka_client_config(config)
+        .create_with_context(context)
+        .map_err(KafkaThreadpoolError::from)
 }
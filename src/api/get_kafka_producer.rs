@@ -1,15 +1,16 @@
 //! Create a [`rdkafka::producer::FutureProducer`](rdkafka::producer::FutureProducer) from
 //! a [`KafkaClientConfig`](crate::config::kafka_client_config::KafkaClientConfig)
 //!
-//! If the tls CA, key and cert are not set, then the producer will use ``PLAINTEXT`` instead
-//! of SSL. ``PLAINTEXT`` means no encryption in transit
+//! The producer uses the ``security.protocol`` from ``config.security_protocol``.
+//! ``PLAINTEXT`` means no encryption in transit
 //! (aka - this is not safe to use with kafka connections that go over the WAN / internet).
 //!
-use log::info;
-
-use rdkafka::config::ClientConfig;
+use rdkafka::client::ClientContext;
 use rdkafka::producer::FutureProducer;
 
+use crate::api::build_rdkafka_client_config::build_rdkafka_producer_config;
+use crate::api::kafka_client_context::KafkaClientContext;
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
 use crate::config::kafka_client_config::KafkaClientConfig;
 
 /// get_kafka_producer
@@ -17,34 +18,74 @@ use crate::config::kafka_client_config::KafkaClientConfig;
 /// # Returns
 ///
 /// An intialized: [`rdkafka::producer::FutureProducer`](rdkafka::producer::FutureProducer)
+/// or a [`KafkaThreadpoolError::Broker`] if ``rdkafka`` fails to
+/// create the producer
+///
+/// ``message.timeout.ms`` defaults to ``5000`` and can be changed
+/// with ``config.rdkafka_properties``
+///
+/// ``enable.idempotence`` is set when ``config.enable_idempotence``
+/// is ``true`` or the transactional mode is enabled
+///
+/// The producer leaves ``OAUTHBEARER`` to ``librdkafka``. Use
+/// [`get_kafka_producer_with_context`] with a
+/// [`KafkaOAuthClientContext`](crate::api::kafka_oauth_client_context::KafkaOAuthClientContext)
+/// for a ``config.oauth_token_provider``.
+///
+/// # Arguments
+///
+/// * `config` - existing [`KafkaClientConfig`] for
+/// configurable static connectivity values
+///
+/// # Errors
+///
+/// [`KafkaThreadpoolError::ConfigInvalid`] if
+/// ``config.oauth_token_provider`` is set
+///
+pub fn get_kafka_producer(
+    config: &KafkaClientConfig,
+) -> Result<FutureProducer<KafkaClientContext>, KafkaThreadpoolError> {
+    if config.oauth_token_provider.is_some() {
+        return Err(KafkaThreadpoolError::config_invalid(
+            "KAFKA_SASL_MECHANISM",
+            "a KafkaOAuthTokenProvider needs \
+            get_kafka_producer_with_context with a KafkaOAuthClientContext",
+        ));
+    }
+    get_kafka_producer_with_context(config, KafkaClientContext::default())
+}
+
+/// get_kafka_producer_with_context
+///
+/// Same as [`get_kafka_producer`] with a caller-built
+/// [`KafkaClientContext`] (the worker threads use this to collect
+/// per-worker statistics) or
+/// [`KafkaOAuthClientContext`](crate::api::kafka_oauth_client_context::KafkaOAuthClientContext)
+///
+/// ``statistics.interval.ms`` is set from
+/// ``config.statistics_interval_ms`` when it is positive
+///
+/// # Returns
+///
+/// An intialized: [`rdkafka::producer::FutureProducer`](rdkafka::producer::FutureProducer)
+/// or a [`KafkaThreadpoolError::Broker`] if ``rdkafka`` fails to
+/// create the producer
 ///
 /// # Arguments
 ///
 /// * `config` - existing [`KafkaClientConfig`] for
 /// configurable static connectivity values
+/// * `context` - client context for the producer
 ///
-pub fn get_kafka_producer(config: &KafkaClientConfig) -> FutureProducer {
-    if config.tls_key.is_empty()
-        && config.tls_cert.is_empty()
-        && config.tls_ca.is_empty()
-    {
-        info!("connecting with PLAINTEXT");
-        ClientConfig::new()
-            .set("bootstrap.servers", config.broker_list.join(","))
-            .set("message.timeout.ms", "5000")
-            .set("security.protocol", "PLAINTEXT")
-            .create()
-            .expect("Producer creation error")
-    } else {
-        ClientConfig::new()
-            .set("bootstrap.servers", config.broker_list.join(","))
-            .set("message.timeout.ms", "5000")
-            .set("security.protocol", "SSL")
-            .set("ssl.ca.location", config.tls_ca.clone())
-            .set("ssl.key.location", config.tls_key.clone())
-            .set("ssl.certificate.location", config.tls_cert.clone())
-            .set("enable.ssl.certificate.verification", "true")
-            .create()
-            .expect("Producer creation error")
+pub fn get_kafka_producer_with_context<C: ClientContext + 'static>(
+    config: &KafkaClientConfig,
+    context: C,
+) -> Result<FutureProducer<C>, KafkaThreadpoolError> {
+    let mut client_config = build_rdkafka_producer_config(config);
+    if config.enable_idempotence || !config.transactional_id_prefix.is_empty() {
+        client_config.set("enable.idempotence", "true");
     }
+    client_config
+        .create_with_context(context)
+        .map_err(KafkaThreadpoolError::from)
 }
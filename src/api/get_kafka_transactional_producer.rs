@@ -0,0 +1,87 @@
+//! Create a transactional
+//! [`rdkafka::producer::FutureProducer`](rdkafka::producer::FutureProducer)
+//! for a single worker thread from a
+//! [`KafkaClientConfig`](crate::config::kafka_client_config::KafkaClientConfig)
+//!
+//! Each worker thread needs a unique ``transactional.id`` so kafka
+//! can fence a zombie producer that reuses the same id after a
+//! restart.
+//!
+use std::time::Duration;
+
+use rdkafka::client::ClientContext;
+use rdkafka::producer::FutureProducer;
+use rdkafka::producer::Producer;
+use rdkafka::util::Timeout;
+
+use crate::api::build_rdkafka_client_config::build_rdkafka_producer_config;
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
+use crate::config::kafka_client_config::KafkaClientConfig;
+
+/// get_transactional_id
+///
+/// # Returns
+///
+/// The ``transactional.id`` for a worker thread:
+/// ``<config.transactional_id_prefix>-<cur_thread_num>``
+///
+/// # Arguments
+///
+/// * `config` - existing [`KafkaClientConfig`] with a
+/// ``transactional_id_prefix``
+/// * `cur_thread_num` - thread counter assigned by
+/// [`start_threads_from_config`](crate::pool::start_threads_from_config)
+///
+pub fn get_transactional_id(
+    config: &KafkaClientConfig,
+    cur_thread_num: u8,
+) -> String {
+    format!("{}-{}", config.transactional_id_prefix, cur_thread_num)
+}
+
+/// get_kafka_transactional_producer
+///
+/// Create an idempotent producer with a per-thread
+/// ``transactional.id`` and register it with the transaction
+/// coordinator with ``init_transactions``. This blocks for up to
+/// ``timeout`` so async callers should use
+/// ``tokio::task::spawn_blocking``.
+///
+/// # Returns
+///
+/// An intialized: [`rdkafka::producer::FutureProducer`](rdkafka::producer::FutureProducer)
+/// ready for ``begin_transaction`` or a
+/// [`KafkaThreadpoolError::Broker`] if ``rdkafka`` fails to
+/// create the producer or initialize the transactions
+///
+/// # Arguments
+///
+/// * `config` - existing [`KafkaClientConfig`] for
+/// configurable static connectivity values
+/// * `cur_thread_num` - thread counter assigned by
+/// [`start_threads_from_config`](crate::pool::start_threads_from_config)
+/// * `context` - client context for the producer
+/// ([`KafkaClientContext`](crate::api::kafka_client_context::KafkaClientContext)
+/// or
+/// [`KafkaOAuthClientContext`](crate::api::kafka_oauth_client_context::KafkaOAuthClientContext))
+/// * `timeout` - max time to wait for the transaction coordinator
+///
+pub fn get_kafka_transactional_producer<C: ClientContext + 'static>(
+    config: &KafkaClientConfig,
+    cur_thread_num: u8,
+    context: C,
+    timeout: Duration,
+) -> Result<FutureProducer<C>, KafkaThreadpoolError> {
+    let mut client_config = build_rdkafka_producer_config(config);
+    client_config.set("enable.idempotence", "true").set(
+        "transactional.id",
+        get_transactional_id(config, cur_thread_num),
+    );
+    let producer: FutureProducer<C> = client_config
+        .create_with_context(context)
+        .map_err(KafkaThreadpoolError::from)?;
+    producer
+        .init_transactions(Timeout::After(timeout))
+        .map_err(KafkaThreadpoolError::from)?;
+    Ok(producer)
+}
@@ -0,0 +1,266 @@
+//! Helper for assigning a message to a worker thread with the
+//! configured
+//! [`KafkaOrderingMode`](crate::api::kafka_ordering_mode::KafkaOrderingMode)
+//!
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+use crate::api::kafka_ordering_mode::KafkaOrderingMode;
+use crate::api::kafka_publish_message::KafkaPublishMessage;
+use crate::api::kafka_publish_message_type::KafkaPublishMessageType;
+
+/// get_message_shard
+///
+/// Get the worker thread that must publish the message to keep the
+/// ordering guarantee. Control messages (``Shutdown``,
+/// ``LogBrokerDetails``, etc.) always go to the first worker thread.
+///
+/// A ``Transaction`` goes to the shard of its first message with an
+/// ordering requirement so it stays in order with that key (or
+/// partition). Its other messages are published in order with the
+/// transaction but not with the messages for their keys outside the
+/// transaction.
+///
+/// # Arguments
+///
+/// * `msg` - the [`KafkaPublishMessage`] to assign
+/// * `ordering_mode` - configured [`KafkaOrderingMode`]
+/// * `num_threads` - number of worker threads in the pool
+///
+/// # Returns
+///
+/// ``Some(thread_num)`` starting at ``0`` or ``None`` if any
+/// worker thread can publish the message
+///
+pub fn get_message_shard(
+    msg: &KafkaPublishMessage,
+    ordering_mode: KafkaOrderingMode,
+    num_threads: u8,
+) -> Option<u8> {
+    if num_threads <= 1 {
+        return Some(0);
+    }
+    if msg.msg_type == KafkaPublishMessageType::Transaction {
+        return msg.transaction.as_ref().and_then(|transaction| {
+            transaction.msgs.iter().find_map(|txn_msg| {
+                get_message_shard(txn_msg, ordering_mode, num_threads)
+            })
+        });
+    }
+    if msg.msg_type != KafkaPublishMessageType::Data
+        && msg.msg_type != KafkaPublishMessageType::Sensitive
+    {
+        return Some(0);
+    }
+    let mut hasher = DefaultHasher::new();
+    match ordering_mode {
+        KafkaOrderingMode::Unordered => return None,
+        KafkaOrderingMode::Key => {
+            msg.key.as_ref()?;
+            msg.topic.hash(&mut hasher);
+            msg.key.hash(&mut hasher);
+        }
+        KafkaOrderingMode::TopicPartition => {
+            msg.topic.hash(&mut hasher);
+            match msg.partition {
+                Some(partition) => partition.hash(&mut hasher),
+                None => {
+                    // librdkafka partitions by key so the key
+                    // identifies the partition
+                    msg.key.as_ref()?;
+                    msg.key.hash(&mut hasher);
+                }
+            }
+        }
+    }
+    Some((hasher.finish() % num_threads as u64) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::kafka_transaction::KafkaTransaction;
+
+    fn data_msg(topic: &str, key: Option<&str>) -> KafkaPublishMessage {
+        KafkaPublishMessage::new_from_bytes(
+            KafkaPublishMessageType::Data,
+            topic,
+            key.map(|key| key.as_bytes().to_vec()),
+            Vec::new(),
+            Some(b"payload".to_vec()),
+        )
+    }
+
+    fn control_msg(msg_type: KafkaPublishMessageType) -> KafkaPublishMessage {
+        let mut msg = data_msg("orders", Some("a"));
+        msg.msg_type = msg_type;
+        msg
+    }
+
+    #[test]
+    fn one_worker_thread_is_always_shard_0() {
+        for ordering_mode in [
+            KafkaOrderingMode::Unordered,
+            KafkaOrderingMode::Key,
+            KafkaOrderingMode::TopicPartition,
+        ] {
+            assert_eq!(
+                get_message_shard(&data_msg("orders", None), ordering_mode, 1),
+                Some(0)
+            );
+        }
+    }
+
+    #[test]
+    fn unordered_has_no_shard() {
+        let msg = data_msg("orders", Some("a"));
+        assert_eq!(
+            get_message_shard(&msg, KafkaOrderingMode::Unordered, 4),
+            None
+        );
+    }
+
+    #[test]
+    fn key_mode_hashes_the_topic_and_key() {
+        let msg = data_msg("orders", Some("account-1"));
+        let shard = get_message_shard(&msg, KafkaOrderingMode::Key, 4);
+        assert!(shard.unwrap() < 4);
+        // the same key always maps to the same worker thread
+        for _ in 0..10 {
+            assert_eq!(
+                get_message_shard(&msg, KafkaOrderingMode::Key, 4),
+                shard
+            );
+        }
+        // the partition does not matter in key mode
+        let msg = msg.with_partition(3);
+        assert_eq!(get_message_shard(&msg, KafkaOrderingMode::Key, 4), shard);
+        // many keys spread across the worker threads
+        let shards: std::collections::HashSet<Option<u8>> = (0..100)
+            .map(|idx| {
+                get_message_shard(
+                    &data_msg("orders", Some(&format!("account-{idx}"))),
+                    KafkaOrderingMode::Key,
+                    4,
+                )
+            })
+            .collect();
+        assert_eq!(shards.len(), 4);
+        // no key means no ordering requirement
+        assert_eq!(
+            get_message_shard(
+                &data_msg("orders", None),
+                KafkaOrderingMode::Key,
+                4
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn topic_partition_mode_uses_the_partition_or_the_key() {
+        let mode = KafkaOrderingMode::TopicPartition;
+        let shard = get_message_shard(
+            &data_msg("orders", Some("a")).with_partition(2),
+            mode,
+            4,
+        );
+        assert!(shard.is_some());
+        // any key in the same partition maps to the same worker thread
+        assert_eq!(
+            get_message_shard(
+                &data_msg("orders", Some("b")).with_partition(2),
+                mode,
+                4
+            ),
+            shard
+        );
+        assert_eq!(
+            get_message_shard(
+                &data_msg("orders", None).with_partition(2),
+                mode,
+                4
+            ),
+            shard
+        );
+        // without a partition the key picks the partition
+        assert_eq!(
+            get_message_shard(&data_msg("orders", Some("a")), mode, 4),
+            get_message_shard(&data_msg("orders", Some("a")), mode, 4)
+        );
+        assert_eq!(get_message_shard(&data_msg("orders", None), mode, 4), None);
+    }
+
+    #[test]
+    fn control_messages_go_to_shard_0() {
+        for msg_type in [
+            KafkaPublishMessageType::Shutdown,
+            KafkaPublishMessageType::LogBrokerDetails,
+            KafkaPublishMessageType::LogBrokerTopicDetails,
+        ] {
+            for ordering_mode in [
+                KafkaOrderingMode::Unordered,
+                KafkaOrderingMode::Key,
+                KafkaOrderingMode::TopicPartition,
+            ] {
+                assert_eq!(
+                    get_message_shard(
+                        &control_msg(msg_type.clone()),
+                        ordering_mode,
+                        4
+                    ),
+                    Some(0)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn sensitive_messages_are_sharded_like_data() {
+        let msg = data_msg("orders", Some("account-1"));
+        let mut sensitive = msg.clone();
+        sensitive.msg_type = KafkaPublishMessageType::Sensitive;
+        assert_eq!(
+            get_message_shard(&sensitive, KafkaOrderingMode::Key, 4),
+            get_message_shard(&msg, KafkaOrderingMode::Key, 4)
+        );
+    }
+
+    #[test]
+    fn transactions_use_the_first_ordered_message() {
+        let keyed = data_msg("orders", Some("account-1"));
+        let expected = get_message_shard(&keyed, KafkaOrderingMode::Key, 4);
+        let txn_msg =
+            KafkaPublishMessage::new_transaction(KafkaTransaction::new(
+                vec![
+                    data_msg("audit", None),
+                    keyed,
+                    data_msg("orders", Some("account-2")),
+                ],
+                None,
+            ));
+        assert_eq!(
+            get_message_shard(&txn_msg, KafkaOrderingMode::Key, 4),
+            expected
+        );
+        // no message with an ordering requirement
+        let txn_msg = KafkaPublishMessage::new_transaction(
+            KafkaTransaction::new(vec![data_msg("audit", None)], None),
+        );
+        assert_eq!(
+            get_message_shard(&txn_msg, KafkaOrderingMode::Key, 4),
+            None
+        );
+        assert_eq!(
+            get_message_shard(
+                &KafkaPublishMessage::new_transaction(
+                    KafkaTransaction::default()
+                ),
+                KafkaOrderingMode::Key,
+                4
+            ),
+            None
+        );
+    }
+}
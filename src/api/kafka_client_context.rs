@@ -0,0 +1,97 @@
+//! Custom ``rdkafka`` client context shared by the producer
+//! and consumer
+//!
+use std::sync::Arc;
+
+use log::error;
+
+use rdkafka::client::ClientContext;
+use rdkafka::consumer::ConsumerContext;
+use rdkafka::error::KafkaError;
+use rdkafka::statistics::Statistics;
+use rdkafka::types::RDKafkaErrorCode;
+
+use crate::health::kafka_health_tracker::KafkaHealthTracker;
+use crate::stats::kafka_producer_stats::KafkaProducerStats;
+use crate::stats::kafka_stats_handler::KafkaStatsHandler;
+use crate::stats::kafka_stats_store::KafkaStatsStore;
+
+/// KafkaClientContext
+///
+/// [`rdkafka::client::ClientContext`](rdkafka::client::ClientContext)
+/// used by every producer and consumer the ``kafka_threadpool``
+/// creates
+///
+/// This context does not install the ``OAUTHBEARER`` token refresh
+/// callback so ``librdkafka`` handles ``OAUTHBEARER`` on its own
+/// (``sasl.oauthbearer.method=oidc`` or unsecured JWTs). Clients
+/// for a ``config.oauth_token_provider`` wrap it in a
+/// [`KafkaOAuthClientContext`](crate::api::kafka_oauth_client_context::KafkaOAuthClientContext).
+///
+/// * `worker_label` - worker thread log label for the statistics
+/// (empty for clients outside the worker threads)
+/// * `stats_store` - optional
+/// [`KafkaStatsStore`](crate::stats::kafka_stats_store::KafkaStatsStore)
+/// that keeps the latest statistics for the worker
+/// * `stats_handler` - optional
+/// [`KafkaStatsHandler`](crate::stats::kafka_stats_handler::KafkaStatsHandler)
+/// called with every statistics snapshot
+/// * `health_label` - worker thread log label for the broker
+/// connectivity (the transactional producer reports under its
+/// worker thread)
+/// * `health_tracker` - optional
+/// [`KafkaHealthTracker`](crate::health::kafka_health_tracker::KafkaHealthTracker)
+/// that records all brokers down events and reconnects
+///
+#[derive(Default, Clone)]
+pub struct KafkaClientContext {
+    pub worker_label: String,
+    pub stats_store: Option<Arc<KafkaStatsStore>>,
+    pub stats_handler: Option<Arc<dyn KafkaStatsHandler>>,
+    pub health_label: String,
+    pub health_tracker: Option<Arc<KafkaHealthTracker>>,
+}
+
+impl ClientContext for KafkaClientContext {
+    // librdkafka only calls this when statistics.interval.ms > 0
+    fn stats(&self, statistics: Statistics) {
+        if self.stats_store.is_none()
+            && self.stats_handler.is_none()
+            && self.health_tracker.is_none()
+        {
+            return;
+        }
+        let stats =
+            KafkaProducerStats::from_statistics(&self.worker_label, statistics);
+        if let Some(health_tracker) = &self.health_tracker {
+            // librdkafka does not emit an event when a broker
+            // reconnects so use the broker states in the snapshot
+            if stats.brokers.iter().any(|broker| broker.state == "UP") {
+                health_tracker.record_brokers_up(&self.health_label);
+            }
+        }
+        if let Some(stats_handler) = &self.stats_handler {
+            stats_handler.handle_stats(&stats);
+        }
+        if let Some(stats_store) = &self.stats_store {
+            stats_store.update(stats);
+        }
+    }
+
+    fn error(&self, error: KafkaError, reason: &str) {
+        error!("librdkafka: {error}: {reason}");
+        if let Some(health_tracker) = &self.health_tracker {
+            match error.rdkafka_error_code() {
+                Some(RDKafkaErrorCode::AllBrokersDown) => health_tracker
+                    .record_brokers_down(&self.health_label, reason),
+                // librdkafka keeps reporting failed reconnects
+                Some(RDKafkaErrorCode::BrokerTransportFailure)
+                | Some(RDKafkaErrorCode::Resolve) => health_tracker
+                    .refresh_brokers_down(&self.health_label, reason),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl ConsumerContext for KafkaClientContext {}
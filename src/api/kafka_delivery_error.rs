@@ -0,0 +1,159 @@
+//! enum for the reasons a
+//! [`KafkaDeliveryFuture`](crate::api::kafka_delivery_notifier::KafkaDeliveryFuture)
+//! can fail
+//!
+use rdkafka::error::KafkaError;
+use rdkafka::types::RDKafkaErrorCode;
+
+/// KafkaDeliveryError
+///
+/// Supported delivery failures returned to callers waiting on a
+/// [`KafkaDeliveryFuture`](crate::api::kafka_delivery_notifier::KafkaDeliveryFuture)
+///
+/// - ``Broker`` - kafka rejected the message with an optional
+/// ``rdkafka`` error code and the error reason
+/// - ``Dropped`` - the message was dropped before kafka
+/// acknowledged it (threadpool shutdown, producer canceled, etc.)
+/// - ``Disabled`` - the threadpool is not enabled so the message
+/// was never queued
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KafkaDeliveryError {
+    Broker {
+        code: Option<RDKafkaErrorCode>,
+        reason: String,
+    },
+    Dropped,
+    Disabled,
+}
+
+impl KafkaDeliveryError {
+    /// is_retriable
+    ///
+    /// Classify the error as retriable (broker unavailable, request
+    /// timed out, queue full, etc.) or fatal (the topic does not
+    /// exist, the message is too large, authorization failed, etc.)
+    ///
+    /// # Returns
+    ///
+    /// ``true`` if publishing the same message again may succeed
+    ///
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            KafkaDeliveryError::Broker { code, .. } => !matches!(
+                code,
+                Some(
+                    RDKafkaErrorCode::UnknownTopicOrPartition
+                        | RDKafkaErrorCode::UnknownTopic
+                        | RDKafkaErrorCode::UnknownPartition
+                        | RDKafkaErrorCode::InvalidPartitions
+                        | RDKafkaErrorCode::InvalidTopic
+                        | RDKafkaErrorCode::MessageSizeTooLarge
+                        | RDKafkaErrorCode::InvalidMessageSize
+                        | RDKafkaErrorCode::InvalidMessage
+                        | RDKafkaErrorCode::InvalidRecord
+                        | RDKafkaErrorCode::MessageBatchTooLarge
+                        | RDKafkaErrorCode::RecordListTooLarge
+                        | RDKafkaErrorCode::InvalidRequiredAcks
+                        | RDKafkaErrorCode::InvalidTimestamp
+                        | RDKafkaErrorCode::TopicAuthorizationFailed
+                        | RDKafkaErrorCode::ClusterAuthorizationFailed
+                        | RDKafkaErrorCode::SaslAuthenticationFailed
+                        | RDKafkaErrorCode::Authentication
+                        | RDKafkaErrorCode::UnsupportedVersion
+                        | RDKafkaErrorCode::UnsupportedForMessageFormat
+                        | RDKafkaErrorCode::PolicyViolation
+                        | RDKafkaErrorCode::InvalidArgument
+                        | RDKafkaErrorCode::Fatal
+                )
+            ),
+            KafkaDeliveryError::Dropped => false,
+            KafkaDeliveryError::Disabled => false,
+        }
+    }
+}
+
+impl From<&KafkaError> for KafkaDeliveryError {
+    fn from(err: &KafkaError) -> Self {
+        KafkaDeliveryError::Broker {
+            code: err.rdkafka_error_code(),
+            reason: err.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for KafkaDeliveryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            KafkaDeliveryError::Broker { code, reason } => {
+                write!(f, "broker error code={code:?} reason={reason}")
+            }
+            KafkaDeliveryError::Dropped => {
+                write!(f, "message dropped before delivery")
+            }
+            KafkaDeliveryError::Disabled => {
+                write!(f, "kafka not enabled")
+            }
+        }
+    }
+}
+
+impl std::error::Error for KafkaDeliveryError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn broker_err(code: Option<RDKafkaErrorCode>) -> KafkaDeliveryError {
+        KafkaDeliveryError::Broker {
+            code,
+            reason: format!("{code:?}"),
+        }
+    }
+
+    #[test]
+    fn is_retriable_for_transient_broker_errors() {
+        for code in [
+            RDKafkaErrorCode::BrokerTransportFailure,
+            RDKafkaErrorCode::AllBrokersDown,
+            RDKafkaErrorCode::RequestTimedOut,
+            RDKafkaErrorCode::MessageTimedOut,
+            RDKafkaErrorCode::QueueFull,
+            RDKafkaErrorCode::NotLeaderForPartition,
+            RDKafkaErrorCode::LeaderNotAvailable,
+            RDKafkaErrorCode::NotEnoughReplicas,
+        ] {
+            assert!(broker_err(Some(code)).is_retriable(), "{code:?}");
+        }
+        // errors without a code are retried
+        assert!(broker_err(None).is_retriable());
+    }
+
+    #[test]
+    fn is_not_retriable_for_fatal_broker_errors() {
+        for code in [
+            RDKafkaErrorCode::UnknownTopicOrPartition,
+            RDKafkaErrorCode::UnknownTopic,
+            RDKafkaErrorCode::UnknownPartition,
+            RDKafkaErrorCode::InvalidPartitions,
+            RDKafkaErrorCode::InvalidTopic,
+            RDKafkaErrorCode::MessageSizeTooLarge,
+            RDKafkaErrorCode::RecordListTooLarge,
+            RDKafkaErrorCode::InvalidMessage,
+            RDKafkaErrorCode::TopicAuthorizationFailed,
+            RDKafkaErrorCode::ClusterAuthorizationFailed,
+            RDKafkaErrorCode::SaslAuthenticationFailed,
+            RDKafkaErrorCode::Authentication,
+            RDKafkaErrorCode::PolicyViolation,
+            RDKafkaErrorCode::Fatal,
+        ] {
+            assert!(!broker_err(Some(code)).is_retriable(), "{code:?}");
+        }
+    }
+
+    #[test]
+    fn is_not_retriable_for_dropped_or_disabled() {
+        assert!(!KafkaDeliveryError::Dropped.is_retriable());
+        assert!(!KafkaDeliveryError::Disabled.is_retriable());
+    }
+}
@@ -0,0 +1,136 @@
+//! Per-message delivery acknowledgements where the
+//! [`KafkaDeliveryNotifier`](crate::api::kafka_delivery_notifier::KafkaDeliveryNotifier)
+//! travels with the
+//! [`KafkaPublishMessage`](crate::api::kafka_publish_message::KafkaPublishMessage)
+//! through the lockable work vec and the caller awaits the matching
+//! [`KafkaDeliveryFuture`](crate::api::kafka_delivery_notifier::KafkaDeliveryFuture)
+//!
+//! Example for waiting on the broker acknowledgement:
+//!
+//! ```rust
+//! let delivery = my_kafka_publisher
+//!     .add_data_msg_with_ack("testing", "key", None, "payload")
+//!     .await
+//!     .unwrap();
+//! let report = delivery.await.unwrap();
+//! println!("stored partition={} offset={}", report.partition, report.offset);
+//! ```
+//!
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+
+use tokio::sync::oneshot;
+
+use crate::api::kafka_delivery_error::KafkaDeliveryError;
+use crate::api::kafka_delivery_report::KafkaDeliveryReport;
+
+/// KafkaDeliveryResult
+///
+/// Outcome for a single published message
+///
+pub type KafkaDeliveryResult = Result<KafkaDeliveryReport, KafkaDeliveryError>;
+
+/// KafkaDeliveryNotifier
+///
+/// Cloneable sending half of a delivery acknowledgement. Only the
+/// first call to
+/// [`notify`](crate::api::kafka_delivery_notifier::KafkaDeliveryNotifier::notify)
+/// reaches the caller. If every clone is dropped without notifying,
+/// the caller receives ``KafkaDeliveryError::Dropped``.
+///
+#[derive(Default, Clone)]
+pub struct KafkaDeliveryNotifier {
+    sender: Arc<Mutex<Option<oneshot::Sender<KafkaDeliveryResult>>>>,
+}
+
+impl KafkaDeliveryNotifier {
+    /// new
+    ///
+    /// Create a connected notifier and future pair
+    ///
+    /// # Returns
+    ///
+    /// ``(KafkaDeliveryNotifier, KafkaDeliveryFuture)``
+    ///
+    pub fn new() -> (Self, KafkaDeliveryFuture) {
+        let (tx, rx) = oneshot::channel();
+        (
+            KafkaDeliveryNotifier {
+                sender: Arc::new(Mutex::new(Some(tx))),
+            },
+            KafkaDeliveryFuture { receiver: rx },
+        )
+    }
+
+    /// notify
+    ///
+    /// Send the delivery result to the waiting caller
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - delivery report or error for the message
+    ///
+    /// # Returns
+    ///
+    /// ``true`` if the result was sent and ``false`` if the caller
+    /// already received a result or stopped waiting
+    ///
+    pub fn notify(&self, result: KafkaDeliveryResult) -> bool {
+        let sender = match self.sender.lock() {
+            Ok(mut locked_sender) => locked_sender.take(),
+            Err(_) => None,
+        };
+        match sender {
+            Some(tx) => tx.send(result).is_ok(),
+            None => false,
+        }
+    }
+}
+
+/// KafkaDeliveryFuture
+///
+/// Receiving half of a delivery acknowledgement that resolves
+/// with a
+/// [`KafkaDeliveryReport`](crate::api::kafka_delivery_report::KafkaDeliveryReport)
+/// once kafka stores the message or a
+/// [`KafkaDeliveryError`](crate::api::kafka_delivery_error::KafkaDeliveryError)
+/// if the message fails or is dropped
+///
+pub struct KafkaDeliveryFuture {
+    receiver: oneshot::Receiver<KafkaDeliveryResult>,
+}
+
+impl KafkaDeliveryFuture {
+    /// failed
+    ///
+    /// Create an already-resolved future for messages that
+    /// were never queued
+    ///
+    /// # Arguments
+    ///
+    /// * `err` - reason the message was not queued
+    ///
+    pub fn failed(err: KafkaDeliveryError) -> Self {
+        let (notifier, delivery) = KafkaDeliveryNotifier::new();
+        notifier.notify(Err(err));
+        delivery
+    }
+}
+
+impl Future for KafkaDeliveryFuture {
+    type Output = KafkaDeliveryResult;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Self::Output> {
+        Pin::new(&mut self.receiver).poll(cx).map(|res| match res {
+            Ok(delivery_result) => delivery_result,
+            Err(_) => Err(KafkaDeliveryError::Dropped),
+        })
+    }
+}
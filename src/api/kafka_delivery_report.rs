@@ -0,0 +1,38 @@
+//! class definition for
+//! [`KafkaDeliveryReport`](crate::api::kafka_delivery_report::KafkaDeliveryReport)
+//! which holds the broker acknowledgement for a published message
+//!
+
+/// KafkaDeliveryReport
+///
+/// Returned to callers waiting on a
+/// [`KafkaDeliveryFuture`](crate::api::kafka_delivery_notifier::KafkaDeliveryFuture)
+/// once the kafka broker acknowledges the message
+///
+/// * `topic` - kafka topic the message was published into
+/// * `partition` - kafka partition that stored the message
+/// * `offset` - kafka offset for the message within the ``partition``
+/// * `timestamp` - optional - message timestamp in milliseconds
+/// since the unix epoch
+///
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct KafkaDeliveryReport {
+    pub topic: String,
+    pub partition: i32,
+    pub offset: i64,
+    pub timestamp: Option<i64>,
+}
+
+impl std::fmt::Display for KafkaDeliveryReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "KafkaDeliveryReport \
+            topic={} \
+            partition={} \
+            offset={} \
+            timestamp={:?}",
+            self.topic, self.partition, self.offset, self.timestamp
+        )
+    }
+}
@@ -0,0 +1,83 @@
+//! class definition for
+//! [`KafkaHeader`](crate::api::kafka_header::KafkaHeader)
+//! which holds a single kafka message header
+//!
+
+/// KafkaHeader
+///
+/// A single kafka message header. Messages hold an ordered list
+/// of headers so the same ``key`` can appear more than once.
+///
+/// * `key` - header name
+/// * `value` - optional - header value bytes (``None`` publishes
+/// a null header value)
+///
+#[derive(Clone, PartialEq, Eq)]
+pub struct KafkaHeader {
+    pub key: String,
+    pub value: Option<Vec<u8>>,
+}
+
+impl KafkaHeader {
+    /// new
+    ///
+    /// Create a header with a binary value
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - header name
+    /// * `value` - optional - header value bytes
+    ///
+    pub fn new(key: &str, value: Option<Vec<u8>>) -> Self {
+        KafkaHeader {
+            key: key.to_string(),
+            value,
+        }
+    }
+
+    /// from_str_value
+    ///
+    /// Create a header with a string value
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - header name
+    /// * `value` - header value
+    ///
+    pub fn from_str_value(key: &str, value: &str) -> Self {
+        KafkaHeader::new(key, Some(value.as_bytes().to_vec()))
+    }
+
+    /// value_as_string
+    ///
+    /// # Returns
+    ///
+    /// the ``value`` as a lossy UTF-8 ``String`` or ``None`` for a
+    /// null value
+    ///
+    pub fn value_as_string(&self) -> Option<String> {
+        self.value
+            .as_ref()
+            .map(|value| String::from_utf8_lossy(value).to_string())
+    }
+
+    /// size_in_bytes
+    ///
+    /// Number of bytes in the ``key`` and ``value``
+    ///
+    pub fn size_in_bytes(&self) -> usize {
+        self.key.len() + self.value.as_ref().map_or(0, |value| value.len())
+    }
+}
+
+impl std::fmt::Display for KafkaHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}={:?}", self.key, self.value_as_string())
+    }
+}
+
+impl std::fmt::Debug for KafkaHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{self}")
+    }
+}
@@ -0,0 +1,142 @@
+//! ``rdkafka`` client context for ``SASL`` ``OAUTHBEARER`` clients
+//! that get their tokens from a
+//! [`KafkaOAuthTokenProvider`](crate::api::kafka_oauth_token_provider::KafkaOAuthTokenProvider)
+//!
+use std::error::Error;
+use std::sync::Arc;
+
+use rdkafka::client::ClientContext;
+use rdkafka::client::OAuthToken;
+use rdkafka::consumer::ConsumerContext;
+use rdkafka::error::KafkaError;
+use rdkafka::statistics::Statistics;
+
+use crate::api::kafka_client_context::KafkaClientContext;
+use crate::api::kafka_oauth_token_provider::KafkaOAuthTokenProvider;
+
+/// KafkaOAuthClientContext
+///
+/// [`rdkafka::client::ClientContext`](rdkafka::client::ClientContext)
+/// that installs the ``OAUTHBEARER`` token refresh callback and
+/// hands every refresh to the ``token_provider``. The statistics
+/// and errors go to the wrapped
+/// [`KafkaClientContext`](crate::api::kafka_client_context::KafkaClientContext).
+///
+/// The ``kafka_threadpool`` only uses this context when
+/// ``config.oauth_token_provider`` is set. ``librdkafka`` rejects
+/// the refresh callback together with its own ``OAUTHBEARER``
+/// handling (``sasl.oauthbearer.method=oidc`` or unsecured JWTs).
+///
+/// * `context` - statistics and broker connectivity context
+/// * `token_provider` -
+/// [`KafkaOAuthTokenProvider`](crate::api::kafka_oauth_token_provider::KafkaOAuthTokenProvider)
+/// called for each token refresh
+///
+#[derive(Clone)]
+pub struct KafkaOAuthClientContext {
+    pub context: KafkaClientContext,
+    pub token_provider: Arc<dyn KafkaOAuthTokenProvider>,
+}
+
+impl KafkaOAuthClientContext {
+    /// new
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - statistics and broker connectivity context
+    /// * `token_provider` - provider called for each token refresh
+    ///
+    pub fn new(
+        context: KafkaClientContext,
+        token_provider: Arc<dyn KafkaOAuthTokenProvider>,
+    ) -> Self {
+        KafkaOAuthClientContext {
+            context,
+            token_provider,
+        }
+    }
+}
+
+impl ClientContext for KafkaOAuthClientContext {
+    // librdkafka only calls this when sasl.mechanism=OAUTHBEARER
+    const ENABLE_REFRESH_OAUTH_TOKEN: bool = true;
+
+    fn generate_oauth_token(
+        &self,
+        oauthbearer_config: Option<&str>,
+    ) -> Result<OAuthToken, Box<dyn Error>> {
+        let token = self.token_provider.generate_token(oauthbearer_config)?;
+        Ok(OAuthToken {
+            token: token.token,
+            principal_name: token.principal_name,
+            lifetime_ms: token.lifetime_ms,
+        })
+    }
+
+    fn stats(&self, statistics: Statistics) {
+        self.context.stats(statistics);
+    }
+
+    fn error(&self, error: KafkaError, reason: &str) {
+        self.context.error(error, reason);
+    }
+}
+
+impl ConsumerContext for KafkaOAuthClientContext {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use std::time::SystemTime;
+    use std::time::UNIX_EPOCH;
+
+    use super::*;
+    use crate::api::kafka_oauth_token_provider::KafkaOAuthToken;
+    use crate::api::kafka_oauth_token_provider::StaticOAuthTokenProvider;
+
+    fn now_ms() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64
+    }
+
+    #[test]
+    fn generate_oauth_token_uses_the_provider() {
+        let context = KafkaOAuthClientContext::new(
+            KafkaClientContext::default(),
+            Arc::new(StaticOAuthTokenProvider::new(
+                "local-stub-token",
+                "local-user",
+                Duration::from_secs(3600),
+            )),
+        );
+        let before_ms = now_ms();
+        let token = context.generate_oauth_token(None).unwrap();
+        assert_eq!(token.token, "local-stub-token");
+        assert_eq!(token.principal_name, "local-user");
+        assert!(token.lifetime_ms >= before_ms + 3_600_000);
+        assert!(token.lifetime_ms <= now_ms() + 3_600_000);
+    }
+
+    #[test]
+    fn generate_oauth_token_returns_provider_errors() {
+        struct FailingProvider;
+        impl KafkaOAuthTokenProvider for FailingProvider {
+            fn generate_token(
+                &self,
+                oauthbearer_config: Option<&str>,
+            ) -> Result<KafkaOAuthToken, Box<dyn Error>> {
+                Err(format!("no token for {oauthbearer_config:?}").into())
+            }
+        }
+        let context = KafkaOAuthClientContext::new(
+            KafkaClientContext::default(),
+            Arc::new(FailingProvider),
+        );
+        let err = context
+            .generate_oauth_token(Some("scope=kafka"))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "no token for Some(\"scope=kafka\")");
+    }
+}
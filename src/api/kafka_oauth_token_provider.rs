@@ -0,0 +1,113 @@
+//! Pluggable token provider for ``SASL`` ``OAUTHBEARER``
+//! authentication. ``librdkafka`` calls the provider from the
+//! [`KafkaOAuthClientContext`](crate::api::kafka_oauth_client_context::KafkaOAuthClientContext)
+//! whenever the current token needs to be refreshed. Without a
+//! provider ``librdkafka`` fetches the tokens itself
+//! (``KAFKA_RDKAFKA_SASL_OAUTHBEARER_METHOD=oidc``).
+//!
+//! ```rust
+//! use std::sync::Arc;
+//! use kafka_threadpool::api::kafka_oauth_token_provider::StaticOAuthTokenProvider;
+//! use kafka_threadpool::api::kafka_security_protocol::KafkaSaslMechanism;
+//! use kafka_threadpool::api::kafka_security_protocol::KafkaSecurityProtocol;
+//! use kafka_threadpool::config::kafka_client_config::KafkaClientConfig;
+//! let config = KafkaClientConfig::builder()
+//!     .brokers(vec!["localhost:9093".to_string()])
+//!     .security_protocol(KafkaSecurityProtocol::SaslPlaintext)
+//!     .sasl_mechanism(KafkaSaslMechanism::OAuthBearer)
+//!     .oauth_token_provider(Arc::new(StaticOAuthTokenProvider::new(
+//!         "local-stub-token",
+//!         "local-user",
+//!         std::time::Duration::from_secs(3600),
+//!     )))
+//!     .build()
+//!     .unwrap();
+//! ```
+//!
+use std::error::Error;
+use std::time::Duration;
+
+/// KafkaOAuthToken
+///
+/// ``OAUTHBEARER`` token handed to ``librdkafka``
+///
+/// * `token` - the token value sent to the broker
+/// * `principal_name` - kafka principal the token belongs to
+/// * `lifetime_ms` - when the token expires in milliseconds
+/// since the unix epoch
+///
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct KafkaOAuthToken {
+    pub token: String,
+    pub principal_name: String,
+    pub lifetime_ms: i64,
+}
+
+/// KafkaOAuthTokenProvider
+///
+/// Implement this trait to fetch ``OAUTHBEARER`` tokens from
+/// an identity provider
+///
+pub trait KafkaOAuthTokenProvider: Send + Sync {
+    /// generate_token
+    ///
+    /// Create a new token
+    ///
+    /// # Arguments
+    ///
+    /// * `oauthbearer_config` - optional value of
+    /// ``sasl.oauthbearer.config`` (set with
+    /// ``KAFKA_SASL_OAUTHBEARER_CONFIG``)
+    ///
+    fn generate_token(
+        &self,
+        oauthbearer_config: Option<&str>,
+    ) -> Result<KafkaOAuthToken, Box<dyn Error>>;
+}
+
+/// StaticOAuthTokenProvider
+///
+/// Token provider that always returns the same token with
+/// a rolling expiration. Useful as a local token stub for
+/// development and testing.
+///
+#[derive(Debug, Clone)]
+pub struct StaticOAuthTokenProvider {
+    token: String,
+    principal_name: String,
+    lifetime: Duration,
+}
+
+impl StaticOAuthTokenProvider {
+    /// new
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - the token value sent to the broker
+    /// * `principal_name` - kafka principal the token belongs to
+    /// * `lifetime` - how long each generated token is valid
+    ///
+    pub fn new(token: &str, principal_name: &str, lifetime: Duration) -> Self {
+        StaticOAuthTokenProvider {
+            token: token.to_string(),
+            principal_name: principal_name.to_string(),
+            lifetime,
+        }
+    }
+}
+
+impl KafkaOAuthTokenProvider for StaticOAuthTokenProvider {
+    fn generate_token(
+        &self,
+        _oauthbearer_config: Option<&str>,
+    ) -> Result<KafkaOAuthToken, Box<dyn Error>> {
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_millis() as i64;
+        Ok(KafkaOAuthToken {
+            token: self.token.clone(),
+            principal_name: self.principal_name.clone(),
+            lifetime_ms: now_ms + self.lifetime.as_millis() as i64,
+        })
+    }
+}
@@ -0,0 +1,63 @@
+//! enum for the supported message ordering modes across the
+//! worker threads
+//!
+
+/// KafkaOrderingMode
+///
+/// Supported ordering guarantees for publishing messages
+/// (set with ``KAFKA_ORDERING_MODE``)
+///
+/// - ``Unordered`` - any worker thread can publish any message
+/// (default)
+/// - ``Key`` - messages are sharded to worker threads by a hash of
+/// the topic and key so each key is published in enqueue order
+/// - ``TopicPartition`` - messages are sharded to worker threads by
+/// a hash of the topic and target partition (or the topic and key
+/// if the message has no target partition) so each partition is
+/// published in enqueue order
+///
+/// Messages without a key (and without a target partition for
+/// ``TopicPartition``) have no ordering requirement and can be
+/// published by any worker thread. A ``Transaction`` is published
+/// by the worker thread of its first message with an ordering
+/// requirement.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KafkaOrderingMode {
+    Unordered,
+    Key,
+    TopicPartition,
+}
+
+impl Default for KafkaOrderingMode {
+    fn default() -> Self {
+        KafkaOrderingMode::Unordered
+    }
+}
+
+impl std::str::FromStr for KafkaOrderingMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "unordered" | "none" => Ok(KafkaOrderingMode::Unordered),
+            "key" => Ok(KafkaOrderingMode::Key),
+            "topic_partition" => Ok(KafkaOrderingMode::TopicPartition),
+            _ => Err(format!(
+                "unsupported ordering mode={s} \
+                please use: unordered, key or topic_partition"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for KafkaOrderingMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mode = match self {
+            KafkaOrderingMode::Unordered => "unordered",
+            KafkaOrderingMode::Key => "key",
+            KafkaOrderingMode::TopicPartition => "topic_partition",
+        };
+        write!(f, "{mode}")
+    }
+}
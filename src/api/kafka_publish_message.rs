@@ -3,7 +3,10 @@
 //!
 use std::collections::HashMap;
 
+use crate::api::kafka_delivery_notifier::KafkaDeliveryNotifier;
+use crate::api::kafka_header::KafkaHeader;
 use crate::api::kafka_publish_message_type::KafkaPublishMessageType;
+use crate::api::kafka_transaction::KafkaTransaction;
 
 /// KafkaPublishMessage
 ///
@@ -13,14 +16,35 @@ use crate::api::kafka_publish_message_type::KafkaPublishMessageType;
 /// - metadata
 /// - messaging info
 /// - headers
+/// - optional delivery acknowledgement
+///
+/// The ``key`` and ``payload`` are bytes so any encoding (Protobuf,
+/// Avro, JSON, etc.) can be published:
+///
+/// - ``key`` = ``None`` publishes a null key so ``librdkafka`` picks
+/// the partition
+/// - ``payload`` = ``None`` publishes a tombstone (null payload)
+/// - ``partition`` = ``None`` lets ``librdkafka`` pick the partition
+/// - ``timestamp`` = ``None`` uses the publish time
+/// - ``headers`` are published in order and may repeat a key
+/// - ``transaction`` holds the grouped messages for the
+/// ``Transaction`` message type
+/// - ``span`` holds the caller's ``tracing`` span captured when the
+/// message was added (requires the ``tracing`` feature)
 ///
 #[derive(Clone)]
 pub struct KafkaPublishMessage {
     pub msg_type: KafkaPublishMessageType,
     pub topic: String,
-    pub key: String,
-    pub headers: Option<HashMap<String, String>>,
-    pub payload: String,
+    pub partition: Option<i32>,
+    pub timestamp: Option<i64>,
+    pub key: Option<Vec<u8>>,
+    pub headers: Vec<KafkaHeader>,
+    pub payload: Option<Vec<u8>>,
+    pub delivery: Option<KafkaDeliveryNotifier>,
+    pub transaction: Option<KafkaTransaction>,
+    #[cfg(feature = "tracing")]
+    pub span: Option<tracing::Span>,
 }
 
 impl Default for KafkaPublishMessage {
@@ -44,9 +68,15 @@ impl KafkaPublishMessage {
         KafkaPublishMessage {
             msg_type: KafkaPublishMessageType::Data,
             topic: "".to_string(),
-            key: "".to_string(),
-            headers: None,
-            payload: "".to_string(),
+            partition: None,
+            timestamp: None,
+            key: None,
+            headers: Vec::new(),
+            payload: None,
+            delivery: None,
+            transaction: None,
+            #[cfg(feature = "tracing")]
+            span: None,
         }
     }
 
@@ -54,14 +84,16 @@ impl KafkaPublishMessage {
     ///
     /// Create a
     /// [`KafkaPublishMessage`](crate::api::kafka_publish_message_type::KafkaPublishMessage)
-    /// from arguments
+    /// from string arguments
     ///
     /// # Arguments
     ///
     /// * `msg_type` - type of message
     /// * `topic` - kafka topic
-    /// * `key` - kafka partition key
+    /// * `key` - kafka partition key (an empty ``key`` publishes
+    /// a null key)
     /// * `headers` - key/value headers to add during publishing
+    /// (in the ``HashMap`` iteration order)
     /// * `payload` - data for this message
     ///
     /// # Examples
@@ -86,15 +118,235 @@ impl KafkaPublishMessage {
         key: &str,
         headers: Option<HashMap<String, String>>,
         payload: &str,
+    ) -> Self {
+        let key = if key.is_empty() {
+            None
+        } else {
+            Some(key.as_bytes().to_vec())
+        };
+        let headers: Vec<KafkaHeader> = headers
+            .unwrap_or_default()
+            .iter()
+            .map(|(k, v)| KafkaHeader::from_str_value(k, v))
+            .collect();
+        KafkaPublishMessage::new_from_bytes(
+            msg_type,
+            topic,
+            key,
+            headers,
+            Some(payload.as_bytes().to_vec()),
+        )
+    }
+
+    /// new_from_bytes
+    ///
+    /// Create a
+    /// [`KafkaPublishMessage`](crate::api::kafka_publish_message_type::KafkaPublishMessage)
+    /// from binary arguments
+    ///
+    /// # Arguments
+    ///
+    /// * `msg_type` - type of message
+    /// * `topic` - kafka topic
+    /// * `key` - optional - kafka partition key (``None`` publishes
+    /// a null key)
+    /// * `headers` - ordered
+    /// [`KafkaHeader`](crate::api::kafka_header::KafkaHeader) list
+    /// to add during publishing
+    /// * `payload` - optional - data for this message (``None``
+    /// publishes a tombstone)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use kafka_threadpool::api::kafka_publish_message::KafkaPublishMessage;
+    /// use kafka_threadpool::api::kafka_publish_message_type::KafkaPublishMessageType;
+    /// let msg: KafkaPublishMessage = KafkaPublishMessage::new_from_bytes(
+    ///     KafkaPublishMessageType::Data,
+    ///     "testing",
+    ///     None,
+    ///     vec![],
+    ///     Some(vec![0x08, 0x96, 0x01]));
+    /// ```
+    pub fn new_from_bytes(
+        msg_type: KafkaPublishMessageType,
+        topic: &str,
+        key: Option<Vec<u8>>,
+        headers: Vec<KafkaHeader>,
+        payload: Option<Vec<u8>>,
     ) -> Self {
         KafkaPublishMessage {
             msg_type,
             topic: topic.to_string(),
-            key: key.to_string(),
+            partition: None,
+            timestamp: None,
+            key,
             headers,
-            payload: payload.to_string(),
+            payload,
+            delivery: None,
+            transaction: None,
+            #[cfg(feature = "tracing")]
+            span: None,
+        }
+    }
+
+    /// new_transaction
+    ///
+    /// Create a ``Transaction``
+    /// [`KafkaPublishMessage`](crate::api::kafka_publish_message_type::KafkaPublishMessage)
+    /// that carries a group of messages for one worker thread to
+    /// commit atomically
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - messages and the optional outcome notifier
+    ///
+    pub fn new_transaction(transaction: KafkaTransaction) -> Self {
+        let mut msg = KafkaPublishMessage::new();
+        msg.msg_type = KafkaPublishMessageType::Transaction;
+        msg.transaction = Some(transaction);
+        msg
+    }
+
+    /// new_tombstone
+    ///
+    /// Create a ``Data``
+    /// [`KafkaPublishMessage`](crate::api::kafka_publish_message_type::KafkaPublishMessage)
+    /// with a null payload for deleting the ``key`` from a
+    /// compacted topic
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - kafka topic
+    /// * `key` - kafka partition key to delete
+    ///
+    pub fn new_tombstone(topic: &str, key: &[u8]) -> Self {
+        KafkaPublishMessage::new_from_bytes(
+            KafkaPublishMessageType::Data,
+            topic,
+            Some(key.to_vec()),
+            Vec::new(),
+            None,
+        )
+    }
+
+    /// with_partition
+    ///
+    /// Publish the message into a specific partition instead of
+    /// letting ``librdkafka`` pick the partition
+    ///
+    /// # Arguments
+    ///
+    /// * `partition` - target kafka partition
+    ///
+    pub fn with_partition(mut self, partition: i32) -> Self {
+        self.partition = Some(partition);
+        self
+    }
+
+    /// with_timestamp
+    ///
+    /// Publish the message with a caller-supplied event timestamp
+    /// instead of the publish time
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - milliseconds since the unix epoch
+    ///
+    pub fn with_timestamp(mut self, timestamp: i64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// with_header
+    ///
+    /// Append a header (the same ``key`` may be added more than once)
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - header name
+    /// * `value` - optional - header value bytes
+    ///
+    pub fn with_header(mut self, key: &str, value: Option<Vec<u8>>) -> Self {
+        self.headers.push(KafkaHeader::new(key, value));
+        self
+    }
+
+    /// is_tombstone
+    ///
+    /// # Returns
+    ///
+    /// ``true`` if the message has a null payload
+    ///
+    pub fn is_tombstone(&self) -> bool {
+        self.payload.is_none()
+    }
+
+    /// key_as_string
+    ///
+    /// # Returns
+    ///
+    /// the ``key`` as a lossy UTF-8 ``String`` or ``None`` for a
+    /// null key
+    ///
+    pub fn key_as_string(&self) -> Option<String> {
+        self.key
+            .as_ref()
+            .map(|key| String::from_utf8_lossy(key).to_string())
+    }
+
+    /// payload_as_string
+    ///
+    /// # Returns
+    ///
+    /// the ``payload`` as a lossy UTF-8 ``String`` or ``None`` for
+    /// a tombstone
+    ///
+    pub fn payload_as_string(&self) -> Option<String> {
+        self.payload
+            .as_ref()
+            .map(|payload| String::from_utf8_lossy(payload).to_string())
+    }
+
+    /// payload_preview
+    ///
+    /// Safe payload summary for logging that never splits a
+    /// UTF-8 character
+    ///
+    /// # Arguments
+    ///
+    /// * `max_chars` - max number of characters to include
+    ///
+    pub fn payload_preview(&self, max_chars: usize) -> String {
+        match &self.payload {
+            Some(payload) => String::from_utf8_lossy(payload)
+                .chars()
+                .take(max_chars)
+                .collect(),
+            None => "TOMBSTONE".to_string(),
         }
     }
+
+    /// size_in_bytes
+    ///
+    /// Approximate number of bytes this message uses in the
+    /// lockable work vec for enforcing ``KAFKA_QUEUE_MAX_BYTES``
+    ///
+    pub fn size_in_bytes(&self) -> usize {
+        let headers_len: usize = self
+            .headers
+            .iter()
+            .map(|header| header.size_in_bytes())
+            .sum();
+        self.topic.len()
+            + self.key.as_ref().map_or(0, |key| key.len())
+            + self.payload.as_ref().map_or(0, |payload| payload.len())
+            + headers_len
+            + self
+                .transaction
+                .as_ref()
+                .map_or(0, |transaction| transaction.size_in_bytes())
+    }
 }
 
 impl std::fmt::Debug for KafkaPublishMessage {
@@ -105,10 +357,16 @@ impl std::fmt::Debug for KafkaPublishMessage {
                 "DEBUG KafkaPublishMessage \
                 type={:?} \
                 topic={} \
-                key={} \
+                partition={:?} \
+                key={:?} \
                 headers={:?} \
-                payload={}",
-                self.msg_type, self.topic, self.key, self.headers, self.payload
+                payload={:?}",
+                self.msg_type,
+                self.topic,
+                self.partition,
+                self.key_as_string(),
+                self.headers,
+                self.payload_as_string()
             )
         } else {
             write!(
@@ -116,9 +374,14 @@ impl std::fmt::Debug for KafkaPublishMessage {
                 "DEBUG SENSITIVE KafkaPublishMessage \
                 type={:?} \
                 topic={} \
-                key={} \
+                partition={:?} \
+                key={:?} \
                 headers={:?}",
-                self.msg_type, self.topic, self.key, self.headers
+                self.msg_type,
+                self.topic,
+                self.partition,
+                self.key_as_string(),
+                self.headers
             )
         }
     }
@@ -132,10 +395,16 @@ impl std::fmt::Display for KafkaPublishMessage {
                 "KafkaPublishMessage \
                 type={:?} \
                 topic={} \
-                key={} \
+                partition={:?} \
+                key={:?} \
                 headers={:?} \
-                payload={}",
-                self.msg_type, self.topic, self.key, self.headers, self.payload,
+                payload={:?}",
+                self.msg_type,
+                self.topic,
+                self.partition,
+                self.key_as_string(),
+                self.headers,
+                self.payload_as_string()
             )
         } else {
             write!(
@@ -143,9 +412,14 @@ impl std::fmt::Display for KafkaPublishMessage {
                 "SENSITIVE KafkaPublishMessage \
                 type={:?} \
                 topic={} \
-                key={} \
+                partition={:?} \
+                key={:?} \
                 headers={:?}",
-                self.msg_type, self.topic, self.key, self.headers
+                self.msg_type,
+                self.topic,
+                self.partition,
+                self.key_as_string(),
+                self.headers
             )
         }
     }
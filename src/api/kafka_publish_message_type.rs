@@ -15,6 +15,9 @@
 /// - ``Sensitive`` - when a thread encounters this message type
 /// it will not verbosely log the message payload and is processed like
 /// a normal ``Data`` message type
+/// - ``Transaction`` - a group of ``Data`` or ``Sensitive`` messages
+/// in ``KafkaPublishMessage.transaction`` that one thread commits
+/// atomically (requires ``KAFKA_TRANSACTIONAL_ID_PREFIX``)
 ///
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum KafkaPublishMessageType {
@@ -23,6 +26,7 @@ pub enum KafkaPublishMessageType {
     LogBrokerDetails,
     LogBrokerTopicDetails,
     Sensitive,
+    Transaction,
 }
 
 // https://users.rust-lang.org/t/derive-default-for-enum-non-only-struct/44046
@@ -0,0 +1,25 @@
+//! enum for the result of adding messages to the bounded
+//! lockable work vec
+//!
+use crate::api::kafka_publish_message::KafkaPublishMessage;
+
+/// KafkaQueueAddStatus
+///
+/// Returned by
+/// [`add_messages_to_bounded_work_vec`](crate::api::add_messages_to_bounded_work_vec::add_messages_to_bounded_work_vec)
+///
+/// - ``Added`` - the messages were added with the updated number
+/// of messages in the work vec and the number of messages dropped
+/// by the ``DropOldest`` or ``DropNewest`` overflow policies
+/// - ``Full`` - the work vec is full and the ``Block`` or ``Reject``
+/// overflow policy returned the messages back to the caller (also
+/// returned by ``DropOldest`` when the work vec only holds messages
+/// it cannot drop)
+///
+pub enum KafkaQueueAddStatus {
+    Added {
+        num_in_vec: usize,
+        num_dropped: usize,
+    },
+    Full(Vec<KafkaPublishMessage>),
+}
@@ -0,0 +1,61 @@
+//! enum for the supported overflow policies when the
+//! lockable work vec reaches its configured capacity
+//!
+
+/// KafkaQueueOverflowPolicy
+///
+/// Supported behaviors for adding messages to a full
+/// lockable work vec (set with ``KAFKA_QUEUE_OVERFLOW_POLICY``)
+///
+/// - ``Block`` - wait until the worker threads drain enough
+/// messages to make space (default)
+/// - ``Reject`` - return an error to the caller without adding
+/// the new messages
+/// - ``DropOldest`` - remove the oldest queued messages to make
+/// space for the new messages
+/// - ``DropNewest`` - keep the queued messages and drop the new
+/// messages that do not fit (control messages and transactions are
+/// always added)
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KafkaQueueOverflowPolicy {
+    Block,
+    Reject,
+    DropOldest,
+    DropNewest,
+}
+
+impl Default for KafkaQueueOverflowPolicy {
+    fn default() -> Self {
+        KafkaQueueOverflowPolicy::Block
+    }
+}
+
+impl std::str::FromStr for KafkaQueueOverflowPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "block" => Ok(KafkaQueueOverflowPolicy::Block),
+            "reject" => Ok(KafkaQueueOverflowPolicy::Reject),
+            "drop_oldest" => Ok(KafkaQueueOverflowPolicy::DropOldest),
+            "drop_newest" => Ok(KafkaQueueOverflowPolicy::DropNewest),
+            _ => Err(format!(
+                "unsupported queue overflow policy={s} \
+                please use: block, reject, drop_oldest or drop_newest"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for KafkaQueueOverflowPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let policy = match self {
+            KafkaQueueOverflowPolicy::Block => "block",
+            KafkaQueueOverflowPolicy::Reject => "reject",
+            KafkaQueueOverflowPolicy::DropOldest => "drop_oldest",
+            KafkaQueueOverflowPolicy::DropNewest => "drop_newest",
+        };
+        write!(f, "{policy}")
+    }
+}
@@ -0,0 +1,145 @@
+//! enums for the supported kafka security protocols and
+//! SASL mechanisms
+//!
+
+/// KafkaSecurityProtocol
+///
+/// Supported ``security.protocol`` values (set with
+/// ``KAFKA_SECURITY_PROTOCOL``)
+///
+/// - ``Plaintext`` - no encryption and no authentication
+/// - ``Ssl`` - TLS encryption with optional mTLS authentication
+/// - ``SaslPlaintext`` - SASL authentication without encryption
+/// - ``SaslSsl`` - SASL authentication with TLS encryption
+///
+/// If not set, ``Ssl`` is used when any of the tls assets are
+/// set, otherwise ``Plaintext``
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KafkaSecurityProtocol {
+    Plaintext,
+    Ssl,
+    SaslPlaintext,
+    SaslSsl,
+}
+
+impl Default for KafkaSecurityProtocol {
+    fn default() -> Self {
+        KafkaSecurityProtocol::Plaintext
+    }
+}
+
+impl KafkaSecurityProtocol {
+    /// as_rdkafka_str
+    ///
+    /// ``librdkafka`` value for ``security.protocol``
+    ///
+    pub fn as_rdkafka_str(&self) -> &'static str {
+        match self {
+            KafkaSecurityProtocol::Plaintext => "PLAINTEXT",
+            KafkaSecurityProtocol::Ssl => "SSL",
+            KafkaSecurityProtocol::SaslPlaintext => "SASL_PLAINTEXT",
+            KafkaSecurityProtocol::SaslSsl => "SASL_SSL",
+        }
+    }
+
+    /// uses_ssl
+    ///
+    /// ``true`` if the protocol encrypts with TLS
+    ///
+    pub fn uses_ssl(&self) -> bool {
+        *self == KafkaSecurityProtocol::Ssl
+            || *self == KafkaSecurityProtocol::SaslSsl
+    }
+
+    /// uses_sasl
+    ///
+    /// ``true`` if the protocol authenticates with SASL
+    ///
+    pub fn uses_sasl(&self) -> bool {
+        *self == KafkaSecurityProtocol::SaslPlaintext
+            || *self == KafkaSecurityProtocol::SaslSsl
+    }
+}
+
+impl std::str::FromStr for KafkaSecurityProtocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().replace('-', "_").as_str() {
+            "PLAINTEXT" => Ok(KafkaSecurityProtocol::Plaintext),
+            "SSL" => Ok(KafkaSecurityProtocol::Ssl),
+            "SASL_PLAINTEXT" => Ok(KafkaSecurityProtocol::SaslPlaintext),
+            "SASL_SSL" => Ok(KafkaSecurityProtocol::SaslSsl),
+            _ => Err(format!(
+                "unsupported security protocol={s} \
+                please use: PLAINTEXT, SSL, SASL_PLAINTEXT or SASL_SSL"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for KafkaSecurityProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_rdkafka_str())
+    }
+}
+
+/// KafkaSaslMechanism
+///
+/// Supported ``sasl.mechanism`` values (set with
+/// ``KAFKA_SASL_MECHANISM``)
+///
+/// - ``Plain`` - username and password
+/// - ``ScramSha256`` - username and password with SCRAM-SHA-256
+/// - ``ScramSha512`` - username and password with SCRAM-SHA-512
+/// - ``OAuthBearer`` - tokens from a
+/// [`KafkaOAuthTokenProvider`](crate::api::kafka_oauth_token_provider::KafkaOAuthTokenProvider)
+/// or from ``librdkafka`` without a provider
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KafkaSaslMechanism {
+    Plain,
+    ScramSha256,
+    ScramSha512,
+    OAuthBearer,
+}
+
+impl KafkaSaslMechanism {
+    /// as_rdkafka_str
+    ///
+    /// ``librdkafka`` value for ``sasl.mechanism``
+    ///
+    pub fn as_rdkafka_str(&self) -> &'static str {
+        match self {
+            KafkaSaslMechanism::Plain => "PLAIN",
+            KafkaSaslMechanism::ScramSha256 => "SCRAM-SHA-256",
+            KafkaSaslMechanism::ScramSha512 => "SCRAM-SHA-512",
+            KafkaSaslMechanism::OAuthBearer => "OAUTHBEARER",
+        }
+    }
+}
+
+impl std::str::FromStr for KafkaSaslMechanism {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().replace('_', "-").as_str() {
+            "PLAIN" => Ok(KafkaSaslMechanism::Plain),
+            "SCRAM-SHA-256" => Ok(KafkaSaslMechanism::ScramSha256),
+            "SCRAM-SHA-512" => Ok(KafkaSaslMechanism::ScramSha512),
+            "OAUTHBEARER" => Ok(KafkaSaslMechanism::OAuthBearer),
+            _ => Err(format!(
+                "unsupported sasl mechanism={s} \
+                please use: PLAIN, SCRAM-SHA-256, SCRAM-SHA-512 \
+                or OAUTHBEARER"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for KafkaSaslMechanism {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_rdkafka_str())
+    }
+}
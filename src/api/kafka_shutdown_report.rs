@@ -0,0 +1,56 @@
+//! class definition for
+//! [`KafkaShutdownReport`](crate::api::kafka_shutdown_report::KafkaShutdownReport)
+//! which summarizes a graceful threadpool shutdown
+//!
+
+/// KafkaShutdownReport
+///
+/// Returned by
+/// [`shutdown_and_wait`](crate::kafka_publisher::KafkaPublisher::shutdown_and_wait)
+/// once every worker thread exits
+///
+/// * `num_delivered` - number of messages kafka acknowledged
+/// since the threadpool started
+/// * `num_failed` - number of messages that failed with a fatal
+/// error or exhausted the
+/// [`KafkaRetryPolicy`](crate::config::kafka_retry_policy::KafkaRetryPolicy)
+/// since the threadpool started
+/// * `num_abandoned` - number of messages that were still queued
+/// or failing when the shutdown deadline expired
+/// * `num_workers` - number of worker threads that were joined
+/// * `num_aborted` - number of worker threads aborted because they
+/// did not exit after the deadline (their in-flight messages are
+/// dropped without being counted as abandoned)
+/// * `timed_out` - ``true`` if the deadline expired before the
+/// worker threads published every queued message
+///
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct KafkaShutdownReport {
+    pub num_delivered: usize,
+    pub num_failed: usize,
+    pub num_abandoned: usize,
+    pub num_workers: usize,
+    pub num_aborted: usize,
+    pub timed_out: bool,
+}
+
+impl std::fmt::Display for KafkaShutdownReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "KafkaShutdownReport \
+            delivered={} \
+            failed={} \
+            abandoned={} \
+            workers={} \
+            aborted={} \
+            timed_out={}",
+            self.num_delivered,
+            self.num_failed,
+            self.num_abandoned,
+            self.num_workers,
+            self.num_aborted,
+            self.timed_out
+        )
+    }
+}
@@ -0,0 +1,164 @@
+//! enum for all errors returned by the ``kafka_threadpool``
+//!
+use rdkafka::error::KafkaError;
+use rdkafka::types::RDKafkaErrorCode;
+
+use crate::api::kafka_delivery_error::KafkaDeliveryError;
+
+/// KafkaThreadpoolError
+///
+/// Supported errors for every fallible ``kafka_threadpool`` API
+///
+/// - ``ConfigInvalid`` - a configuration value (environment
+/// variable or builder setting) is missing or invalid
+/// - ``TlsAssetMissing`` - a configured mTLS key, certificate
+/// or CA file does not exist
+/// - ``NoMessages`` - the caller tried to add an empty Vec of
+/// messages to the work vec
+/// - ``QueueFull`` - the work vec is at its configured capacity
+/// and the overflow policy rejected the messages
+/// - ``PoolShutdown`` - the threadpool is shutting down and is not
+/// accepting new messages
+/// - ``LockPoisoned`` - a thread panicked while holding the work
+/// vec ``Mutex``
+/// - ``Broker`` - kafka or ``rdkafka`` returned an error with the
+/// optional ``rdkafka`` error code
+/// - ``Delivery`` - a message failed to deliver
+/// - ``DeadLetter`` - a dead-letter sink failed to store a message
+/// that could not be published
+/// - ``Serialization`` - a ``PayloadSerializer`` failed to encode
+/// a message payload (requires the ``serde`` feature)
+/// - ``SchemaRegistry`` - the schema registry rejected a schema or
+/// was offline without a static schema id for the subject
+/// (requires the ``schema-registry`` feature)
+/// - ``Admin`` - kafka rejected a topic admin operation
+/// (create, delete, add partitions or alter configs)
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KafkaThreadpoolError {
+    ConfigInvalid {
+        key: String,
+        reason: String,
+    },
+    TlsAssetMissing {
+        key: String,
+        path: String,
+    },
+    NoMessages,
+    QueueFull {
+        num_msgs: usize,
+        max_msgs: usize,
+        max_bytes: usize,
+    },
+    PoolShutdown,
+    LockPoisoned(String),
+    Broker {
+        code: Option<RDKafkaErrorCode>,
+        reason: String,
+    },
+    Delivery(KafkaDeliveryError),
+    DeadLetter {
+        sink: String,
+        reason: String,
+    },
+    Serialization {
+        content_type: String,
+        reason: String,
+    },
+    SchemaRegistry {
+        subject: String,
+        reason: String,
+    },
+    Admin {
+        name: String,
+        reason: String,
+    },
+}
+
+impl KafkaThreadpoolError {
+    /// config_invalid
+    ///
+    /// Helper for building a ``ConfigInvalid`` error
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - name of the invalid configuration value
+    /// (environment variable name)
+    /// * `reason` - why the value is invalid
+    ///
+    pub fn config_invalid(key: &str, reason: &str) -> Self {
+        KafkaThreadpoolError::ConfigInvalid {
+            key: key.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl From<KafkaError> for KafkaThreadpoolError {
+    fn from(err: KafkaError) -> Self {
+        KafkaThreadpoolError::Broker {
+            code: err.rdkafka_error_code(),
+            reason: err.to_string(),
+        }
+    }
+}
+
+impl From<KafkaDeliveryError> for KafkaThreadpoolError {
+    fn from(err: KafkaDeliveryError) -> Self {
+        KafkaThreadpoolError::Delivery(err)
+    }
+}
+
+impl std::fmt::Display for KafkaThreadpoolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            KafkaThreadpoolError::ConfigInvalid { key, reason } => {
+                write!(f, "invalid config {key} - {reason}")
+            }
+            KafkaThreadpoolError::TlsAssetMissing { key, path } => {
+                write!(f, "missing tls asset {key}={path}")
+            }
+            KafkaThreadpoolError::NoMessages => write!(f, "no msgs to add"),
+            KafkaThreadpoolError::QueueFull {
+                num_msgs,
+                max_msgs,
+                max_bytes,
+            } => write!(
+                f,
+                "work vec full - rejected {num_msgs} msgs \
+                max_msgs={max_msgs} max_bytes={max_bytes}"
+            ),
+            KafkaThreadpoolError::PoolShutdown => {
+                write!(f, "threadpool is shut down")
+            }
+            KafkaThreadpoolError::LockPoisoned(reason) => {
+                write!(f, "failed to get lock on work vec with err={reason}")
+            }
+            KafkaThreadpoolError::Broker { code, reason } => {
+                write!(f, "kafka error code={code:?} reason={reason}")
+            }
+            KafkaThreadpoolError::Delivery(err) => {
+                write!(f, "delivery failed with err={err}")
+            }
+            KafkaThreadpoolError::DeadLetter { sink, reason } => {
+                write!(f, "dead-letter sink {sink} failed with err={reason}")
+            }
+            KafkaThreadpoolError::Serialization {
+                content_type,
+                reason,
+            } => write!(
+                f,
+                "failed to serialize payload as {content_type} \
+                with err={reason}"
+            ),
+            KafkaThreadpoolError::SchemaRegistry { subject, reason } => {
+                write!(f, "schema registry subject={subject} err={reason}")
+            }
+            KafkaThreadpoolError::Admin { name, reason } => {
+                write!(f, "admin operation failed for {name} with err={reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for KafkaThreadpoolError {}
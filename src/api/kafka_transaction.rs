@@ -0,0 +1,74 @@
+//! class definition for a group of
+//! [`KafkaPublishMessage`](crate::api::kafka_publish_message::KafkaPublishMessage)
+//! messages that are committed atomically
+//!
+use crate::api::kafka_delivery_error::KafkaDeliveryError;
+use crate::api::kafka_publish_message::KafkaPublishMessage;
+use crate::api::kafka_transaction_notifier::KafkaTransactionNotifier;
+
+/// KafkaTransaction
+///
+/// Messages published by one worker thread between
+/// ``begin_transaction`` and ``commit_transaction``. Either every
+/// message is committed or the transaction is aborted.
+///
+/// * `msgs` - ``Data`` or ``Sensitive`` messages in publish order
+/// * `notifier` - optional - reports the commit or abort back to
+/// the caller
+///
+#[derive(Clone, Default)]
+pub struct KafkaTransaction {
+    pub msgs: Vec<KafkaPublishMessage>,
+    pub notifier: Option<KafkaTransactionNotifier>,
+}
+
+impl KafkaTransaction {
+    /// new
+    ///
+    /// Create a transaction for a group of messages
+    ///
+    /// # Arguments
+    ///
+    /// * `msgs` - messages to commit atomically
+    /// * `notifier` - optional - sending half of the caller's
+    /// [`KafkaTransactionFuture`](crate::api::kafka_transaction_notifier::KafkaTransactionFuture)
+    ///
+    pub fn new(
+        msgs: Vec<KafkaPublishMessage>,
+        notifier: Option<KafkaTransactionNotifier>,
+    ) -> Self {
+        KafkaTransaction { msgs, notifier }
+    }
+
+    /// notify_dropped
+    ///
+    /// Tell the caller and each message's delivery notifier that
+    /// the transaction was dropped before it was published
+    /// (threadpool shutdown deadline expired, etc.)
+    ///
+    pub fn notify_dropped(&self) {
+        for msg in self.msgs.iter() {
+            if let Some(delivery) = &msg.delivery {
+                delivery.notify(Err(KafkaDeliveryError::Dropped));
+            }
+        }
+        if let Some(notifier) = &self.notifier {
+            notifier.notify(Err(KafkaDeliveryError::Dropped));
+        }
+    }
+
+    /// size_in_bytes
+    ///
+    /// Approximate number of bytes all messages use in the
+    /// lockable work vec
+    ///
+    pub fn size_in_bytes(&self) -> usize {
+        self.msgs.iter().map(|msg| msg.size_in_bytes()).sum()
+    }
+}
+
+impl std::fmt::Debug for KafkaTransaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "KafkaTransaction msgs={:?}", self.msgs)
+    }
+}
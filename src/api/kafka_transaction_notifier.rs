@@ -0,0 +1,143 @@
+//! Transaction outcomes where the
+//! [`KafkaTransactionNotifier`](crate::api::kafka_transaction_notifier::KafkaTransactionNotifier)
+//! travels with the
+//! [`KafkaTransaction`](crate::api::kafka_transaction::KafkaTransaction)
+//! through the lockable work vec and the caller awaits the matching
+//! [`KafkaTransactionFuture`](crate::api::kafka_transaction_notifier::KafkaTransactionFuture)
+//!
+//! Example for waiting on a committed transaction:
+//!
+//! ```rust
+//! let transaction = my_kafka_publisher
+//!     .add_transaction_msgs(msgs)
+//!     .await
+//!     .unwrap();
+//! match transaction.await {
+//!     Ok(reports) => println!("committed {} msgs", reports.len()),
+//!     Err(e) => println!("aborted with err={e}"),
+//! }
+//! ```
+//!
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+
+use tokio::sync::oneshot;
+
+use crate::api::kafka_delivery_error::KafkaDeliveryError;
+use crate::api::kafka_delivery_report::KafkaDeliveryReport;
+
+/// KafkaTransactionResult
+///
+/// Outcome for a group of messages published in a single
+/// transaction. ``Ok`` holds one
+/// [`KafkaDeliveryReport`](crate::api::kafka_delivery_report::KafkaDeliveryReport)
+/// per message (in the submitted order) once the transaction is
+/// committed and ``Err`` means the transaction was aborted so no
+/// message is visible to ``read_committed`` consumers.
+///
+pub type KafkaTransactionResult =
+    Result<Vec<KafkaDeliveryReport>, KafkaDeliveryError>;
+
+/// KafkaTransactionNotifier
+///
+/// Cloneable sending half of a transaction outcome. Only the
+/// first call to
+/// [`notify`](crate::api::kafka_transaction_notifier::KafkaTransactionNotifier::notify)
+/// reaches the caller. If every clone is dropped without notifying,
+/// the caller receives ``KafkaDeliveryError::Dropped``.
+///
+#[derive(Default, Clone)]
+pub struct KafkaTransactionNotifier {
+    sender: Arc<Mutex<Option<oneshot::Sender<KafkaTransactionResult>>>>,
+}
+
+impl KafkaTransactionNotifier {
+    /// new
+    ///
+    /// Create a connected notifier and future pair
+    ///
+    /// # Returns
+    ///
+    /// ``(KafkaTransactionNotifier, KafkaTransactionFuture)``
+    ///
+    pub fn new() -> (Self, KafkaTransactionFuture) {
+        let (tx, rx) = oneshot::channel();
+        (
+            KafkaTransactionNotifier {
+                sender: Arc::new(Mutex::new(Some(tx))),
+            },
+            KafkaTransactionFuture { receiver: rx },
+        )
+    }
+
+    /// notify
+    ///
+    /// Send the transaction outcome to the waiting caller
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - delivery reports for a committed transaction
+    /// or the error that aborted it
+    ///
+    /// # Returns
+    ///
+    /// ``true`` if the result was sent and ``false`` if the caller
+    /// already received a result or stopped waiting
+    ///
+    pub fn notify(&self, result: KafkaTransactionResult) -> bool {
+        let sender = match self.sender.lock() {
+            Ok(mut locked_sender) => locked_sender.take(),
+            Err(_) => None,
+        };
+        match sender {
+            Some(tx) => tx.send(result).is_ok(),
+            None => false,
+        }
+    }
+}
+
+/// KafkaTransactionFuture
+///
+/// Receiving half of a transaction outcome that resolves with
+/// the delivery reports once the transaction is committed or a
+/// [`KafkaDeliveryError`](crate::api::kafka_delivery_error::KafkaDeliveryError)
+/// if the transaction is aborted or dropped
+///
+pub struct KafkaTransactionFuture {
+    receiver: oneshot::Receiver<KafkaTransactionResult>,
+}
+
+impl KafkaTransactionFuture {
+    /// failed
+    ///
+    /// Create an already-resolved future for transactions that
+    /// were never queued
+    ///
+    /// # Arguments
+    ///
+    /// * `err` - reason the transaction was not queued
+    ///
+    pub fn failed(err: KafkaDeliveryError) -> Self {
+        let (notifier, transaction) = KafkaTransactionNotifier::new();
+        notifier.notify(Err(err));
+        transaction
+    }
+}
+
+impl Future for KafkaTransactionFuture {
+    type Output = KafkaTransactionResult;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Self::Output> {
+        Pin::new(&mut self.receiver).poll(cx).map(|res| match res {
+            Ok(transaction_result) => transaction_result,
+            Err(_) => Err(KafkaDeliveryError::Dropped),
+        })
+    }
+}
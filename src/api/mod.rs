@@ -1,10 +1,29 @@
 //! Commonly-used API function(s), struct(s) and enum(s)
 //!
+pub mod add_messages_to_bounded_work_vec;
 pub mod add_messages_to_locked_work_vec;
 pub mod build_kafka_client_config;
 pub mod build_kafka_publish_message;
+pub mod build_rdkafka_client_config;
 pub mod drain_messages_from_locked_work_vec;
 pub mod get_kafka_consumer;
 pub mod get_kafka_producer;
+pub mod get_kafka_transactional_producer;
+pub mod get_message_shard;
+pub mod kafka_client_context;
+pub mod kafka_delivery_error;
+pub mod kafka_delivery_notifier;
+pub mod kafka_delivery_report;
+pub mod kafka_header;
+pub mod kafka_oauth_client_context;
+pub mod kafka_oauth_token_provider;
+pub mod kafka_ordering_mode;
 pub mod kafka_publish_message;
 pub mod kafka_publish_message_type;
+pub mod kafka_queue_add_status;
+pub mod kafka_queue_overflow_policy;
+pub mod kafka_security_protocol;
+pub mod kafka_shutdown_report;
+pub mod kafka_threadpool_error;
+pub mod kafka_transaction;
+pub mod kafka_transaction_notifier;
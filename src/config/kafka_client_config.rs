@@ -1,5 +1,7 @@
 //! Static configuration for the threadpool where
-//! the values are read from environment variables
+//! the values are built programmatically with
+//! [`KafkaClientConfig::builder()`](crate::config::kafka_client_config::KafkaClientConfig::builder)
+//! or read from environment variables
 //! at startup with supported environment variables:
 //! | Environment Variable Name        | Purpose / Value                                |
 //! | -------------------------------- | ---------------------------------------------- |
@@ -7,19 +9,67 @@
 //! | KAFKA_LOG_LABEL                  | tracking label that shows up in all crate logs |
 //! | KAFKA_BROKERS                    | comma-delimited list of brokers (``host1:port,host2:port,host3:port``) |
 //! | KAFKA_TOPICS                     | comma-delimited list of supported topics |
-//! | KAFKA_PUBLISH_RETRY_INTERVAL_SEC | number of seconds to sleep before each publish retry |
-//! | KAFKA_PUBLISH_IDLE_INTERVAL_SEC  | number of seconds to sleep if there are no message to process |
+//! | KAFKA_ENSURE_TOPICS              | optional - set to ``true`` or ``1`` to create any missing ``KAFKA_TOPICS`` (and declared ``KafkaTopicSpec`` topics) when the threadpool starts (defaults to ``false``) |
+//! | KAFKA_TOPIC_NUM_PARTITIONS       | optional - number of partitions for created topics without a ``KafkaTopicSpec`` (defaults to ``-1`` which uses the broker default) |
+//! | KAFKA_TOPIC_REPLICATION_FACTOR   | optional - replication factor for created topics without a ``KafkaTopicSpec`` (defaults to ``-1`` which uses the broker default) |
+//! | KAFKA_ADMIN_TIMEOUT_SEC          | optional - max number of seconds to wait for each topic admin operation (defaults to ``30``) |
+//! | KAFKA_PUBLISH_RETRY_INTERVAL_SEC | number of seconds to sleep before the first publish retry (the backoff grows with each retry) |
+//! | KAFKA_PUBLISH_RETRY_MAX_INTERVAL_SEC | optional - max number of seconds to sleep between publish retries (defaults to ``30``) |
+//! | KAFKA_PUBLISH_RETRY_MULTIPLIER   | optional - backoff growth factor applied after each publish retry (defaults to ``2.0``) |
+//! | KAFKA_PUBLISH_RETRY_JITTER       | optional - fraction between ``0.0`` and ``1.0`` of random variation applied to each backoff (defaults to ``0.2``) |
+//! | KAFKA_PUBLISH_RETRY_MAX_ATTEMPTS | optional - max number of publish attempts per message before giving up (defaults to ``10`` and ``0`` retries until the max elapsed time) |
+//! | KAFKA_PUBLISH_RETRY_MAX_ELAPSED_SEC | optional - max number of seconds spent publishing a message before giving up (defaults to ``0`` which is unbounded) |
+//! | KAFKA_DLQ_TOPIC                  | optional - dead-letter topic for messages that fail with a fatal error or exhaust the retry policy (the original topic, error and attempt count are added as headers) |
+//! | KAFKA_DLQ_FILE                   | optional - append-only file for messages that fail with a fatal error or exhaust the retry policy (``Sensitive`` keys, headers and payloads are redacted) |
+//! | KAFKA_PUBLISH_IDLE_INTERVAL_SEC  | max number of seconds an idle thread waits before rechecking the work vec (threads wake immediately when messages are added) |
 //! | KAFKA_NUM_THREADS                | number of threads for the threadpool |
+//! | KAFKA_PUBLISH_BATCH_SIZE         | optional - max number of messages each thread drains from the work vec and publishes concurrently (defaults to ``10``) |
 //! | KAFKA_TLS_CLIENT_KEY             | optional - path to the kafka mTLS key |
 //! | KAFKA_TLS_CLIENT_CERT            | optional - path to the kafka mTLS certificate |
 //! | KAFKA_TLS_CLIENT_CA              | optional - path to the kafka mTLS certificate authority (CA) |
+//! | KAFKA_SECURITY_PROTOCOL          | optional - ``PLAINTEXT``, ``SSL``, ``SASL_PLAINTEXT`` or ``SASL_SSL`` (defaults to ``SSL`` if any tls asset is set otherwise ``PLAINTEXT``) |
+//! | KAFKA_SASL_MECHANISM             | optional - ``PLAIN``, ``SCRAM-SHA-256``, ``SCRAM-SHA-512`` or ``OAUTHBEARER`` for the ``SASL_*`` security protocols (``OAUTHBEARER`` without a ``KafkaOAuthTokenProvider`` uses ``librdkafka``'s own tokens, e.g. ``KAFKA_RDKAFKA_SASL_OAUTHBEARER_METHOD=oidc``) |
+//! | KAFKA_SASL_USERNAME              | optional - username for the ``PLAIN`` and ``SCRAM`` SASL mechanisms |
+//! | KAFKA_SASL_PASSWORD              | optional - password for the ``PLAIN`` and ``SCRAM`` SASL mechanisms |
+//! | KAFKA_SASL_OAUTHBEARER_CONFIG    | optional - ``sasl.oauthbearer.config`` passed to the ``OAUTHBEARER`` token provider or ``librdkafka``'s unsecured JWT builder |
 //! | KAFKA_METADATA_COUNT_MSG_OFFSETS | optional - set to anything but ``true`` to bypass counting the offsets |
+//! | KAFKA_METADATA_TIMEOUT_SEC       | optional - max number of seconds to wait for the cluster metadata (defaults to ``30``) |
+//! | KAFKA_METADATA_WATERMARK_TIMEOUT_SEC | optional - max number of seconds to wait for each partition's watermarks when counting messages (defaults to ``1``) |
+//! | KAFKA_METADATA_WATERMARK_CONCURRENCY | optional - max number of partition watermarks fetched at the same time when counting messages (defaults to ``1``) |
+//! | KAFKA_QUEUE_MAX_MSGS             | optional - max number of messages in the work vec (``0`` is unbounded, split evenly across the worker threads' work vecs with the ``key`` and ``topic_partition`` ordering modes) |
+//! | KAFKA_QUEUE_MAX_BYTES            | optional - max number of bytes in the work vec (``0`` is unbounded, split evenly across the worker threads' work vecs with the ``key`` and ``topic_partition`` ordering modes) |
+//! | KAFKA_QUEUE_OVERFLOW_POLICY      | optional - ``block``, ``reject``, ``drop_oldest`` or ``drop_newest`` when the work vec is full |
+//! | KAFKA_ORDERING_MODE              | optional - ``unordered``, ``key`` or ``topic_partition`` where ``key`` and ``topic_partition`` shard messages to per-worker work vecs so each key (or partition) is published in enqueue order (a transaction follows its first keyed message) (defaults to ``unordered``) |
+//! | KAFKA_ENABLE_IDEMPOTENCE         | optional - set to ``true`` or ``1`` to start each producer with ``enable.idempotence=true`` so retries cannot duplicate or reorder messages (defaults to ``false``) |
+//! | KAFKA_TRANSACTIONAL_ID_PREFIX    | optional - enables the transactional mode for ``KafkaPublisher::add_transaction_msgs`` where each thread uses the unique ``transactional.id``: ``<prefix>-<thread number>`` (implies idempotence) |
+//! | KAFKA_STATISTICS_INTERVAL_MS     | optional - how often in milliseconds ``librdkafka`` emits statistics for each worker thread's producer that are available with ``KafkaPublisher::stats()`` (defaults to ``0`` which disables statistics) |
+//! | KAFKA_HEALTH_DEGRADED_QUEUE_DEPTH | optional - number of queued messages that makes ``KafkaPublisher::health()`` report ``Degraded`` (defaults to ``0`` which disables the check) |
+//! | KAFKA_HEALTH_UNHEALTHY_QUEUE_DEPTH | optional - number of queued messages that makes ``KafkaPublisher::health()`` report ``Unhealthy`` (defaults to ``0`` which disables the check) |
+//! | KAFKA_HEALTH_DEGRADED_DELIVERY_SEC | optional - seconds without a successful delivery while there are messages to publish that makes ``KafkaPublisher::health()`` report ``Degraded`` (defaults to ``60``, ``0`` disables the check) |
+//! | KAFKA_HEALTH_UNHEALTHY_DELIVERY_SEC | optional - seconds without a successful delivery while there are messages to publish that makes ``KafkaPublisher::health()`` report ``Unhealthy`` (defaults to ``300``, ``0`` disables the check) |
+//! | KAFKA_HEALTH_BROKER_DOWN_SEC     | optional - seconds an all brokers down event from ``librdkafka`` counts in ``KafkaPublisher::health()`` without another connection failure (defaults to ``60``) |
+//! | KAFKA_RDKAFKA_<PROP>             | optional - pass-through ``librdkafka`` property for the producer and consumer where ``<PROP>`` is lowercased and ``_`` becomes ``.`` (``KAFKA_RDKAFKA_LINGER_MS=5`` sets ``linger.ms=5`` and ``KAFKA_RDKAFKA_ENABLE_IDEMPOTENCE`` works like ``KAFKA_ENABLE_IDEMPOTENCE``) |
 //!
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
 use log::info;
 use log::trace;
 
+use crate::admin::kafka_topic_spec::KafkaTopicSpec;
+use crate::api::kafka_oauth_token_provider::KafkaOAuthTokenProvider;
+use crate::api::kafka_ordering_mode::KafkaOrderingMode;
+use crate::api::kafka_queue_overflow_policy::KafkaQueueOverflowPolicy;
+use crate::api::kafka_security_protocol::KafkaSaslMechanism;
+use crate::api::kafka_security_protocol::KafkaSecurityProtocol;
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
+use crate::config::kafka_client_config_builder::KafkaClientConfigBuilder;
+use crate::config::kafka_health_thresholds::KafkaHealthThresholds;
+use crate::config::kafka_retry_policy::KafkaRetryPolicy;
+use crate::dlq::kafka_dead_letter_sink::KafkaDeadLetterSink;
+use crate::stats::kafka_stats_handler::KafkaStatsHandler;
+
 /// KafkaClientConfig
 ///
 /// Kafka client configuration holding connectivity and static
@@ -31,16 +81,196 @@ pub struct KafkaClientConfig {
     pub is_enabled: bool,
     pub broker_list: Vec<String>,
     pub publish_topics: HashMap<String, String>,
+    pub ensure_topics: bool,
+    pub topic_specs: Vec<KafkaTopicSpec>,
+    pub default_num_partitions: i32,
+    pub default_replication_factor: i32,
+    pub admin_timeout: Duration,
     pub num_threads: u8,
-    pub retry_sleep_sec: u64,
+    pub publish_batch_size: usize,
+    pub retry_policy: KafkaRetryPolicy,
     pub idle_sleep_sec: u64,
     pub tls_key: String,
     pub tls_cert: String,
     pub tls_ca: String,
+    pub max_queue_msgs: usize,
+    pub max_queue_bytes: usize,
+    pub queue_overflow_policy: KafkaQueueOverflowPolicy,
+    pub ordering_mode: KafkaOrderingMode,
+    pub enable_idempotence: bool,
+    pub transactional_id_prefix: String,
+    pub statistics_interval_ms: u64,
+    pub metadata_timeout: Duration,
+    pub watermark_timeout: Duration,
+    pub watermark_concurrency: usize,
+    pub rdkafka_properties: HashMap<String, String>,
+    pub security_protocol: KafkaSecurityProtocol,
+    pub sasl_mechanism: Option<KafkaSaslMechanism>,
+    pub sasl_username: String,
+    pub sasl_password: String,
+    pub sasl_oauthbearer_config: String,
+    pub oauth_token_provider: Option<Arc<dyn KafkaOAuthTokenProvider>>,
+    pub dead_letter_sinks: Vec<KafkaDeadLetterSink>,
+    pub stats_handler: Option<Arc<dyn KafkaStatsHandler>>,
+    pub health_thresholds: KafkaHealthThresholds,
+}
+
+/// parse_interval
+///
+/// Internal helper for converting a number of seconds (as a float
+/// string) from an environment variable into a ``Duration``
+///
+/// # Arguments
+///
+/// * `key` - environment variable name for error reporting
+/// * `value` - environment variable value
+///
+fn parse_interval(
+    key: &str,
+    value: &str,
+) -> Result<Duration, KafkaThreadpoolError> {
+    match value.parse::<f64>() {
+        Ok(val) if val >= 0.001 => {
+            Ok(Duration::from_millis((val * 1000.0) as u64))
+        }
+        _ => Err(KafkaThreadpoolError::config_invalid(
+            key,
+            &format!(
+                "invalid interval={value} \
+                please set to a positive float between [0.001, inf]"
+            ),
+        )),
+    }
+}
+
+/// parse_usize
+///
+/// Internal helper for converting an environment variable
+/// into a ``usize``
+///
+/// # Arguments
+///
+/// * `key` - environment variable name for error reporting
+/// * `value` - environment variable value
+///
+fn parse_usize(key: &str, value: &str) -> Result<usize, KafkaThreadpoolError> {
+    value.parse::<usize>().map_err(|_| {
+        KafkaThreadpoolError::config_invalid(
+            key,
+            &format!(
+                "invalid number={value} \
+                please set to a number between [0, inf] (0 is unbounded)"
+            ),
+        )
+    })
+}
+
+/// parse_f64
+///
+/// Internal helper for converting an environment variable
+/// into a non-negative ``f64``
+///
+/// # Arguments
+///
+/// * `key` - environment variable name for error reporting
+/// * `value` - environment variable value
+///
+fn parse_f64(key: &str, value: &str) -> Result<f64, KafkaThreadpoolError> {
+    match value.parse::<f64>() {
+        Ok(val) if val.is_finite() && val >= 0.0 => Ok(val),
+        _ => Err(KafkaThreadpoolError::config_invalid(
+            key,
+            &format!(
+                "invalid number={value} \
+                please set to a positive float between [0.0, inf]"
+            ),
+        )),
+    }
+}
+
+/// parse_secs
+///
+/// Internal helper for converting a non-negative number of
+/// seconds (as a float string) from an environment variable into
+/// a ``Duration`` where ``0`` is allowed
+///
+/// # Arguments
+///
+/// * `key` - environment variable name for error reporting
+/// * `value` - environment variable value
+///
+fn parse_secs(
+    key: &str,
+    value: &str,
+) -> Result<Duration, KafkaThreadpoolError> {
+    Duration::try_from_secs_f64(parse_f64(key, value)?).map_err(|e| {
+        KafkaThreadpoolError::config_invalid(
+            key,
+            &format!(
+                "invalid number of seconds={value} with err={e} \
+                please set to a smaller positive float"
+            ),
+        )
+    })
+}
+
+/// get_rdkafka_properties_from_env
+///
+/// Internal helper for converting all ``KAFKA_RDKAFKA_<PROP>``
+/// environment variables into ``librdkafka`` properties where
+/// ``<PROP>`` is lowercased and ``_`` is replaced with ``.``
+///
+/// ``KAFKA_RDKAFKA_LINGER_MS=5`` becomes ``linger.ms=5``
+///
+fn get_rdkafka_properties_from_env() -> HashMap<String, String> {
+    std::env::vars()
+        .filter_map(|(key, value)| {
+            key.strip_prefix("KAFKA_RDKAFKA_")
+                .map(|prop| (prop.to_lowercase().replace('_', "."), value))
+        })
+        .collect()
 }
 
 impl KafkaClientConfig {
-    pub fn new(label: &str) -> Self {
+    /// builder
+    ///
+    /// Create a
+    /// [`KafkaClientConfigBuilder`](crate::config::kafka_client_config_builder::KafkaClientConfigBuilder)
+    /// for building a config without environment variables
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use kafka_threadpool::config::kafka_client_config::KafkaClientConfig;
+    /// let config = KafkaClientConfig::builder()
+    ///     .label("billing")
+    ///     .brokers(vec!["localhost:9092".to_string()])
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    ///
+    pub fn builder() -> KafkaClientConfigBuilder {
+        KafkaClientConfigBuilder::new()
+    }
+
+    /// new
+    ///
+    /// Build a [`KafkaClientConfig`] from the supported
+    /// environment variables using the
+    /// [`KafkaClientConfigBuilder`](crate::config::kafka_client_config_builder::KafkaClientConfigBuilder)
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - tracking label for logs (``KAFKA_LOG_LABEL``
+    /// takes precedence if set)
+    ///
+    /// # Errors
+    ///
+    /// [`KafkaThreadpoolError::ConfigInvalid`] if an environment
+    /// variable is invalid or [`KafkaThreadpoolError::TlsAssetMissing`]
+    /// if a tls asset path does not exist
+    ///
+    pub fn new(label: &str) -> Result<Self, KafkaThreadpoolError> {
         let is_enabled_s = std::env::var("KAFKA_ENABLED")
             .unwrap_or_else(|_| "false".to_string())
             .to_lowercase();
@@ -51,18 +281,11 @@ impl KafkaClientConfig {
 
         if !is_enabled {
             trace!("kafka disabled KAFKA_ENABLED={is_enabled}");
-            return KafkaClientConfig {
-                label: label.to_string(),
-                is_enabled,
-                broker_list: Vec::new(),
-                publish_topics: HashMap::new(),
-                num_threads: 0,
-                retry_sleep_sec: 0,
-                idle_sleep_sec: 0,
-                tls_key: "".to_string(),
-                tls_cert: "".to_string(),
-                tls_ca: "".to_string(),
-            };
+            return KafkaClientConfig::builder()
+                .label(label)
+                .enabled(false)
+                .num_threads(0)
+                .build();
         }
 
         let use_label = std::env::var("KAFKA_LOG_LABEL")
@@ -80,92 +303,304 @@ impl KafkaClientConfig {
         let retry_sleep_interval_s =
             std::env::var("KAFKA_PUBLISH_RETRY_INTERVAL_SEC")
                 .unwrap_or_else(|_| "1".to_string());
+        let retry_max_interval_s =
+            std::env::var("KAFKA_PUBLISH_RETRY_MAX_INTERVAL_SEC")
+                .unwrap_or_else(|_| "30".to_string());
+        let retry_multiplier_s =
+            std::env::var("KAFKA_PUBLISH_RETRY_MULTIPLIER")
+                .unwrap_or_else(|_| "2.0".to_string());
+        let retry_jitter_s = std::env::var("KAFKA_PUBLISH_RETRY_JITTER")
+            .unwrap_or_else(|_| "0.2".to_string());
+        let retry_max_attempts_s =
+            std::env::var("KAFKA_PUBLISH_RETRY_MAX_ATTEMPTS")
+                .unwrap_or_else(|_| "10".to_string());
+        let retry_max_elapsed_s =
+            std::env::var("KAFKA_PUBLISH_RETRY_MAX_ELAPSED_SEC")
+                .unwrap_or_else(|_| "0".to_string());
         let idle_sleep_interval_s =
             std::env::var("KAFKA_PUBLISH_IDLE_INTERVAL_SEC")
                 .unwrap_or_else(|_| "0.5".to_string());
         let num_threads_s = std::env::var("KAFKA_NUM_THREADS")
             .unwrap_or_else(|_| "5".to_string())
             .to_lowercase();
+        let publish_batch_size_s = std::env::var("KAFKA_PUBLISH_BATCH_SIZE")
+            .unwrap_or_else(|_| "10".to_string());
+        let max_queue_msgs_s = std::env::var("KAFKA_QUEUE_MAX_MSGS")
+            .unwrap_or_else(|_| "0".to_string());
+        let max_queue_bytes_s = std::env::var("KAFKA_QUEUE_MAX_BYTES")
+            .unwrap_or_else(|_| "0".to_string());
+        let queue_overflow_policy_s =
+            std::env::var("KAFKA_QUEUE_OVERFLOW_POLICY")
+                .unwrap_or_else(|_| "block".to_string());
+        let ordering_mode_s = std::env::var("KAFKA_ORDERING_MODE")
+            .unwrap_or_else(|_| "unordered".to_string());
+        let enable_idempotence_s = std::env::var("KAFKA_ENABLE_IDEMPOTENCE")
+            .unwrap_or_else(|_| "false".to_string())
+            .to_lowercase();
+        let transactional_id_prefix =
+            std::env::var("KAFKA_TRANSACTIONAL_ID_PREFIX")
+                .unwrap_or_else(|_| "".to_string());
+        let statistics_interval_ms_s =
+            std::env::var("KAFKA_STATISTICS_INTERVAL_MS")
+                .unwrap_or_else(|_| "0".to_string());
+        let health_degraded_queue_depth_s =
+            std::env::var("KAFKA_HEALTH_DEGRADED_QUEUE_DEPTH")
+                .unwrap_or_else(|_| "0".to_string());
+        let health_unhealthy_queue_depth_s =
+            std::env::var("KAFKA_HEALTH_UNHEALTHY_QUEUE_DEPTH")
+                .unwrap_or_else(|_| "0".to_string());
+        let health_degraded_delivery_s =
+            std::env::var("KAFKA_HEALTH_DEGRADED_DELIVERY_SEC")
+                .unwrap_or_else(|_| "60".to_string());
+        let health_unhealthy_delivery_s =
+            std::env::var("KAFKA_HEALTH_UNHEALTHY_DELIVERY_SEC")
+                .unwrap_or_else(|_| "300".to_string());
+        let health_broker_down_s =
+            std::env::var("KAFKA_HEALTH_BROKER_DOWN_SEC")
+                .unwrap_or_else(|_| "60".to_string());
+        let ensure_topics_s = std::env::var("KAFKA_ENSURE_TOPICS")
+            .unwrap_or_else(|_| "false".to_string())
+            .to_lowercase();
+        let default_num_partitions_s =
+            std::env::var("KAFKA_TOPIC_NUM_PARTITIONS")
+                .unwrap_or_else(|_| "-1".to_string());
+        let default_replication_factor_s =
+            std::env::var("KAFKA_TOPIC_REPLICATION_FACTOR")
+                .unwrap_or_else(|_| "-1".to_string());
+        let admin_timeout_s = std::env::var("KAFKA_ADMIN_TIMEOUT_SEC")
+            .unwrap_or_else(|_| "30".to_string());
+        let metadata_timeout_s = std::env::var("KAFKA_METADATA_TIMEOUT_SEC")
+            .unwrap_or_else(|_| "30".to_string());
+        let watermark_timeout_s =
+            std::env::var("KAFKA_METADATA_WATERMARK_TIMEOUT_SEC")
+                .unwrap_or_else(|_| "1".to_string());
+        let watermark_concurrency_s =
+            std::env::var("KAFKA_METADATA_WATERMARK_CONCURRENCY")
+                .unwrap_or_else(|_| "1".to_string());
+        let security_protocol_s = std::env::var("KAFKA_SECURITY_PROTOCOL")
+            .unwrap_or_else(|_| "".to_string());
+        let sasl_mechanism_s = std::env::var("KAFKA_SASL_MECHANISM")
+            .unwrap_or_else(|_| "".to_string());
+        let sasl_username = std::env::var("KAFKA_SASL_USERNAME")
+            .unwrap_or_else(|_| "".to_string());
+        let sasl_password = std::env::var("KAFKA_SASL_PASSWORD")
+            .unwrap_or_else(|_| "".to_string());
+        let sasl_oauthbearer_config =
+            std::env::var("KAFKA_SASL_OAUTHBEARER_CONFIG")
+                .unwrap_or_else(|_| "".to_string());
+        let dlq_topic =
+            std::env::var("KAFKA_DLQ_TOPIC").unwrap_or_else(|_| "".to_string());
+        let dlq_file =
+            std::env::var("KAFKA_DLQ_FILE").unwrap_or_else(|_| "".to_string());
 
-        let retry_sleep_sec_f64 = match retry_sleep_interval_s.parse::<f64>() {
-            Ok(val) => val * 1000.0,
-            Err(_) => panic!(
-                "invalid retry sleep interval for \
-                KAFKA_PUBLISH_RETRY_INTERVAL_SEC={retry_sleep_interval_s} \
-                please set to a positive float between [0.001, inf]"
-            ),
+        let retry_sleep_interval = parse_interval(
+            "KAFKA_PUBLISH_RETRY_INTERVAL_SEC",
+            &retry_sleep_interval_s,
+        )?;
+        let retry_policy = KafkaRetryPolicy {
+            max_attempts: retry_max_attempts_s.parse::<u32>().map_err(
+                |_| {
+                    KafkaThreadpoolError::config_invalid(
+                        "KAFKA_PUBLISH_RETRY_MAX_ATTEMPTS",
+                        &format!(
+                            "invalid number={retry_max_attempts_s} \
+                            please set to a number between [0, inf] \
+                            (0 retries until the max elapsed time)"
+                        ),
+                    )
+                },
+            )?,
+            initial_backoff: retry_sleep_interval,
+            max_backoff: parse_interval(
+                "KAFKA_PUBLISH_RETRY_MAX_INTERVAL_SEC",
+                &retry_max_interval_s,
+            )?,
+            multiplier: parse_f64(
+                "KAFKA_PUBLISH_RETRY_MULTIPLIER",
+                &retry_multiplier_s,
+            )?,
+            jitter: parse_f64("KAFKA_PUBLISH_RETRY_JITTER", &retry_jitter_s)?,
+            max_elapsed: parse_secs(
+                "KAFKA_PUBLISH_RETRY_MAX_ELAPSED_SEC",
+                &retry_max_elapsed_s,
+            )?,
         };
-        let retry_sleep_sec = retry_sleep_sec_f64 as u64;
-        if retry_sleep_sec <= 1 {
-            panic!(
-                "please use a positive float for the retry sleep interval \
-                KAFKA_PUBLISH_RETRY_INTERVAL_SEC={retry_sleep_sec} \
-                please set to a number between [0.001, inf]"
+        let idle_sleep_interval = parse_interval(
+            "KAFKA_PUBLISH_IDLE_INTERVAL_SEC",
+            &idle_sleep_interval_s,
+        )?;
+        let num_threads = num_threads_s.parse::<u8>().map_err(|_| {
+            KafkaThreadpoolError::config_invalid(
+                "KAFKA_NUM_THREADS",
+                &format!(
+                    "invalid number of threads={num_threads_s} \
+                    please set to a number between 1-255"
+                ),
             )
-        }
-        let idle_sleep_sec_f64 = match idle_sleep_interval_s.parse::<f64>() {
-            Ok(val) => val * 1000.0,
-            Err(_) => panic!(
-                "invalid idle sleep interval for \
-                KAFKA_PUBLISH_IDLE_INTERVAL_SEC={idle_sleep_interval_s} \
-                please set to a positive float between [0.001, inf]"
-            ),
+        })?;
+        let publish_batch_size =
+            parse_usize("KAFKA_PUBLISH_BATCH_SIZE", &publish_batch_size_s)?;
+        let max_queue_msgs =
+            parse_usize("KAFKA_QUEUE_MAX_MSGS", &max_queue_msgs_s)?;
+        let max_queue_bytes =
+            parse_usize("KAFKA_QUEUE_MAX_BYTES", &max_queue_bytes_s)?;
+        let queue_overflow_policy = queue_overflow_policy_s
+            .parse::<KafkaQueueOverflowPolicy>()
+            .map_err(|e| {
+                KafkaThreadpoolError::config_invalid(
+                    "KAFKA_QUEUE_OVERFLOW_POLICY",
+                    &e,
+                )
+            })?;
+        let ordering_mode =
+            ordering_mode_s.parse::<KafkaOrderingMode>().map_err(|e| {
+                KafkaThreadpoolError::config_invalid("KAFKA_ORDERING_MODE", &e)
+            })?;
+
+        let default_num_partitions =
+            default_num_partitions_s.parse::<i32>().map_err(|_| {
+                KafkaThreadpoolError::config_invalid(
+                    "KAFKA_TOPIC_NUM_PARTITIONS",
+                    &format!(
+                        "invalid number={default_num_partitions_s} \
+                        please set to -1 (broker default) or a number \
+                        between [1, inf]"
+                    ),
+                )
+            })?;
+        let default_replication_factor =
+            default_replication_factor_s.parse::<i32>().map_err(|_| {
+                KafkaThreadpoolError::config_invalid(
+                    "KAFKA_TOPIC_REPLICATION_FACTOR",
+                    &format!(
+                        "invalid number={default_replication_factor_s} \
+                        please set to -1 (broker default) or a number \
+                        between [1, inf]"
+                    ),
+                )
+            })?;
+        let statistics_interval_ms =
+            statistics_interval_ms_s.parse::<u64>().map_err(|_| {
+                KafkaThreadpoolError::config_invalid(
+                    "KAFKA_STATISTICS_INTERVAL_MS",
+                    &format!(
+                        "invalid number={statistics_interval_ms_s} \
+                        please set to a number of milliseconds between \
+                        [0, inf] (0 disables the statistics)"
+                    ),
+                )
+            })?;
+        let admin_timeout =
+            parse_interval("KAFKA_ADMIN_TIMEOUT_SEC", &admin_timeout_s)?;
+        let metadata_timeout =
+            parse_interval("KAFKA_METADATA_TIMEOUT_SEC", &metadata_timeout_s)?;
+        let watermark_timeout = parse_interval(
+            "KAFKA_METADATA_WATERMARK_TIMEOUT_SEC",
+            &watermark_timeout_s,
+        )?;
+        let watermark_concurrency = parse_usize(
+            "KAFKA_METADATA_WATERMARK_CONCURRENCY",
+            &watermark_concurrency_s,
+        )?;
+
+        let health_thresholds = KafkaHealthThresholds {
+            degraded_queue_depth: parse_usize(
+                "KAFKA_HEALTH_DEGRADED_QUEUE_DEPTH",
+                &health_degraded_queue_depth_s,
+            )?,
+            unhealthy_queue_depth: parse_usize(
+                "KAFKA_HEALTH_UNHEALTHY_QUEUE_DEPTH",
+                &health_unhealthy_queue_depth_s,
+            )?,
+            degraded_delivery_age: parse_secs(
+                "KAFKA_HEALTH_DEGRADED_DELIVERY_SEC",
+                &health_degraded_delivery_s,
+            )?,
+            unhealthy_delivery_age: parse_secs(
+                "KAFKA_HEALTH_UNHEALTHY_DELIVERY_SEC",
+                &health_unhealthy_delivery_s,
+            )?,
+            broker_down_ttl: parse_interval(
+                "KAFKA_HEALTH_BROKER_DOWN_SEC",
+                &health_broker_down_s,
+            )?,
         };
-        let idle_sleep_sec = idle_sleep_sec_f64 as u64;
-        if idle_sleep_sec <= 1 {
-            panic!(
-                "please use a positive float for the idle sleep interval \
-                KAFKA_PUBLISH_IDLE_INTERVAL_SEC={idle_sleep_sec} \
-                please set to a number between [0.001, inf]"
+
+        let broker_list: Vec<String> =
+            broker_list_s.split(',').map(|br| br.to_string()).collect();
+        let topics: Vec<String> =
+            env_topics.split(',').map(|tp| tp.to_string()).collect();
+
+        let mut builder = KafkaClientConfig::builder()
+            .label(&use_label)
+            .enabled(is_enabled)
+            .brokers(broker_list)
+            .topics(topics)
+            .ensure_topics(ensure_topics_s == "true" || ensure_topics_s == "1")
+            .default_num_partitions(default_num_partitions)
+            .default_replication_factor(default_replication_factor)
+            .admin_timeout(admin_timeout)
+            .num_threads(num_threads)
+            .publish_batch_size(publish_batch_size)
+            .retry_policy(retry_policy)
+            .idle_sleep_interval(idle_sleep_interval)
+            .tls_key(&tls_key)
+            .tls_cert(&tls_cert)
+            .tls_ca(&tls_ca)
+            .max_queue_msgs(max_queue_msgs)
+            .max_queue_bytes(max_queue_bytes)
+            .queue_overflow_policy(queue_overflow_policy)
+            .ordering_mode(ordering_mode)
+            .enable_idempotence(
+                enable_idempotence_s == "true" || enable_idempotence_s == "1",
             )
+            .transactional_id_prefix(&transactional_id_prefix)
+            .statistics_interval_ms(statistics_interval_ms)
+            .metadata_timeout(metadata_timeout)
+            .watermark_timeout(watermark_timeout)
+            .watermark_concurrency(watermark_concurrency)
+            .health_thresholds(health_thresholds)
+            .rdkafka_properties(get_rdkafka_properties_from_env())
+            .sasl_username(&sasl_username)
+            .sasl_password(&sasl_password)
+            .sasl_oauthbearer_config(&sasl_oauthbearer_config);
+        if !security_protocol_s.is_empty() {
+            builder = builder.security_protocol(
+                security_protocol_s
+                    .parse::<KafkaSecurityProtocol>()
+                    .map_err(|e| {
+                        KafkaThreadpoolError::config_invalid(
+                            "KAFKA_SECURITY_PROTOCOL",
+                            &e,
+                        )
+                    })?,
+            );
         }
-        let num_threads = match num_threads_s.parse::<u8>() {
-            Ok(val) => val,
-            Err(_) => panic!(
-                "invalid number of threads for KAFKA_NUM_THREADS={num_threads_s} \
-                please set to a number between 1-50"
-            ),
-        };
-        if num_threads == 0 {
-            panic!(
-                "please use a valid number for the number of threads \
-                KAFKA_NUM_THREADS={num_threads_s} \
-                please set to a number between 1-100"
-            )
+        if !sasl_mechanism_s.is_empty() {
+            builder = builder.sasl_mechanism(
+                sasl_mechanism_s.parse::<KafkaSaslMechanism>().map_err(
+                    |e| {
+                        KafkaThreadpoolError::config_invalid(
+                            "KAFKA_SASL_MECHANISM",
+                            &e,
+                        )
+                    },
+                )?,
+            );
+        }
+        if !dlq_topic.is_empty() {
+            builder =
+                builder.dead_letter_sink(KafkaDeadLetterSink::Topic(dlq_topic));
+        }
+        if !dlq_file.is_empty() {
+            builder =
+                builder.dead_letter_sink(KafkaDeadLetterSink::File(dlq_file));
         }
+        let config = builder.build()?;
 
-        let mut publish_topics: HashMap<String, String> = HashMap::new();
-        let mut broker_list: Vec<String> = Vec::new();
-        broker_list_s.split(',').for_each(|br| {
-            broker_list.push(br.to_string());
-        });
-        env_topics.split(',').for_each(|tp| {
-            publish_topics.insert(tp.to_string(), "0".to_string());
-        });
-
-        info!(
-            "build_kafka_client_config - label={label} \
-            enabled={is_enabled} \
-            tls key={tls_key} cert={tls_cert} ca={tls_ca} \
-            retry_sleep={retry_sleep_sec} \
-            threads={num_threads} \
-            broker_list={:?} \
-            topics={:?}",
-            broker_list, publish_topics
-        );
+        info!("build_kafka_client_config - {config}");
 
-        KafkaClientConfig {
-            label: use_label,
-            is_enabled,
-            broker_list,
-            publish_topics,
-            num_threads,
-            retry_sleep_sec,
-            idle_sleep_sec,
-            tls_key,
-            tls_cert,
-            tls_ca,
-        }
+        Ok(config)
     }
 }
 
@@ -176,9 +611,21 @@ impl std::fmt::Debug for KafkaClientConfig {
             "DEBUG KafkaClientConfig label={} \
             enabled={} \
             tls key={} cert={} ca={} \
-            retry_sleep={} \
+            security_protocol={} sasl_mechanism={:?} sasl_username={} \
+            retry_policy={} \
             idle_sleep={} \
             threads={} \
+            batch_size={} \
+            queue max_msgs={} max_bytes={} overflow={} \
+            ordering={} \
+            idempotence={} transactional_id_prefix={} \
+            statistics_interval_ms={} \
+            ensure_topics={} topic_specs={} admin_timeout={:?} \
+            metadata timeout={:?} watermark_timeout={:?} \
+            watermark_concurrency={} \
+            health_thresholds={} \
+            rdkafka_properties={:?} \
+            dead_letter_sinks={:?} \
             broker_list={:?} \
             topics={:?}",
             self.label,
@@ -186,9 +633,29 @@ impl std::fmt::Debug for KafkaClientConfig {
             self.tls_key,
             self.tls_cert,
             self.tls_ca,
-            self.retry_sleep_sec,
+            self.security_protocol,
+            self.sasl_mechanism,
+            self.sasl_username,
+            self.retry_policy,
             self.idle_sleep_sec,
             self.num_threads,
+            self.publish_batch_size,
+            self.max_queue_msgs,
+            self.max_queue_bytes,
+            self.queue_overflow_policy,
+            self.ordering_mode,
+            self.enable_idempotence,
+            self.transactional_id_prefix,
+            self.statistics_interval_ms,
+            self.ensure_topics,
+            self.topic_specs.len(),
+            self.admin_timeout,
+            self.metadata_timeout,
+            self.watermark_timeout,
+            self.watermark_concurrency,
+            self.health_thresholds,
+            self.rdkafka_properties.keys().collect::<Vec<&String>>(),
+            self.dead_letter_sinks,
             self.broker_list,
             self.publish_topics
         )
@@ -202,9 +669,21 @@ impl std::fmt::Display for KafkaClientConfig {
             "KafkaClientConfig label={} \
             enabled={} \
             tls key={} cert={} ca={} \
-            retry_sleep={} \
+            security_protocol={} sasl_mechanism={:?} sasl_username={} \
+            retry_policy={} \
             idle_sleep={} \
             threads={} \
+            batch_size={} \
+            queue max_msgs={} max_bytes={} overflow={} \
+            ordering={} \
+            idempotence={} transactional_id_prefix={} \
+            statistics_interval_ms={} \
+            ensure_topics={} topic_specs={} admin_timeout={:?} \
+            metadata timeout={:?} watermark_timeout={:?} \
+            watermark_concurrency={} \
+            health_thresholds={} \
+            rdkafka_properties={:?} \
+            dead_letter_sinks={:?} \
             broker_list={:?} \
             topics={:?}",
             self.label,
@@ -212,11 +691,181 @@ impl std::fmt::Display for KafkaClientConfig {
             self.tls_key,
             self.tls_cert,
             self.tls_ca,
-            self.retry_sleep_sec,
+            self.security_protocol,
+            self.sasl_mechanism,
+            self.sasl_username,
+            self.retry_policy,
             self.idle_sleep_sec,
             self.num_threads,
+            self.publish_batch_size,
+            self.max_queue_msgs,
+            self.max_queue_bytes,
+            self.queue_overflow_policy,
+            self.ordering_mode,
+            self.enable_idempotence,
+            self.transactional_id_prefix,
+            self.statistics_interval_ms,
+            self.ensure_topics,
+            self.topic_specs.len(),
+            self.admin_timeout,
+            self.metadata_timeout,
+            self.watermark_timeout,
+            self.watermark_concurrency,
+            self.health_thresholds,
+            self.rdkafka_properties.keys().collect::<Vec<&String>>(),
+            self.dead_letter_sinks,
             self.broker_list,
             self.publish_topics
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    // KafkaClientConfig::new reads the process environment so the
+    // tests take turns setting it
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    fn config_from_env(
+        vars: &[(&str, &str)],
+    ) -> Result<KafkaClientConfig, KafkaThreadpoolError> {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let base = [
+            ("KAFKA_ENABLED", "true"),
+            ("KAFKA_BROKERS", "localhost:9092"),
+        ];
+        for (key, value) in base.iter().chain(vars.iter()) {
+            std::env::set_var(key, value);
+        }
+        let config = KafkaClientConfig::new("test");
+        for (key, _) in base.iter().chain(vars.iter()) {
+            std::env::remove_var(key);
+        }
+        config
+    }
+
+    fn invalid_key(
+        res: Result<KafkaClientConfig, KafkaThreadpoolError>,
+    ) -> String {
+        match res {
+            Err(KafkaThreadpoolError::ConfigInvalid { key, .. }) => key,
+            Err(e) => panic!("expected ConfigInvalid got {e}"),
+            Ok(_) => panic!("expected ConfigInvalid got a config"),
+        }
+    }
+
+    #[test]
+    fn statistics_interval_defaults_to_disabled() {
+        let config = config_from_env(&[]).unwrap();
+        assert_eq!(config.statistics_interval_ms, 0);
+    }
+
+    #[test]
+    fn statistics_interval_is_read_from_env() {
+        let config =
+            config_from_env(&[("KAFKA_STATISTICS_INTERVAL_MS", "5000")])
+                .unwrap();
+        assert_eq!(config.statistics_interval_ms, 5000);
+    }
+
+    #[test]
+    fn invalid_statistics_interval_is_config_invalid() {
+        for value in ["-1", "1.5", "abc"] {
+            assert_eq!(
+                invalid_key(config_from_env(&[(
+                    "KAFKA_STATISTICS_INTERVAL_MS",
+                    value
+                )])),
+                "KAFKA_STATISTICS_INTERVAL_MS"
+            );
+        }
+    }
+
+    #[test]
+    fn seconds_env_vars_parse_into_durations() {
+        let config = config_from_env(&[
+            ("KAFKA_PUBLISH_RETRY_MAX_ELAPSED_SEC", "90.5"),
+            ("KAFKA_HEALTH_DEGRADED_DELIVERY_SEC", "0"),
+            ("KAFKA_HEALTH_UNHEALTHY_DELIVERY_SEC", "600"),
+        ])
+        .unwrap();
+        assert_eq!(
+            config.retry_policy.max_elapsed,
+            Duration::from_millis(90500)
+        );
+        assert_eq!(
+            config.health_thresholds.degraded_delivery_age,
+            Duration::ZERO
+        );
+        assert_eq!(
+            config.health_thresholds.unhealthy_delivery_age,
+            Duration::from_secs(600)
+        );
+    }
+
+    #[test]
+    fn invalid_seconds_env_vars_are_config_invalid() {
+        for key in [
+            "KAFKA_PUBLISH_RETRY_MAX_ELAPSED_SEC",
+            "KAFKA_HEALTH_DEGRADED_DELIVERY_SEC",
+            "KAFKA_HEALTH_UNHEALTHY_DELIVERY_SEC",
+        ] {
+            for value in ["-1", "NaN", "inf", "1e30", "abc"] {
+                assert_eq!(
+                    invalid_key(config_from_env(&[(key, value)])),
+                    key,
+                    "{key}={value}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rdkafka_env_vars_are_passed_through() {
+        let config = config_from_env(&[
+            ("KAFKA_RDKAFKA_LINGER_MS", "5"),
+            ("KAFKA_RDKAFKA_COMPRESSION_TYPE", "zstd"),
+        ])
+        .unwrap();
+        assert_eq!(
+            config
+                .rdkafka_properties
+                .get("linger.ms")
+                .map(String::as_str),
+            Some("5")
+        );
+        assert_eq!(
+            config
+                .rdkafka_properties
+                .get("compression.type")
+                .map(String::as_str),
+            Some("zstd")
+        );
+    }
+
+    #[test]
+    fn rdkafka_enable_idempotence_env_var_enables_idempotence() {
+        let config =
+            config_from_env(&[("KAFKA_RDKAFKA_ENABLE_IDEMPOTENCE", "true")])
+                .unwrap();
+        assert!(config.enable_idempotence);
+        assert!(!config.rdkafka_properties.contains_key("enable.idempotence"));
+
+        let res = config_from_env(&[
+            ("KAFKA_TRANSACTIONAL_ID_PREFIX", "billing"),
+            ("KAFKA_RDKAFKA_ENABLE_IDEMPOTENCE", "false"),
+        ]);
+        assert_eq!(invalid_key(res), "enable.idempotence");
+    }
+
+    #[test]
+    fn managed_rdkafka_env_vars_are_config_invalid() {
+        let res =
+            config_from_env(&[("KAFKA_RDKAFKA_SECURITY_PROTOCOL", "SSL")]);
+        assert_eq!(invalid_key(res), "security.protocol");
+    }
+}
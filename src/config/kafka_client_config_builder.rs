@@ -0,0 +1,989 @@
+//! Programmatic builder for a
+//! [`KafkaClientConfig`](crate::config::kafka_client_config::KafkaClientConfig)
+//! that does not read any environment variables. This allows running
+//! more than one differently-configured threadpool in the same process.
+//!
+//! ```rust
+//! use kafka_threadpool::config::kafka_client_config::KafkaClientConfig;
+//! use kafka_threadpool::pool::start_threads_from_config::start_threads_from_config;
+//! let config = KafkaClientConfig::builder()
+//!     .label("audit")
+//!     .brokers(vec!["localhost:9092".to_string()])
+//!     .topics(vec!["audit".to_string()])
+//!     .num_threads(2)
+//!     .build()
+//!     .unwrap();
+//! let kafka_publisher = start_threads_from_config(config).await.unwrap();
+//! ```
+//!
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::admin::kafka_topic_spec::KafkaTopicSpec;
+use crate::api::build_rdkafka_client_config::is_managed_rdkafka_property;
+use crate::api::kafka_oauth_token_provider::KafkaOAuthTokenProvider;
+use crate::api::kafka_ordering_mode::KafkaOrderingMode;
+use crate::api::kafka_queue_overflow_policy::KafkaQueueOverflowPolicy;
+use crate::api::kafka_security_protocol::KafkaSaslMechanism;
+use crate::api::kafka_security_protocol::KafkaSecurityProtocol;
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
+use crate::config::kafka_client_config::KafkaClientConfig;
+use crate::config::kafka_health_thresholds::KafkaHealthThresholds;
+use crate::config::kafka_retry_policy::KafkaRetryPolicy;
+use crate::dlq::kafka_dead_letter_sink::KafkaDeadLetterSink;
+use crate::stats::kafka_stats_handler::KafkaStatsHandler;
+
+/// KafkaClientConfigBuilder
+///
+/// Typed setters for every
+/// [`KafkaClientConfig`](crate::config::kafka_client_config::KafkaClientConfig)
+/// value with validation in
+/// [`build()`](crate::config::kafka_client_config_builder::KafkaClientConfigBuilder::build)
+///
+/// By default:
+///
+/// - ``label`` = ``ktp``
+/// - ``enabled`` = ``true``
+/// - ``num_threads`` = ``5``
+/// - ``publish_batch_size`` = ``10``
+/// - ``retry_policy`` = up to ``10`` attempts with a ``1s`` initial
+/// backoff that doubles up to ``30s`` with ``20%`` jitter
+/// - ``idle_sleep_interval`` = ``500ms``
+/// - the work vec is unbounded with the ``Block`` overflow policy
+/// - messages are ``Unordered`` across the worker threads
+/// - the producer is not idempotent or transactional
+/// - ``statistics_interval_ms`` = ``0`` (statistics are disabled)
+/// - topics are not created at startup and new topics use the
+/// broker's partition and replication defaults
+/// - ``admin_timeout`` = ``30s``
+/// - ``metadata_timeout`` = ``30s`` and ``watermark_timeout`` = ``1s``
+/// with one watermark fetch at a time
+/// - ``health_thresholds`` = ``Degraded`` after ``60s`` and
+/// ``Unhealthy`` after ``300s`` without a delivery while there are
+/// messages to publish with no queue depth thresholds and an all
+/// brokers down event counts for ``60s`` after the last connection
+/// failure
+/// - ``security_protocol`` = ``SSL`` if any tls asset is set
+/// otherwise ``PLAINTEXT``
+///
+#[derive(Clone)]
+pub struct KafkaClientConfigBuilder {
+    config: KafkaClientConfig,
+    security_protocol: Option<KafkaSecurityProtocol>,
+}
+
+impl Default for KafkaClientConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// validate_tls_asset
+///
+/// Internal helper for making sure a configured tls asset exists
+///
+/// # Arguments
+///
+/// * `key` - name of the tls asset for error reporting
+/// * `path` - path to the tls asset (empty means not set)
+///
+fn validate_tls_asset(
+    key: &str,
+    path: &str,
+) -> Result<(), KafkaThreadpoolError> {
+    if !path.is_empty() && !std::path::Path::new(path).exists() {
+        return Err(KafkaThreadpoolError::TlsAssetMissing {
+            key: key.to_string(),
+            path: path.to_string(),
+        });
+    }
+    Ok(())
+}
+
+impl KafkaClientConfigBuilder {
+    /// new
+    ///
+    /// Create a builder with the default values
+    ///
+    pub fn new() -> Self {
+        KafkaClientConfigBuilder {
+            config: KafkaClientConfig {
+                label: "ktp".to_string(),
+                is_enabled: true,
+                broker_list: Vec::new(),
+                publish_topics: HashMap::new(),
+                ensure_topics: false,
+                topic_specs: Vec::new(),
+                default_num_partitions: -1,
+                default_replication_factor: -1,
+                admin_timeout: Duration::from_secs(30),
+                num_threads: 5,
+                publish_batch_size: 10,
+                retry_policy: KafkaRetryPolicy::default(),
+                idle_sleep_sec: 500,
+                tls_key: "".to_string(),
+                tls_cert: "".to_string(),
+                tls_ca: "".to_string(),
+                max_queue_msgs: 0,
+                max_queue_bytes: 0,
+                queue_overflow_policy: KafkaQueueOverflowPolicy::default(),
+                ordering_mode: KafkaOrderingMode::default(),
+                enable_idempotence: false,
+                transactional_id_prefix: "".to_string(),
+                statistics_interval_ms: 0,
+                metadata_timeout: Duration::from_secs(30),
+                watermark_timeout: Duration::from_secs(1),
+                watermark_concurrency: 1,
+                rdkafka_properties: HashMap::new(),
+                security_protocol: KafkaSecurityProtocol::default(),
+                sasl_mechanism: None,
+                sasl_username: "".to_string(),
+                sasl_password: "".to_string(),
+                sasl_oauthbearer_config: "".to_string(),
+                oauth_token_provider: None,
+                dead_letter_sinks: Vec::new(),
+                stats_handler: None,
+                health_thresholds: KafkaHealthThresholds::default(),
+            },
+            security_protocol: None,
+        }
+    }
+
+    /// label
+    ///
+    /// tracking label that shows up in all crate logs
+    ///
+    pub fn label(mut self, label: &str) -> Self {
+        self.config.label = label.to_string();
+        self
+    }
+
+    /// enabled
+    ///
+    /// toggle the threadpool on or off
+    ///
+    pub fn enabled(mut self, is_enabled: bool) -> Self {
+        self.config.is_enabled = is_enabled;
+        self
+    }
+
+    /// brokers
+    ///
+    /// list of brokers (``host:port``)
+    ///
+    pub fn brokers(mut self, broker_list: Vec<String>) -> Self {
+        self.config.broker_list = broker_list;
+        self
+    }
+
+    /// topics
+    ///
+    /// list of supported topics
+    ///
+    pub fn topics(mut self, topics: Vec<String>) -> Self {
+        self.config.publish_topics = topics
+            .into_iter()
+            .map(|topic| (topic, "0".to_string()))
+            .collect();
+        self
+    }
+
+    /// ensure_topics
+    ///
+    /// create any missing ``topics`` and ``topic_spec`` topics when
+    /// the threadpool starts
+    ///
+    pub fn ensure_topics(mut self, ensure_topics: bool) -> Self {
+        self.config.ensure_topics = ensure_topics;
+        self
+    }
+
+    /// topic_spec
+    ///
+    /// declare the partitions, replication factor and configs for
+    /// a topic created by
+    /// [`KafkaAdminClient`](crate::admin::kafka_admin_client::KafkaAdminClient)
+    /// (replaces an earlier spec with the same name)
+    ///
+    pub fn topic_spec(mut self, spec: KafkaTopicSpec) -> Self {
+        self.config.topic_specs.retain(|cur| cur.name != spec.name);
+        self.config.topic_specs.push(spec);
+        self
+    }
+
+    /// default_num_partitions
+    ///
+    /// number of partitions for ``topics`` without a ``topic_spec``
+    /// (``-1`` uses the broker default)
+    ///
+    pub fn default_num_partitions(mut self, num_partitions: i32) -> Self {
+        self.config.default_num_partitions = num_partitions;
+        self
+    }
+
+    /// default_replication_factor
+    ///
+    /// replication factor for ``topics`` without a ``topic_spec``
+    /// (``-1`` uses the broker default)
+    ///
+    pub fn default_replication_factor(
+        mut self,
+        replication_factor: i32,
+    ) -> Self {
+        self.config.default_replication_factor = replication_factor;
+        self
+    }
+
+    /// admin_timeout
+    ///
+    /// max time to wait for each topic admin operation
+    ///
+    pub fn admin_timeout(mut self, timeout: Duration) -> Self {
+        self.config.admin_timeout = timeout;
+        self
+    }
+
+    /// num_threads
+    ///
+    /// number of threads for the threadpool
+    ///
+    pub fn num_threads(mut self, num_threads: u8) -> Self {
+        self.config.num_threads = num_threads;
+        self
+    }
+
+    /// publish_batch_size
+    ///
+    /// max number of messages each thread drains from the work vec
+    /// and publishes concurrently
+    ///
+    pub fn publish_batch_size(mut self, publish_batch_size: usize) -> Self {
+        self.config.publish_batch_size = publish_batch_size;
+        self
+    }
+
+    /// retry_sleep_interval
+    ///
+    /// time to sleep before the first publish retry
+    ///
+    pub fn retry_sleep_interval(mut self, interval: Duration) -> Self {
+        self.config.retry_policy.initial_backoff = interval;
+        self
+    }
+
+    /// retry_policy
+    ///
+    /// [`KafkaRetryPolicy`](crate::config::kafka_retry_policy::KafkaRetryPolicy)
+    /// for failed publishes
+    ///
+    pub fn retry_policy(mut self, retry_policy: KafkaRetryPolicy) -> Self {
+        self.config.retry_policy = retry_policy;
+        self
+    }
+
+    /// idle_sleep_interval
+    ///
+    /// max time an idle thread waits before rechecking the work vec
+    ///
+    pub fn idle_sleep_interval(mut self, interval: Duration) -> Self {
+        self.config.idle_sleep_sec = interval.as_millis() as u64;
+        self
+    }
+
+    /// tls_key
+    ///
+    /// path to the kafka mTLS key
+    ///
+    pub fn tls_key(mut self, path: &str) -> Self {
+        self.config.tls_key = path.to_string();
+        self
+    }
+
+    /// tls_cert
+    ///
+    /// path to the kafka mTLS certificate
+    ///
+    pub fn tls_cert(mut self, path: &str) -> Self {
+        self.config.tls_cert = path.to_string();
+        self
+    }
+
+    /// tls_ca
+    ///
+    /// path to the kafka mTLS certificate authority (CA)
+    ///
+    pub fn tls_ca(mut self, path: &str) -> Self {
+        self.config.tls_ca = path.to_string();
+        self
+    }
+
+    /// max_queue_msgs
+    ///
+    /// max number of messages in the work vec (``0`` is unbounded).
+    /// The ``Key`` and ``TopicPartition`` ordering modes split it
+    /// evenly across the worker threads' work vecs.
+    ///
+    pub fn max_queue_msgs(mut self, max_queue_msgs: usize) -> Self {
+        self.config.max_queue_msgs = max_queue_msgs;
+        self
+    }
+
+    /// max_queue_bytes
+    ///
+    /// max number of bytes in the work vec (``0`` is unbounded).
+    /// The ``Key`` and ``TopicPartition`` ordering modes split it
+    /// evenly across the worker threads' work vecs.
+    ///
+    pub fn max_queue_bytes(mut self, max_queue_bytes: usize) -> Self {
+        self.config.max_queue_bytes = max_queue_bytes;
+        self
+    }
+
+    /// queue_overflow_policy
+    ///
+    /// what to do when adding messages to a full work vec
+    ///
+    pub fn queue_overflow_policy(
+        mut self,
+        policy: KafkaQueueOverflowPolicy,
+    ) -> Self {
+        self.config.queue_overflow_policy = policy;
+        self
+    }
+
+    /// ordering_mode
+    ///
+    /// [`KafkaOrderingMode`](crate::api::kafka_ordering_mode::KafkaOrderingMode)
+    /// for sharding messages across the worker threads
+    ///
+    pub fn ordering_mode(mut self, ordering_mode: KafkaOrderingMode) -> Self {
+        self.config.ordering_mode = ordering_mode;
+        self
+    }
+
+    /// enable_idempotence
+    ///
+    /// start each producer with ``enable.idempotence=true`` so
+    /// retries cannot duplicate or reorder messages within a partition
+    ///
+    pub fn enable_idempotence(mut self, enable_idempotence: bool) -> Self {
+        self.config.enable_idempotence = enable_idempotence;
+        self
+    }
+
+    /// transactional_id_prefix
+    ///
+    /// enable the transactional mode where each worker thread
+    /// uses the ``transactional.id``: ``<prefix>-<thread number>``
+    /// (an empty ``prefix`` disables transactions)
+    ///
+    pub fn transactional_id_prefix(
+        mut self,
+        transactional_id_prefix: &str,
+    ) -> Self {
+        self.config.transactional_id_prefix =
+            transactional_id_prefix.to_string();
+        self
+    }
+
+    /// statistics_interval_ms
+    ///
+    /// how often ``librdkafka`` emits statistics for each worker
+    /// thread's producer in milliseconds (``0`` disables statistics)
+    ///
+    pub fn statistics_interval_ms(
+        mut self,
+        statistics_interval_ms: u64,
+    ) -> Self {
+        self.config.statistics_interval_ms = statistics_interval_ms;
+        self
+    }
+
+    /// health_thresholds
+    ///
+    /// [`KafkaHealthThresholds`](crate::config::kafka_health_thresholds::KafkaHealthThresholds)
+    /// for the
+    /// [`KafkaPublisher::health()`](crate::kafka_publisher::KafkaPublisher::health)
+    /// check
+    ///
+    pub fn health_thresholds(
+        mut self,
+        health_thresholds: KafkaHealthThresholds,
+    ) -> Self {
+        self.config.health_thresholds = health_thresholds;
+        self
+    }
+
+    /// metadata_timeout
+    ///
+    /// max time to wait for the cluster metadata
+    ///
+    pub fn metadata_timeout(mut self, timeout: Duration) -> Self {
+        self.config.metadata_timeout = timeout;
+        self
+    }
+
+    /// watermark_timeout
+    ///
+    /// max time to wait for each partition's watermarks when
+    /// counting messages in the metadata
+    ///
+    pub fn watermark_timeout(mut self, timeout: Duration) -> Self {
+        self.config.watermark_timeout = timeout;
+        self
+    }
+
+    /// watermark_concurrency
+    ///
+    /// max number of partition watermarks fetched at the same
+    /// time (``1`` fetches one partition at a time)
+    ///
+    pub fn watermark_concurrency(
+        mut self,
+        watermark_concurrency: usize,
+    ) -> Self {
+        self.config.watermark_concurrency = watermark_concurrency;
+        self
+    }
+
+    /// rdkafka_property
+    ///
+    /// pass-through ``librdkafka`` property applied to the producer
+    /// and consumer (``linger.ms``, ``compression.type``, ``acks``, etc.)
+    /// where ``enable.idempotence`` sets
+    /// [`enable_idempotence`](crate::config::kafka_client_config_builder::KafkaClientConfigBuilder::enable_idempotence)
+    ///
+    pub fn rdkafka_property(mut self, key: &str, value: &str) -> Self {
+        self.config
+            .rdkafka_properties
+            .insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// rdkafka_properties
+    ///
+    /// add many pass-through ``librdkafka`` properties
+    ///
+    pub fn rdkafka_properties(
+        mut self,
+        properties: HashMap<String, String>,
+    ) -> Self {
+        self.config.rdkafka_properties.extend(properties);
+        self
+    }
+
+    /// security_protocol
+    ///
+    /// ``security.protocol`` for connecting to the brokers
+    ///
+    pub fn security_protocol(
+        mut self,
+        security_protocol: KafkaSecurityProtocol,
+    ) -> Self {
+        self.security_protocol = Some(security_protocol);
+        self
+    }
+
+    /// sasl_mechanism
+    ///
+    /// ``sasl.mechanism`` for the ``SASL_PLAINTEXT`` and ``SASL_SSL``
+    /// security protocols
+    ///
+    pub fn sasl_mechanism(
+        mut self,
+        sasl_mechanism: KafkaSaslMechanism,
+    ) -> Self {
+        self.config.sasl_mechanism = Some(sasl_mechanism);
+        self
+    }
+
+    /// sasl_username
+    ///
+    /// username for the ``PLAIN`` and ``SCRAM`` SASL mechanisms
+    ///
+    pub fn sasl_username(mut self, username: &str) -> Self {
+        self.config.sasl_username = username.to_string();
+        self
+    }
+
+    /// sasl_password
+    ///
+    /// password for the ``PLAIN`` and ``SCRAM`` SASL mechanisms
+    ///
+    pub fn sasl_password(mut self, password: &str) -> Self {
+        self.config.sasl_password = password.to_string();
+        self
+    }
+
+    /// sasl_oauthbearer_config
+    ///
+    /// optional ``sasl.oauthbearer.config`` passed to the
+    /// ``OAUTHBEARER`` token provider (or ``librdkafka``'s unsecured
+    /// JWT builder without a provider)
+    ///
+    pub fn sasl_oauthbearer_config(mut self, oauthbearer_config: &str) -> Self {
+        self.config.sasl_oauthbearer_config = oauthbearer_config.to_string();
+        self
+    }
+
+    /// oauth_token_provider
+    ///
+    /// [`KafkaOAuthTokenProvider`](crate::api::kafka_oauth_token_provider::KafkaOAuthTokenProvider)
+    /// for the ``OAUTHBEARER`` SASL mechanism. Without a provider
+    /// ``librdkafka`` fetches the tokens itself (set with
+    /// ``KAFKA_RDKAFKA_SASL_OAUTHBEARER_METHOD=oidc`` and the
+    /// ``KAFKA_RDKAFKA_SASL_OAUTHBEARER_*`` properties).
+    ///
+    pub fn oauth_token_provider(
+        mut self,
+        token_provider: Arc<dyn KafkaOAuthTokenProvider>,
+    ) -> Self {
+        self.config.oauth_token_provider = Some(token_provider);
+        self
+    }
+
+    /// dead_letter_sink
+    ///
+    /// add a
+    /// [`KafkaDeadLetterSink`](crate::dlq::kafka_dead_letter_sink::KafkaDeadLetterSink)
+    /// for messages that fail with a fatal error or exhaust the
+    /// retry policy
+    ///
+    pub fn dead_letter_sink(mut self, sink: KafkaDeadLetterSink) -> Self {
+        self.config.dead_letter_sinks.push(sink);
+        self
+    }
+
+    /// stats_handler
+    ///
+    /// [`KafkaStatsHandler`](crate::stats::kafka_stats_handler::KafkaStatsHandler)
+    /// called with every worker's statistics (requires a positive
+    /// ``statistics_interval_ms``)
+    ///
+    pub fn stats_handler(
+        mut self,
+        stats_handler: Arc<dyn KafkaStatsHandler>,
+    ) -> Self {
+        self.config.stats_handler = Some(stats_handler);
+        self
+    }
+
+    /// build
+    ///
+    /// Validate the values and build the
+    /// [`KafkaClientConfig`](crate::config::kafka_client_config::KafkaClientConfig).
+    /// A disabled config skips validation.
+    ///
+    /// # Errors
+    ///
+    /// [`KafkaThreadpoolError::ConfigInvalid`] if a value is invalid or
+    /// [`KafkaThreadpoolError::TlsAssetMissing`] if a tls asset path
+    /// does not exist
+    ///
+    pub fn build(self) -> Result<KafkaClientConfig, KafkaThreadpoolError> {
+        let mut config = self.config;
+        config.security_protocol = match self.security_protocol {
+            Some(security_protocol) => security_protocol,
+            None => {
+                if config.tls_key.is_empty()
+                    && config.tls_cert.is_empty()
+                    && config.tls_ca.is_empty()
+                {
+                    KafkaSecurityProtocol::Plaintext
+                } else {
+                    KafkaSecurityProtocol::Ssl
+                }
+            }
+        };
+        if !config.is_enabled {
+            return Ok(config);
+        }
+        if config.broker_list.is_empty()
+            || config.broker_list.iter().any(|broker| broker.is_empty())
+        {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_BROKERS",
+                &format!(
+                    "please set at least one broker with no empty \
+                    entries brokers={:?}",
+                    config.broker_list
+                ),
+            ));
+        }
+        if config.num_threads == 0 {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_NUM_THREADS",
+                "please set to a number between 1-255",
+            ));
+        }
+        if config.publish_batch_size == 0 {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_PUBLISH_BATCH_SIZE",
+                "please set to a number between [1, inf]",
+            ));
+        }
+        if config.retry_policy.initial_backoff.is_zero() {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_PUBLISH_RETRY_INTERVAL_SEC",
+                "please set to a positive interval of at least 1ms",
+            ));
+        }
+        if config.retry_policy.max_backoff < config.retry_policy.initial_backoff
+        {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_PUBLISH_RETRY_MAX_INTERVAL_SEC",
+                "please set to an interval greater than or equal to \
+                KAFKA_PUBLISH_RETRY_INTERVAL_SEC",
+            ));
+        }
+        if !config.retry_policy.multiplier.is_finite()
+            || config.retry_policy.multiplier < 1.0
+        {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_PUBLISH_RETRY_MULTIPLIER",
+                "please set to a float between [1.0, inf]",
+            ));
+        }
+        if !(0.0..=1.0).contains(&config.retry_policy.jitter) {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_PUBLISH_RETRY_JITTER",
+                "please set to a float between [0.0, 1.0]",
+            ));
+        }
+        if config
+            .transactional_id_prefix
+            .chars()
+            .any(|c| c.is_whitespace())
+        {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_TRANSACTIONAL_ID_PREFIX",
+                "please set a transactional id prefix without whitespace",
+            ));
+        }
+        if config.statistics_interval_ms > 86_400_000 {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_STATISTICS_INTERVAL_MS",
+                "please set to a number of milliseconds between \
+                [0, 86400000]",
+            ));
+        }
+        if config.stats_handler.is_some() && config.statistics_interval_ms == 0
+        {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_STATISTICS_INTERVAL_MS",
+                "a KafkaStatsHandler requires a positive statistics interval",
+            ));
+        }
+        let invalid_count = |count: i32| count == 0 || count < -1;
+        if invalid_count(config.default_num_partitions) {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_TOPIC_NUM_PARTITIONS",
+                "please set to -1 (broker default) or a number between \
+                [1, inf]",
+            ));
+        }
+        if invalid_count(config.default_replication_factor) {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_TOPIC_REPLICATION_FACTOR",
+                "please set to -1 (broker default) or a number between \
+                [1, inf]",
+            ));
+        }
+        for spec in config.topic_specs.iter() {
+            if spec.name.is_empty()
+                || invalid_count(spec.num_partitions)
+                || invalid_count(spec.replication_factor)
+            {
+                return Err(KafkaThreadpoolError::config_invalid(
+                    "topic_spec",
+                    &format!(
+                        "please set a topic name with -1 (broker default) \
+                        or a positive number of partitions and replicas \
+                        {spec}"
+                    ),
+                ));
+            }
+        }
+        if config.admin_timeout.is_zero() {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_ADMIN_TIMEOUT_SEC",
+                "please set to a positive interval of at least 1ms",
+            ));
+        }
+        if config.metadata_timeout.is_zero() {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_METADATA_TIMEOUT_SEC",
+                "please set to a positive interval of at least 1ms",
+            ));
+        }
+        if config.watermark_timeout.is_zero() {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_METADATA_WATERMARK_TIMEOUT_SEC",
+                "please set to a positive interval of at least 1ms",
+            ));
+        }
+        if config.watermark_concurrency == 0 {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_METADATA_WATERMARK_CONCURRENCY",
+                "please set to a number between [1, inf]",
+            ));
+        }
+        let thresholds = &config.health_thresholds;
+        if thresholds.degraded_queue_depth > 0
+            && thresholds.unhealthy_queue_depth > 0
+            && thresholds.unhealthy_queue_depth
+                < thresholds.degraded_queue_depth
+        {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_HEALTH_UNHEALTHY_QUEUE_DEPTH",
+                "please set to 0 (disabled) or a number greater than or \
+                equal to KAFKA_HEALTH_DEGRADED_QUEUE_DEPTH",
+            ));
+        }
+        if !thresholds.degraded_delivery_age.is_zero()
+            && !thresholds.unhealthy_delivery_age.is_zero()
+            && thresholds.unhealthy_delivery_age
+                < thresholds.degraded_delivery_age
+        {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_HEALTH_UNHEALTHY_DELIVERY_SEC",
+                "please set to 0 (disabled) or an interval greater than \
+                or equal to KAFKA_HEALTH_DEGRADED_DELIVERY_SEC",
+            ));
+        }
+        if thresholds.broker_down_ttl.is_zero() {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_HEALTH_BROKER_DOWN_SEC",
+                "please set to a positive interval of at least 1ms",
+            ));
+        }
+        if config.idle_sleep_sec == 0 {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_PUBLISH_IDLE_INTERVAL_SEC",
+                "please set to a positive interval of at least 1ms",
+            ));
+        }
+        validate_tls_asset("KAFKA_TLS_CLIENT_KEY", &config.tls_key)?;
+        validate_tls_asset("KAFKA_TLS_CLIENT_CERT", &config.tls_cert)?;
+        validate_tls_asset("KAFKA_TLS_CLIENT_CA", &config.tls_ca)?;
+        if config.security_protocol.uses_sasl() {
+            match config.sasl_mechanism {
+                None => {
+                    return Err(KafkaThreadpoolError::config_invalid(
+                        "KAFKA_SASL_MECHANISM",
+                        &format!(
+                            "please set a sasl mechanism for \
+                            security_protocol={}",
+                            config.security_protocol
+                        ),
+                    ));
+                }
+                Some(KafkaSaslMechanism::OAuthBearer) => {
+                    // without a token provider librdkafka handles
+                    // OAUTHBEARER (sasl.oauthbearer.method=oidc or
+                    // enable.sasl.oauthbearer.unsecure.jwt=true) and
+                    // it rejects the provider's refresh callback with
+                    // either of them
+                    let uses_librdkafka_tokens = matches!(
+                        config.rdkafka_properties.get("sasl.oauthbearer.method"),
+                        Some(method) if method.eq_ignore_ascii_case("oidc")
+                    ) || matches!(
+                        config
+                            .rdkafka_properties
+                            .get("enable.sasl.oauthbearer.unsecure.jwt"),
+                        Some(enabled) if enabled == "true"
+                    );
+                    if config.oauth_token_provider.is_some()
+                        && uses_librdkafka_tokens
+                    {
+                        return Err(KafkaThreadpoolError::config_invalid(
+                            "KAFKA_SASL_MECHANISM",
+                            "a KafkaOAuthTokenProvider cannot be used with \
+                            sasl.oauthbearer.method=oidc or \
+                            enable.sasl.oauthbearer.unsecure.jwt=true",
+                        ));
+                    }
+                }
+                Some(_) => {
+                    if config.sasl_username.is_empty()
+                        || config.sasl_password.is_empty()
+                    {
+                        return Err(KafkaThreadpoolError::config_invalid(
+                            "KAFKA_SASL_USERNAME",
+                            "please set the sasl username and password",
+                        ));
+                    }
+                }
+            }
+        }
+        for sink in config.dead_letter_sinks.iter() {
+            match sink {
+                KafkaDeadLetterSink::Topic(topic) if topic.is_empty() => {
+                    return Err(KafkaThreadpoolError::config_invalid(
+                        "KAFKA_DLQ_TOPIC",
+                        "please set a dead-letter topic name",
+                    ));
+                }
+                KafkaDeadLetterSink::File(path) => {
+                    let parent_exists =
+                        match std::path::Path::new(path).parent() {
+                            Some(parent) => {
+                                parent.as_os_str().is_empty() || parent.exists()
+                            }
+                            None => false,
+                        };
+                    if path.is_empty() || !parent_exists {
+                        return Err(KafkaThreadpoolError::config_invalid(
+                            "KAFKA_DLQ_FILE",
+                            &format!(
+                                "please set a dead-letter file path in an \
+                                existing directory path={path}"
+                            ),
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+        // a pass-through enable.idempotence is the same as
+        // enable_idempotence as long as it does not turn off the
+        // idempotence the transactional mode requires
+        if let Some(value) =
+            config.rdkafka_properties.remove("enable.idempotence")
+        {
+            let enable_idempotence = match value.as_str() {
+                "true" | "1" => true,
+                "false" | "0" => false,
+                _ => {
+                    return Err(KafkaThreadpoolError::config_invalid(
+                        "enable.idempotence",
+                        &format!(
+                            "invalid value={value} please set to true or false"
+                        ),
+                    ))
+                }
+            };
+            if !enable_idempotence
+                && (config.enable_idempotence
+                    || !config.transactional_id_prefix.is_empty())
+            {
+                return Err(KafkaThreadpoolError::config_invalid(
+                    "enable.idempotence",
+                    "cannot be disabled with KAFKA_ENABLE_IDEMPOTENCE or \
+                    KAFKA_TRANSACTIONAL_ID_PREFIX set",
+                ));
+            }
+            config.enable_idempotence = enable_idempotence;
+        }
+        if let Some(key) = config
+            .rdkafka_properties
+            .keys()
+            .find(|key| is_managed_rdkafka_property(key))
+        {
+            return Err(KafkaThreadpoolError::config_invalid(
+                key,
+                "this rdkafka property is managed by the kafka_threadpool \
+                and cannot be overridden",
+            ));
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builder() -> KafkaClientConfigBuilder {
+        KafkaClientConfigBuilder::new()
+            .brokers(vec!["localhost:9092".to_string()])
+    }
+
+    fn invalid_key(
+        res: Result<KafkaClientConfig, KafkaThreadpoolError>,
+    ) -> String {
+        match res {
+            Err(KafkaThreadpoolError::ConfigInvalid { key, .. }) => key,
+            Err(e) => panic!("expected ConfigInvalid got {e}"),
+            Ok(_) => panic!("expected ConfigInvalid got a config"),
+        }
+    }
+
+    #[test]
+    fn pass_through_properties_are_kept() {
+        let config = builder()
+            .rdkafka_property("linger.ms", "5")
+            .rdkafka_property("acks", "all")
+            .build()
+            .unwrap();
+        assert_eq!(config.rdkafka_properties.len(), 2);
+        assert_eq!(
+            config
+                .rdkafka_properties
+                .get("linger.ms")
+                .map(String::as_str),
+            Some("5")
+        );
+    }
+
+    #[test]
+    fn managed_properties_are_config_invalid() {
+        for key in
+            ["bootstrap.servers", "security.protocol", "transactional.id"]
+        {
+            assert_eq!(
+                invalid_key(builder().rdkafka_property(key, "value").build()),
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn pass_through_enable_idempotence_sets_enable_idempotence() {
+        for value in ["true", "1"] {
+            let config = builder()
+                .rdkafka_property("enable.idempotence", value)
+                .build()
+                .unwrap();
+            assert!(config.enable_idempotence);
+            assert!(!config
+                .rdkafka_properties
+                .contains_key("enable.idempotence"));
+        }
+        let config = builder()
+            .rdkafka_property("enable.idempotence", "false")
+            .build()
+            .unwrap();
+        assert!(!config.enable_idempotence);
+        assert!(config.rdkafka_properties.is_empty());
+    }
+
+    #[test]
+    fn pass_through_enable_idempotence_cannot_disable_idempotence() {
+        let res = builder()
+            .enable_idempotence(true)
+            .rdkafka_property("enable.idempotence", "false")
+            .build();
+        assert_eq!(invalid_key(res), "enable.idempotence");
+        let res = builder()
+            .transactional_id_prefix("billing")
+            .rdkafka_property("enable.idempotence", "false")
+            .build();
+        assert_eq!(invalid_key(res), "enable.idempotence");
+        let config = builder()
+            .transactional_id_prefix("billing")
+            .rdkafka_property("enable.idempotence", "true")
+            .build()
+            .unwrap();
+        assert!(config.enable_idempotence);
+    }
+
+    #[test]
+    fn invalid_pass_through_enable_idempotence_is_config_invalid() {
+        let res = builder()
+            .rdkafka_property("enable.idempotence", "yes please")
+            .build();
+        assert_eq!(invalid_key(res), "enable.idempotence");
+    }
+}
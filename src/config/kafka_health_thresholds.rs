@@ -0,0 +1,80 @@
+//! Thresholds for the
+//! [`KafkaPublisher::health()`](crate::kafka_publisher::KafkaPublisher::health)
+//! check where a backed up work vec or a worker pool that has not
+//! delivered a message in too long is reported as ``Degraded`` or
+//! ``Unhealthy``
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use kafka_threadpool::config::kafka_client_config::KafkaClientConfig;
+//! use kafka_threadpool::config::kafka_health_thresholds::KafkaHealthThresholds;
+//! let config = KafkaClientConfig::builder()
+//!     .brokers(vec!["localhost:9092".to_string()])
+//!     .health_thresholds(KafkaHealthThresholds {
+//!         degraded_queue_depth: 1000,
+//!         unhealthy_queue_depth: 10000,
+//!         degraded_delivery_age: Duration::from_secs(30),
+//!         unhealthy_delivery_age: Duration::from_secs(120),
+//!         broker_down_ttl: Duration::from_secs(30),
+//!     })
+//!     .build()
+//!     .unwrap();
+//! ```
+//!
+use std::time::Duration;
+
+/// KafkaHealthThresholds
+///
+/// Controls when the health check reports a ``Degraded`` or
+/// ``Unhealthy`` threadpool
+///
+/// * `degraded_queue_depth` - number of queued messages that
+/// degrades the threadpool (``0`` disables the check)
+/// * `unhealthy_queue_depth` - number of queued messages that
+/// makes the threadpool unhealthy (``0`` disables the check)
+/// * `degraded_delivery_age` - time since the last successful
+/// delivery that degrades the threadpool while there are messages
+/// to publish (``0`` disables the check)
+/// * `unhealthy_delivery_age` - time since the last successful
+/// delivery that makes the threadpool unhealthy while there are
+/// messages to publish (``0`` disables the check)
+/// * `broker_down_ttl` - how long an all brokers down event counts
+/// without another connection failure (``librdkafka`` does not
+/// report reconnects so an idle worker would otherwise stay down)
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KafkaHealthThresholds {
+    pub degraded_queue_depth: usize,
+    pub unhealthy_queue_depth: usize,
+    pub degraded_delivery_age: Duration,
+    pub unhealthy_delivery_age: Duration,
+    pub broker_down_ttl: Duration,
+}
+
+impl Default for KafkaHealthThresholds {
+    fn default() -> Self {
+        KafkaHealthThresholds {
+            degraded_queue_depth: 0,
+            unhealthy_queue_depth: 0,
+            degraded_delivery_age: Duration::from_secs(60),
+            unhealthy_delivery_age: Duration::from_secs(300),
+            broker_down_ttl: Duration::from_secs(60),
+        }
+    }
+}
+
+impl std::fmt::Display for KafkaHealthThresholds {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "queue_depth degraded={} unhealthy={} \
+            delivery_age degraded={:?} unhealthy={:?} \
+            broker_down_ttl={:?}",
+            self.degraded_queue_depth,
+            self.unhealthy_queue_depth,
+            self.degraded_delivery_age,
+            self.unhealthy_delivery_age,
+            self.broker_down_ttl
+        )
+    }
+}
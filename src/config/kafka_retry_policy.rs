@@ -0,0 +1,292 @@
+//! Retry policy for publishing messages where each failed publish
+//! waits with an exponential backoff (plus jitter) before retrying
+//! until the message is delivered, the error is not retriable, the
+//! max number of attempts is reached or the max total elapsed time
+//! is exceeded
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use kafka_threadpool::config::kafka_client_config::KafkaClientConfig;
+//! use kafka_threadpool::config::kafka_retry_policy::KafkaRetryPolicy;
+//! let config = KafkaClientConfig::builder()
+//!     .brokers(vec!["localhost:9092".to_string()])
+//!     .retry_policy(KafkaRetryPolicy {
+//!         max_attempts: 5,
+//!         initial_backoff: Duration::from_millis(100),
+//!         max_backoff: Duration::from_secs(5),
+//!         multiplier: 2.0,
+//!         jitter: 0.2,
+//!         max_elapsed: Duration::from_secs(30),
+//!     })
+//!     .build()
+//!     .unwrap();
+//! ```
+//!
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::api::kafka_delivery_error::KafkaDeliveryError;
+
+/// KafkaRetryPolicy
+///
+/// Controls how many times and how often a worker thread retries
+/// a failed publish before giving up on the message
+///
+/// * `max_attempts` - max number of publish attempts per message
+/// (``0`` retries until ``max_elapsed``)
+/// * `initial_backoff` - time to sleep before the first retry
+/// * `max_backoff` - upper bound for the time between retries
+/// * `multiplier` - backoff growth factor applied after each retry
+/// * `jitter` - fraction between ``0.0`` and ``1.0`` of random
+/// variation applied to each backoff
+/// * `max_elapsed` - max total time spent publishing a message
+/// (``0`` is unbounded)
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct KafkaRetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+    pub jitter: f64,
+    pub max_elapsed: Duration,
+}
+
+impl Default for KafkaRetryPolicy {
+    fn default() -> Self {
+        KafkaRetryPolicy {
+            max_attempts: 10,
+            initial_backoff: Duration::from_millis(1000),
+            max_backoff: Duration::from_millis(30000),
+            multiplier: 2.0,
+            jitter: 0.2,
+            max_elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl KafkaRetryPolicy {
+    /// backoff
+    ///
+    /// Get the time to sleep before the next retry
+    ///
+    /// # Arguments
+    ///
+    /// * `attempt` - number of publish attempts that already failed
+    /// (starting at ``1``)
+    ///
+    /// # Returns
+    ///
+    /// ``initial_backoff * multiplier^(attempt - 1)`` capped at
+    /// ``max_backoff`` with up to ``+/- jitter`` random variation
+    ///
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let max_secs = self.max_backoff.as_secs_f64();
+        let base_secs = (self.initial_backoff.as_secs_f64()
+            * self.multiplier.powi(exponent))
+        .min(max_secs);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let delay_secs = if jitter > 0.0 {
+            base_secs
+                * rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter)
+        } else {
+            base_secs
+        };
+        Duration::from_secs_f64(delay_secs.clamp(0.0, max_secs))
+    }
+
+    /// next_retry
+    ///
+    /// Decide if a failed publish should be retried
+    ///
+    /// # Arguments
+    ///
+    /// * `attempt` - number of publish attempts that already failed
+    /// (starting at ``1``)
+    /// * `err` - the publish error
+    /// * `elapsed` - total time spent publishing the message
+    ///
+    /// # Returns
+    ///
+    /// ``Some(Duration)`` with the time to sleep before retrying or
+    /// ``None`` when the error is fatal or the retries are exhausted
+    ///
+    pub fn next_retry(
+        &self,
+        attempt: u32,
+        err: &KafkaDeliveryError,
+        elapsed: Duration,
+    ) -> Option<Duration> {
+        if !err.is_retriable() {
+            return None;
+        }
+        if self.max_attempts > 0 && attempt >= self.max_attempts {
+            return None;
+        }
+        let delay = self.backoff(attempt);
+        if !self.max_elapsed.is_zero() && elapsed + delay > self.max_elapsed {
+            return None;
+        }
+        Some(delay)
+    }
+}
+
+impl std::fmt::Display for KafkaRetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "max_attempts={} \
+            backoff initial={:?} max={:?} multiplier={} jitter={} \
+            max_elapsed={:?}",
+            self.max_attempts,
+            self.initial_backoff,
+            self.max_backoff,
+            self.multiplier,
+            self.jitter,
+            self.max_elapsed
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rdkafka::types::RDKafkaErrorCode;
+
+    use super::*;
+
+    fn no_jitter_policy() -> KafkaRetryPolicy {
+        KafkaRetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(1000),
+            multiplier: 2.0,
+            jitter: 0.0,
+            max_elapsed: Duration::ZERO,
+        }
+    }
+
+    fn broker_err(code: RDKafkaErrorCode) -> KafkaDeliveryError {
+        KafkaDeliveryError::Broker {
+            code: Some(code),
+            reason: format!("{code:?}"),
+        }
+    }
+
+    #[test]
+    fn backoff_grows_with_the_multiplier_until_the_cap() {
+        let policy = no_jitter_policy();
+        let backoffs: Vec<u64> = (1..=6)
+            .map(|attempt| policy.backoff(attempt).as_millis() as u64)
+            .collect();
+        assert_eq!(backoffs, vec![100, 200, 400, 800, 1000, 1000]);
+        // attempt 0 is treated like the first retry
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        // huge attempts do not overflow past the cap
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn backoff_jitter_stays_in_bounds() {
+        let policy = KafkaRetryPolicy {
+            jitter: 0.5,
+            ..no_jitter_policy()
+        };
+        for _ in 0..1000 {
+            // base 200ms +/- 50%
+            let delay = policy.backoff(2);
+            assert!(delay >= Duration::from_millis(100), "{delay:?}");
+            assert!(delay <= Duration::from_millis(300), "{delay:?}");
+            // jitter never goes past the cap
+            assert!(policy.backoff(10) <= policy.max_backoff);
+        }
+    }
+
+    #[test]
+    fn backoff_clamps_out_of_range_jitter() {
+        let policy = KafkaRetryPolicy {
+            jitter: 3.0,
+            ..no_jitter_policy()
+        };
+        for _ in 0..1000 {
+            assert!(policy.backoff(2) <= Duration::from_millis(400));
+        }
+        let policy = KafkaRetryPolicy {
+            jitter: -1.0,
+            ..no_jitter_policy()
+        };
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn next_retry_stops_after_max_attempts() {
+        let policy = no_jitter_policy();
+        let err = broker_err(RDKafkaErrorCode::RequestTimedOut);
+        assert_eq!(
+            policy.next_retry(4, &err, Duration::ZERO),
+            Some(Duration::from_millis(800))
+        );
+        assert_eq!(policy.next_retry(5, &err, Duration::ZERO), None);
+    }
+
+    #[test]
+    fn next_retry_without_max_attempts_retries_until_max_elapsed() {
+        let policy = KafkaRetryPolicy {
+            max_attempts: 0,
+            max_elapsed: Duration::from_secs(10),
+            ..no_jitter_policy()
+        };
+        let err = broker_err(RDKafkaErrorCode::BrokerTransportFailure);
+        assert_eq!(
+            policy.next_retry(1000, &err, Duration::from_secs(5)),
+            Some(Duration::from_millis(1000))
+        );
+        // the next backoff would end after max_elapsed
+        assert_eq!(
+            policy.next_retry(1000, &err, Duration::from_millis(9001)),
+            None
+        );
+        // the backoff that ends exactly at max_elapsed is allowed
+        assert_eq!(
+            policy.next_retry(1, &err, Duration::from_millis(9900)),
+            Some(Duration::from_millis(100))
+        );
+    }
+
+    #[test]
+    fn next_retry_without_limits_always_retries() {
+        let policy = KafkaRetryPolicy {
+            max_attempts: 0,
+            ..no_jitter_policy()
+        };
+        let err = broker_err(RDKafkaErrorCode::QueueFull);
+        assert_eq!(
+            policy.next_retry(u32::MAX, &err, Duration::from_secs(86400)),
+            Some(Duration::from_millis(1000))
+        );
+    }
+
+    #[test]
+    fn next_retry_does_not_retry_fatal_errors() {
+        let policy = no_jitter_policy();
+        for err in [
+            broker_err(RDKafkaErrorCode::UnknownTopicOrPartition),
+            broker_err(RDKafkaErrorCode::UnknownPartition),
+            broker_err(RDKafkaErrorCode::InvalidPartitions),
+            broker_err(RDKafkaErrorCode::MessageSizeTooLarge),
+            broker_err(RDKafkaErrorCode::RecordListTooLarge),
+            broker_err(RDKafkaErrorCode::TopicAuthorizationFailed),
+            broker_err(RDKafkaErrorCode::Fatal),
+            KafkaDeliveryError::Dropped,
+            KafkaDeliveryError::Disabled,
+        ] {
+            assert_eq!(policy.next_retry(1, &err, Duration::ZERO), None);
+        }
+        let err = broker_err(RDKafkaErrorCode::NotLeaderForPartition);
+        assert_eq!(
+            policy.next_retry(1, &err, Duration::ZERO),
+            Some(Duration::from_millis(100))
+        );
+    }
+}
@@ -1,4 +1,7 @@
 //! Module for building a static configuration object
-//! from environment variables
+//! programmatically or from environment variables
 //!
 pub mod kafka_client_config;
+pub mod kafka_client_config_builder;
+pub mod kafka_health_thresholds;
+pub mod kafka_retry_policy;
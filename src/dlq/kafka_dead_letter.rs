@@ -0,0 +1,254 @@
+//! class definition for
+//! [`KafkaDeadLetter`](crate::dlq::kafka_dead_letter::KafkaDeadLetter)
+//! which holds a failed message and the reason it failed
+//!
+use crate::api::kafka_delivery_error::KafkaDeliveryError;
+use crate::api::kafka_publish_message::KafkaPublishMessage;
+use crate::api::kafka_publish_message_type::KafkaPublishMessageType;
+
+/// header added to dead-letter topic messages with the
+/// original topic
+pub const DLQ_HEADER_ORIGINAL_TOPIC: &str = "ktp-dlq-original-topic";
+/// header added to dead-letter topic messages with the
+/// publish error
+pub const DLQ_HEADER_ERROR: &str = "ktp-dlq-error";
+/// header added to dead-letter topic messages with the
+/// number of publish attempts
+pub const DLQ_HEADER_ATTEMPTS: &str = "ktp-dlq-attempts";
+
+/// bytes_to_file_field
+///
+/// Internal helper for writing a key or payload on a single line
+/// where UTF-8 values are quoted, binary values are hex-encoded
+/// with a ``hex:`` prefix and missing values are ``null``
+///
+fn bytes_to_file_field(bytes: &Option<Vec<u8>>) -> String {
+    match bytes {
+        Some(bytes) => match std::str::from_utf8(bytes) {
+            Ok(utf8) => format!("{utf8:?}"),
+            Err(_) => {
+                let hex: String =
+                    bytes.iter().map(|b| format!("{b:02x}")).collect();
+                format!("hex:{hex}")
+            }
+        },
+        None => "null".to_string(),
+    }
+}
+
+/// KafkaDeadLetter
+///
+/// A message that could not be published
+///
+/// * `msg` - the original
+/// [`KafkaPublishMessage`](crate::api::kafka_publish_message::KafkaPublishMessage)
+/// * `error` - the last publish error
+/// * `attempts` - number of publish attempts
+/// * `failed_at_ms` - time the threadpool gave up on the message in
+/// milliseconds since the unix epoch
+///
+#[derive(Clone)]
+pub struct KafkaDeadLetter {
+    pub msg: KafkaPublishMessage,
+    pub error: KafkaDeliveryError,
+    pub attempts: u32,
+    pub failed_at_ms: i64,
+}
+
+impl KafkaDeadLetter {
+    /// new
+    ///
+    /// Create a
+    /// [`KafkaDeadLetter`](crate::dlq::kafka_dead_letter::KafkaDeadLetter)
+    /// stamped with the current time
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - the original message
+    /// * `error` - the last publish error
+    /// * `attempts` - number of publish attempts
+    ///
+    pub fn new(
+        msg: KafkaPublishMessage,
+        error: KafkaDeliveryError,
+        attempts: u32,
+    ) -> Self {
+        let failed_at_ms = match std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+        {
+            Ok(since_epoch) => since_epoch.as_millis() as i64,
+            Err(_) => 0,
+        };
+        KafkaDeadLetter {
+            msg,
+            error,
+            attempts,
+            failed_at_ms,
+        }
+    }
+
+    /// is_sensitive
+    ///
+    /// # Returns
+    ///
+    /// ``true`` if the original message uses the ``Sensitive``
+    /// message type and the key, headers and payload must not be
+    /// written in cleartext
+    ///
+    pub fn is_sensitive(&self) -> bool {
+        self.msg.msg_type == KafkaPublishMessageType::Sensitive
+    }
+
+    /// to_file_line
+    ///
+    /// Format the dead letter as a single line for the file sink.
+    /// ``Sensitive`` messages have the key, headers and payload
+    /// redacted.
+    ///
+    pub fn to_file_line(&self) -> String {
+        if self.is_sensitive() {
+            format!(
+                "failed_at_ms={} \
+                type={:?} \
+                topic={:?} \
+                key=REDACTED \
+                attempts={} \
+                error={:?} \
+                headers=REDACTED \
+                payload=REDACTED",
+                self.failed_at_ms,
+                self.msg.msg_type,
+                self.msg.topic,
+                self.attempts,
+                self.error.to_string()
+            )
+        } else {
+            format!(
+                "failed_at_ms={} \
+                type={:?} \
+                topic={:?} \
+                key={} \
+                attempts={} \
+                error={:?} \
+                headers={:?} \
+                payload={}",
+                self.failed_at_ms,
+                self.msg.msg_type,
+                self.msg.topic,
+                bytes_to_file_field(&self.msg.key),
+                self.attempts,
+                self.error.to_string(),
+                self.msg.headers,
+                bytes_to_file_field(&self.msg.payload)
+            )
+        }
+    }
+}
+
+impl std::fmt::Display for KafkaDeadLetter {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "KafkaDeadLetter \
+            attempts={} \
+            error={} \
+            msg={}",
+            self.attempts, self.error, self.msg
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rdkafka::types::RDKafkaErrorCode;
+
+    use super::*;
+    use crate::api::kafka_header::KafkaHeader;
+
+    fn dead_letter(
+        msg_type: KafkaPublishMessageType,
+        key: Option<Vec<u8>>,
+        payload: Option<Vec<u8>>,
+    ) -> KafkaDeadLetter {
+        let mut dead_letter = KafkaDeadLetter::new(
+            KafkaPublishMessage::new_from_bytes(
+                msg_type,
+                "orders",
+                key,
+                vec![KafkaHeader::from_str_value("user", "alice")],
+                payload,
+            ),
+            KafkaDeliveryError::Broker {
+                code: Some(RDKafkaErrorCode::MessageSizeTooLarge),
+                reason: "too large".to_string(),
+            },
+            3,
+        );
+        dead_letter.failed_at_ms = 1700000000000;
+        dead_letter
+    }
+
+    #[test]
+    fn file_line_quotes_utf8_fields() {
+        let line = dead_letter(
+            KafkaPublishMessageType::Data,
+            Some(b"order-1".to_vec()),
+            Some(b"{\"id\":1}".to_vec()),
+        )
+        .to_file_line();
+        assert_eq!(
+            line,
+            "failed_at_ms=1700000000000 type=Data topic=\"orders\" \
+            key=\"order-1\" attempts=3 \
+            error=\"broker error code=Some(MessageSizeTooLarge) \
+            reason=too large\" \
+            headers=[user=Some(\"alice\")] \
+            payload=\"{\\\"id\\\":1}\""
+        );
+    }
+
+    #[test]
+    fn file_line_hex_encodes_binary_and_marks_missing_fields() {
+        let line = dead_letter(
+            KafkaPublishMessageType::Data,
+            None,
+            Some(vec![0xff, 0x00, 0x0a]),
+        )
+        .to_file_line();
+        assert!(line.contains(" key=null "));
+        assert!(line.ends_with(" payload=hex:ff000a"));
+        // one dead letter per line
+        assert!(!line.contains('\n'));
+    }
+
+    #[test]
+    fn file_line_escapes_newlines() {
+        let line = dead_letter(
+            KafkaPublishMessageType::Data,
+            Some(b"a\nb".to_vec()),
+            Some(b"line 1\nline 2".to_vec()),
+        )
+        .to_file_line();
+        assert!(line.contains(" key=\"a\\nb\" "));
+        assert!(line.ends_with(" payload=\"line 1\\nline 2\""));
+        assert!(!line.contains('\n'));
+    }
+
+    #[test]
+    fn file_line_redacts_sensitive_key_headers_and_payload() {
+        let dead_letter = dead_letter(
+            KafkaPublishMessageType::Sensitive,
+            Some(b"ssn-123-45-6789".to_vec()),
+            Some(b"card=4111111111111111".to_vec()),
+        );
+        assert!(dead_letter.is_sensitive());
+        assert_eq!(
+            dead_letter.to_file_line(),
+            "failed_at_ms=1700000000000 type=Sensitive topic=\"orders\" \
+            key=REDACTED attempts=3 \
+            error=\"broker error code=Some(MessageSizeTooLarge) \
+            reason=too large\" \
+            headers=REDACTED payload=REDACTED"
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! enum for the supported dead-letter sinks and the trait for
+//! user callbacks
+//!
+use std::sync::Arc;
+
+use crate::dlq::kafka_dead_letter::KafkaDeadLetter;
+
+/// KafkaDeadLetterHandler
+///
+/// Implement this trait (or pass a closure) to receive every
+/// [`KafkaDeadLetter`](crate::dlq::kafka_dead_letter::KafkaDeadLetter)
+/// with the
+/// [`KafkaDeadLetterSink::Callback`](crate::dlq::kafka_dead_letter_sink::KafkaDeadLetterSink)
+/// sink. Worker threads call the handler inline so it should not
+/// block for long.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use kafka_threadpool::config::kafka_client_config::KafkaClientConfig;
+/// use kafka_threadpool::dlq::kafka_dead_letter::KafkaDeadLetter;
+/// use kafka_threadpool::dlq::kafka_dead_letter_sink::KafkaDeadLetterSink;
+/// let config = KafkaClientConfig::builder()
+///     .brokers(vec!["localhost:9092".to_string()])
+///     .dead_letter_sink(KafkaDeadLetterSink::Callback(Arc::new(
+///         |dead_letter: &KafkaDeadLetter| {
+///             println!("failed to publish {dead_letter}");
+///         },
+///     )))
+///     .build()
+///     .unwrap();
+/// ```
+///
+pub trait KafkaDeadLetterHandler: Send + Sync {
+    fn handle_dead_letter(&self, dead_letter: &KafkaDeadLetter);
+}
+
+impl<F> KafkaDeadLetterHandler for F
+where
+    F: Fn(&KafkaDeadLetter) + Send + Sync,
+{
+    fn handle_dead_letter(&self, dead_letter: &KafkaDeadLetter) {
+        self(dead_letter)
+    }
+}
+
+/// KafkaDeadLetterSink
+///
+/// Where failed messages go once the threadpool gives up
+///
+/// - ``Topic`` - publish the message into a dead-letter kafka topic
+/// with the original topic, error and attempt count added as headers
+/// - ``File`` - append one line per message to a local file
+/// (``Sensitive`` keys, headers and payloads are redacted)
+/// - ``Callback`` - call a user
+/// [`KafkaDeadLetterHandler`](crate::dlq::kafka_dead_letter_sink::KafkaDeadLetterHandler)
+///
+#[derive(Clone)]
+pub enum KafkaDeadLetterSink {
+    Topic(String),
+    File(String),
+    Callback(Arc<dyn KafkaDeadLetterHandler>),
+}
+
+impl std::fmt::Display for KafkaDeadLetterSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            KafkaDeadLetterSink::Topic(topic) => write!(f, "topic={topic}"),
+            KafkaDeadLetterSink::File(path) => write!(f, "file={path}"),
+            KafkaDeadLetterSink::Callback(_) => write!(f, "callback"),
+        }
+    }
+}
+
+impl std::fmt::Debug for KafkaDeadLetterSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{self}")
+    }
+}
@@ -0,0 +1,13 @@
+//! Dead-letter handling for messages that fail with a fatal error
+//! or exhaust the
+//! [`KafkaRetryPolicy`](crate::config::kafka_retry_policy::KafkaRetryPolicy)
+//!
+//! Supported sinks:
+//!
+//! - a dead-letter kafka topic
+//! - a local append-only file
+//! - a user callback
+//!
+pub mod kafka_dead_letter;
+pub mod kafka_dead_letter_sink;
+pub mod send_to_dead_letter_sinks;
@@ -0,0 +1,181 @@
+//! Send a
+//! [`KafkaDeadLetter`](crate::dlq::kafka_dead_letter::KafkaDeadLetter)
+//! to every configured
+//! [`KafkaDeadLetterSink`](crate::dlq::kafka_dead_letter_sink::KafkaDeadLetterSink)
+//!
+use std::io::Write;
+
+use log::error;
+use log::trace;
+
+use rdkafka::client::ClientContext;
+use rdkafka::message::OwnedHeaders;
+use rdkafka::producer::FutureProducer;
+
+use crate::api::kafka_header::KafkaHeader;
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
+use crate::config::kafka_client_config::KafkaClientConfig;
+use crate::dlq::kafka_dead_letter::KafkaDeadLetter;
+use crate::dlq::kafka_dead_letter::DLQ_HEADER_ATTEMPTS;
+use crate::dlq::kafka_dead_letter::DLQ_HEADER_ERROR;
+use crate::dlq::kafka_dead_letter::DLQ_HEADER_ORIGINAL_TOPIC;
+use crate::dlq::kafka_dead_letter_sink::KafkaDeadLetterSink;
+use crate::msg::publish_message::convert_kafka_headers_to_ownedheaders;
+use crate::msg::publish_message::publish_message;
+
+/// send_to_dead_letter_topic
+///
+/// Publish the dead letter into the dead-letter topic once (no
+/// retries) with the original topic, error and attempt count
+/// added as headers
+///
+/// # Arguments
+///
+/// * `producer` - the worker thread's producer
+/// * `dead_letter_topic` - kafka topic for dead letters
+/// * `dead_letter` - the failed message
+///
+async fn send_to_dead_letter_topic<C: ClientContext + 'static>(
+    producer: &FutureProducer<C>,
+    dead_letter_topic: &str,
+    dead_letter: &KafkaDeadLetter,
+) -> Result<(), KafkaThreadpoolError> {
+    if dead_letter.msg.topic == dead_letter_topic {
+        return Err(KafkaThreadpoolError::DeadLetter {
+            sink: dead_letter_topic.to_string(),
+            reason: "the message failed to publish into the \
+                dead-letter topic"
+                .to_string(),
+        });
+    }
+    let mut headers = dead_letter.msg.headers.clone();
+    headers.push(KafkaHeader::from_str_value(
+        DLQ_HEADER_ORIGINAL_TOPIC,
+        &dead_letter.msg.topic,
+    ));
+    headers.push(KafkaHeader::from_str_value(
+        DLQ_HEADER_ERROR,
+        &dead_letter.error.to_string(),
+    ));
+    headers.push(KafkaHeader::from_str_value(
+        DLQ_HEADER_ATTEMPTS,
+        &dead_letter.attempts.to_string(),
+    ));
+    let owned_headers =
+        convert_kafka_headers_to_ownedheaders(&headers, OwnedHeaders::new());
+    let mut dlq_msg = dead_letter.msg.clone();
+    dlq_msg.topic = dead_letter_topic.to_string();
+    // let librdkafka pick the dead-letter topic partition
+    dlq_msg.partition = None;
+    dlq_msg.delivery = None;
+    match publish_message(producer, &dlq_msg, &owned_headers).await {
+        Ok(_) => Ok(()),
+        Err(e) => Err(KafkaThreadpoolError::DeadLetter {
+            sink: dead_letter_topic.to_string(),
+            reason: e.to_string(),
+        }),
+    }
+}
+
+/// send_to_dead_letter_file
+///
+/// Append the dead letter as a single line to a local file with
+/// one ``write_all`` call. ``Sensitive`` messages are redacted.
+///
+/// # Arguments
+///
+/// * `path` - path to the append-only dead-letter file
+/// * `dead_letter` - the failed message
+///
+async fn send_to_dead_letter_file(
+    path: &str,
+    dead_letter: &KafkaDeadLetter,
+) -> Result<(), KafkaThreadpoolError> {
+    // one write per line so concurrent worker threads appending to
+    // the same file cannot interleave their lines
+    let line = format!("{}\n", dead_letter.to_file_line());
+    let file_path = path.to_string();
+    let res = tokio::task::spawn_blocking(move || {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&file_path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+    })
+    .await;
+    match res {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(KafkaThreadpoolError::DeadLetter {
+            sink: path.to_string(),
+            reason: e.to_string(),
+        }),
+        Err(e) => Err(KafkaThreadpoolError::DeadLetter {
+            sink: path.to_string(),
+            reason: e.to_string(),
+        }),
+    }
+}
+
+/// send_to_dead_letter_sinks
+///
+/// Worker threads call this after giving up on a message. Each
+/// configured sink gets the dead letter and sink failures are
+/// logged without stopping the other sinks.
+///
+/// # Arguments
+///
+/// * `log_label` - worker thread log label
+/// * `producer` - the worker thread's producer for the
+/// ``Topic`` sink
+/// * `config` - [`KafkaClientConfig`] with the
+/// ``dead_letter_sinks``
+/// * `dead_letter` - the failed message
+///
+/// # Returns
+///
+/// number of sinks that accepted the dead letter
+///
+pub async fn send_to_dead_letter_sinks<C: ClientContext + 'static>(
+    log_label: &str,
+    producer: &FutureProducer<C>,
+    config: &KafkaClientConfig,
+    dead_letter: &KafkaDeadLetter,
+) -> usize {
+    let mut num_sent = 0;
+    for sink in config.dead_letter_sinks.iter() {
+        let res = match sink {
+            KafkaDeadLetterSink::Topic(dead_letter_topic) => {
+                send_to_dead_letter_topic(
+                    producer,
+                    dead_letter_topic,
+                    dead_letter,
+                )
+                .await
+            }
+            KafkaDeadLetterSink::File(path) => {
+                send_to_dead_letter_file(path, dead_letter).await
+            }
+            KafkaDeadLetterSink::Callback(handler) => {
+                handler.handle_dead_letter(dead_letter);
+                Ok(())
+            }
+        };
+        match res {
+            Ok(()) => {
+                trace!(
+                    "{log_label} - sent dead letter topic={} to sink {sink}",
+                    dead_letter.msg.topic
+                );
+                num_sent += 1;
+            }
+            Err(e) => {
+                error!(
+                    "{log_label} - failed to send dead letter topic={} \
+                    to sink {sink} with err={e}",
+                    dead_letter.msg.topic
+                );
+            }
+        }
+    }
+    num_sent
+}
@@ -0,0 +1,445 @@
+//! Build a
+//! [`KafkaHealthReport`](crate::health::kafka_health_report::KafkaHealthReport)
+//! from the shared threadpool state
+//!
+use crate::config::kafka_client_config::KafkaClientConfig;
+use crate::health::kafka_health_report::KafkaHealthReport;
+use crate::health::kafka_health_status::KafkaHealthStatus;
+use crate::health::kafka_worker_state::KafkaWorkerState;
+use crate::pool::kafka_pool_state::KafkaPoolState;
+use crate::pool::kafka_work_queues::KafkaWorkQueues;
+
+/// get_kafka_health
+///
+/// Check the threadpool's health. The threadpool is:
+///
+/// - ``Unhealthy`` if it is disabled or shutting down, has no
+/// worker threads, every worker thread exited, every running
+/// worker thread's producer reported all brokers down, the work
+/// vec lock is poisoned or an ``unhealthy`` threshold was crossed
+/// - ``Degraded`` if some worker threads exited, are retrying or
+/// reported all brokers down, or a ``degraded`` threshold was
+/// crossed
+/// - ``Healthy`` otherwise
+///
+/// A brokers down event only counts for
+/// ``config.health_thresholds.broker_down_ttl`` unless
+/// ``librdkafka`` keeps reporting connection failures.
+///
+/// The delivery age thresholds only apply while there are messages
+/// to publish (queued or in flight) so an idle threadpool stays
+/// ``Healthy``. The age is measured from the threadpool start if
+/// no message was delivered.
+///
+/// # Arguments
+///
+/// * `config` - initialized [`KafkaClientConfig`] with the
+/// [`KafkaHealthThresholds`](crate::config::kafka_health_thresholds::KafkaHealthThresholds)
+/// * `pool_state` - shared [`KafkaPoolState`] for the threadpool
+/// * `work_queues` - shared [`KafkaWorkQueues`] for the queue depth
+///
+/// # Returns
+///
+/// [`KafkaHealthReport`] with the status and the reasons for it
+///
+pub fn get_kafka_health(
+    config: &KafkaClientConfig,
+    pool_state: &KafkaPoolState,
+    work_queues: &KafkaWorkQueues,
+) -> KafkaHealthReport {
+    let mut report = KafkaHealthReport {
+        workers: pool_state
+            .health
+            .snapshot(config.health_thresholds.broker_down_ttl),
+        since_last_delivery: pool_state.health.since_last_delivery(),
+        is_shutting_down: pool_state.is_shutting_down(),
+        ..Default::default()
+    };
+    if !config.is_enabled {
+        report.add_finding(
+            KafkaHealthStatus::Unhealthy,
+            "kafka not enabled".to_string(),
+        );
+        return report;
+    }
+    if report.is_shutting_down {
+        report.add_finding(
+            KafkaHealthStatus::Unhealthy,
+            "threadpool is shutting down".to_string(),
+        );
+    }
+    for lockable_work_vec in work_queues.queues.iter() {
+        match lockable_work_vec.lock() {
+            Ok(locked_work_vec) => report.queue_depth += locked_work_vec.len(),
+            Err(e) => report.add_finding(
+                KafkaHealthStatus::Unhealthy,
+                format!("work vec lock poisoned err={e}"),
+            ),
+        }
+    }
+
+    let num_workers = report.workers.len();
+    let num_exited = report
+        .workers
+        .iter()
+        .filter(|worker| worker.state == KafkaWorkerState::Exited)
+        .count();
+    let num_running = num_workers - num_exited;
+    let num_brokers_down = report
+        .workers
+        .iter()
+        .filter(|worker| {
+            worker.state != KafkaWorkerState::Exited && worker.is_brokers_down()
+        })
+        .count();
+    let num_retrying = report
+        .workers
+        .iter()
+        .filter(|worker| worker.state == KafkaWorkerState::Retrying)
+        .count();
+    if num_workers == 0 {
+        report.add_finding(
+            KafkaHealthStatus::Unhealthy,
+            "no worker threads".to_string(),
+        );
+    } else if num_exited == num_workers {
+        report.add_finding(
+            KafkaHealthStatus::Unhealthy,
+            format!("all {num_workers} worker threads exited"),
+        );
+    } else if num_exited > 0 {
+        report.add_finding(
+            KafkaHealthStatus::Degraded,
+            format!("{num_exited}/{num_workers} worker threads exited"),
+        );
+    }
+    if num_running > 0 && num_brokers_down == num_running {
+        report.add_finding(
+            KafkaHealthStatus::Unhealthy,
+            "all brokers down for every worker thread".to_string(),
+        );
+    } else if num_brokers_down > 0 {
+        report.add_finding(
+            KafkaHealthStatus::Degraded,
+            format!(
+                "all brokers down for {num_brokers_down}/{num_running} \
+                worker threads"
+            ),
+        );
+    }
+    if num_retrying > 0 {
+        report.add_finding(
+            KafkaHealthStatus::Degraded,
+            format!("{num_retrying}/{num_workers} worker threads retrying"),
+        );
+    }
+
+    let thresholds = &config.health_thresholds;
+    let queue_depth = report.queue_depth;
+    if thresholds.unhealthy_queue_depth > 0
+        && queue_depth >= thresholds.unhealthy_queue_depth
+    {
+        report.add_finding(
+            KafkaHealthStatus::Unhealthy,
+            format!(
+                "queue_depth={queue_depth} >= {}",
+                thresholds.unhealthy_queue_depth
+            ),
+        );
+    } else if thresholds.degraded_queue_depth > 0
+        && queue_depth >= thresholds.degraded_queue_depth
+    {
+        report.add_finding(
+            KafkaHealthStatus::Degraded,
+            format!(
+                "queue_depth={queue_depth} >= {}",
+                thresholds.degraded_queue_depth
+            ),
+        );
+    }
+
+    let has_pending_work = queue_depth > 0
+        || report.workers.iter().any(|worker| {
+            worker.state == KafkaWorkerState::Running
+                || worker.state == KafkaWorkerState::Retrying
+        });
+    if has_pending_work {
+        let delivery_age = report
+            .since_last_delivery
+            .unwrap_or_else(|| pool_state.health.started.elapsed());
+        if !thresholds.unhealthy_delivery_age.is_zero()
+            && delivery_age >= thresholds.unhealthy_delivery_age
+        {
+            report.add_finding(
+                KafkaHealthStatus::Unhealthy,
+                format!(
+                    "no delivery in {delivery_age:?} >= {:?}",
+                    thresholds.unhealthy_delivery_age
+                ),
+            );
+        } else if !thresholds.degraded_delivery_age.is_zero()
+            && delivery_age >= thresholds.degraded_delivery_age
+        {
+            report.add_finding(
+                KafkaHealthStatus::Degraded,
+                format!(
+                    "no delivery in {delivery_age:?} >= {:?}",
+                    thresholds.degraded_delivery_age
+                ),
+            );
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::api::kafka_publish_message::KafkaPublishMessage;
+    use crate::api::kafka_publish_message_type::KafkaPublishMessageType;
+
+    fn build_config() -> KafkaClientConfig {
+        let mut config = KafkaClientConfig::default();
+        config.is_enabled = true;
+        config
+    }
+
+    fn health_with_workers(states: &[KafkaWorkerState]) -> KafkaPoolState {
+        let pool_state = KafkaPoolState::new();
+        for (worker_num, state) in states.iter().enumerate() {
+            let label = format!("test-tid-{}", worker_num + 1);
+            pool_state.health.register_worker(&label);
+            pool_state.health.set_state(&label, *state);
+        }
+        pool_state
+    }
+
+    fn queue_msgs(work_queues: &KafkaWorkQueues, num_msgs: usize) {
+        let mut work_vec = work_queues.queues[0].lock().unwrap();
+        for _ in 0..num_msgs {
+            work_vec.push(KafkaPublishMessage::new_from_bytes(
+                KafkaPublishMessageType::Data,
+                "orders",
+                None,
+                Vec::new(),
+                Some(b"payload".to_vec()),
+            ));
+        }
+    }
+
+    #[test]
+    fn idle_workers_are_healthy() {
+        let config = build_config();
+        let pool_state = health_with_workers(&[
+            KafkaWorkerState::Idle,
+            KafkaWorkerState::Idle,
+        ]);
+        let work_queues = KafkaWorkQueues::new(&config);
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Healthy);
+        assert!(report.reasons.is_empty());
+        assert!(report.is_ready());
+    }
+
+    #[test]
+    fn disabled_or_workerless_threadpools_are_unhealthy() {
+        let pool_state = KafkaPoolState::new();
+        let work_queues = KafkaWorkQueues::default();
+        let report = get_kafka_health(
+            &KafkaClientConfig::default(),
+            &pool_state,
+            &work_queues,
+        );
+        assert_eq!(report.status, KafkaHealthStatus::Unhealthy);
+        assert_eq!(report.reasons, vec!["kafka not enabled"]);
+
+        let report =
+            get_kafka_health(&build_config(), &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Unhealthy);
+        assert_eq!(report.reasons, vec!["no worker threads"]);
+    }
+
+    #[test]
+    fn shutting_down_is_unhealthy() {
+        let config = build_config();
+        let pool_state = health_with_workers(&[KafkaWorkerState::Idle]);
+        pool_state.start_shutdown();
+        let work_queues = KafkaWorkQueues::new(&config);
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert!(report.is_shutting_down);
+        assert_eq!(report.status, KafkaHealthStatus::Unhealthy);
+    }
+
+    #[test]
+    fn exited_and_retrying_workers() {
+        let config = build_config();
+        let work_queues = KafkaWorkQueues::new(&config);
+        let pool_state = health_with_workers(&[
+            KafkaWorkerState::Idle,
+            KafkaWorkerState::Exited,
+        ]);
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Degraded);
+        assert_eq!(report.reasons, vec!["1/2 worker threads exited"]);
+
+        let pool_state = health_with_workers(&[
+            KafkaWorkerState::Exited,
+            KafkaWorkerState::Exited,
+        ]);
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Unhealthy);
+        assert_eq!(report.reasons, vec!["all 2 worker threads exited"]);
+
+        let pool_state = health_with_workers(&[
+            KafkaWorkerState::Idle,
+            KafkaWorkerState::Retrying,
+        ]);
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Degraded);
+        assert_eq!(report.reasons, vec!["1/2 worker threads retrying"]);
+    }
+
+    #[test]
+    fn brokers_down_for_some_or_every_running_worker() {
+        let config = build_config();
+        let work_queues = KafkaWorkQueues::new(&config);
+        let pool_state = health_with_workers(&[
+            KafkaWorkerState::Idle,
+            KafkaWorkerState::Idle,
+        ]);
+        pool_state
+            .health
+            .record_brokers_down("test-tid-1", "all brokers down");
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Degraded);
+        assert_eq!(
+            report.reasons,
+            vec!["all brokers down for 1/2 worker threads"]
+        );
+
+        pool_state
+            .health
+            .record_brokers_down("test-tid-2", "all brokers down");
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Unhealthy);
+
+        // a delivery proves the worker reconnected
+        pool_state.health.record_delivery("test-tid-2");
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Degraded);
+    }
+
+    #[test]
+    fn brokers_down_for_an_exited_worker_is_ignored() {
+        let config = build_config();
+        let work_queues = KafkaWorkQueues::new(&config);
+        let pool_state = health_with_workers(&[
+            KafkaWorkerState::Idle,
+            KafkaWorkerState::Exited,
+        ]);
+        pool_state
+            .health
+            .record_brokers_down("test-tid-2", "all brokers down");
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Degraded);
+        assert_eq!(report.reasons, vec!["1/2 worker threads exited"]);
+    }
+
+    #[test]
+    fn brokers_down_expires_after_the_ttl() {
+        let mut config = build_config();
+        let work_queues = KafkaWorkQueues::new(&config);
+        let pool_state = health_with_workers(&[KafkaWorkerState::Idle]);
+        pool_state
+            .health
+            .record_brokers_down("test-tid-1", "all brokers down");
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Unhealthy);
+        assert_eq!(report.workers[0].brokers_up, Some(false));
+
+        // without another connection failure the down event is stale
+        config.health_thresholds.broker_down_ttl = Duration::ZERO;
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Healthy);
+        assert_eq!(report.workers[0].brokers_up, None);
+
+        // another connection failure keeps the worker down
+        config.health_thresholds.broker_down_ttl = Duration::from_secs(60);
+        pool_state
+            .health
+            .refresh_brokers_down("test-tid-1", "connection refused");
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Unhealthy);
+        assert_eq!(
+            report.workers[0].broker_error.as_deref(),
+            Some("connection refused")
+        );
+    }
+
+    #[test]
+    fn queue_depth_thresholds() {
+        let mut config = build_config();
+        config.health_thresholds.degraded_queue_depth = 2;
+        config.health_thresholds.unhealthy_queue_depth = 4;
+        let work_queues = KafkaWorkQueues::new(&config);
+        let pool_state = health_with_workers(&[KafkaWorkerState::Idle]);
+        pool_state.health.record_delivery("test-tid-1");
+
+        queue_msgs(&work_queues, 1);
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.queue_depth, 1);
+        assert_eq!(report.status, KafkaHealthStatus::Healthy);
+
+        queue_msgs(&work_queues, 1);
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Degraded);
+        assert_eq!(report.reasons, vec!["queue_depth=2 >= 2"]);
+
+        queue_msgs(&work_queues, 2);
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Unhealthy);
+        assert_eq!(report.reasons, vec!["queue_depth=4 >= 4"]);
+    }
+
+    #[test]
+    fn delivery_age_thresholds_only_apply_with_pending_work() {
+        let mut config = build_config();
+        config.health_thresholds.degraded_delivery_age =
+            Duration::from_millis(1);
+        config.health_thresholds.unhealthy_delivery_age =
+            Duration::from_secs(3600);
+        let work_queues = KafkaWorkQueues::new(&config);
+        let pool_state = health_with_workers(&[KafkaWorkerState::Idle]);
+        std::thread::sleep(Duration::from_millis(5));
+
+        // an idle threadpool that never delivered stays healthy
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.since_last_delivery, None);
+        assert_eq!(report.status, KafkaHealthStatus::Healthy);
+
+        // queued messages measure the age from the threadpool start
+        queue_msgs(&work_queues, 1);
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Degraded);
+
+        config.health_thresholds.unhealthy_delivery_age =
+            Duration::from_millis(2);
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Unhealthy);
+
+        // a running worker counts as pending work and a zero
+        // threshold disables the check
+        let work_queues = KafkaWorkQueues::new(&config);
+        pool_state
+            .health
+            .set_state("test-tid-1", KafkaWorkerState::Running);
+        config.health_thresholds.unhealthy_delivery_age = Duration::ZERO;
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Degraded);
+        config.health_thresholds.degraded_delivery_age = Duration::ZERO;
+        let report = get_kafka_health(&config, &pool_state, &work_queues);
+        assert_eq!(report.status, KafkaHealthStatus::Healthy);
+    }
+}
@@ -0,0 +1,89 @@
+//! class definition for the health check returned by
+//! [`KafkaPublisher::health()`](crate::kafka_publisher::KafkaPublisher::health)
+//!
+use std::time::Duration;
+
+use crate::health::kafka_health_status::KafkaHealthStatus;
+use crate::health::kafka_worker_health::KafkaWorkerHealth;
+
+/// KafkaHealthReport
+///
+/// Point-in-time health of the threadpool
+///
+/// * `status` - worst [`KafkaHealthStatus`] across every check
+/// * `reasons` - why the threadpool is not ``Healthy`` (empty
+/// when it is)
+/// * `workers` - [`KafkaWorkerHealth`] for each worker thread
+/// sorted by the worker thread log label
+/// * `queue_depth` - number of messages waiting in the work vec
+/// * `since_last_delivery` - optional - time since the most recent
+/// successful delivery across every worker thread (``None`` if no
+/// message was delivered)
+/// * `is_shutting_down` - ``true`` once a shutdown has started
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KafkaHealthReport {
+    pub status: KafkaHealthStatus,
+    pub reasons: Vec<String>,
+    pub workers: Vec<KafkaWorkerHealth>,
+    pub queue_depth: usize,
+    pub since_last_delivery: Option<Duration>,
+    pub is_shutting_down: bool,
+}
+
+impl KafkaHealthReport {
+    /// add_finding
+    ///
+    /// Record a check that failed and keep the worst status
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - status for the failed check
+    /// * `reason` - why the check failed
+    ///
+    pub fn add_finding(&mut self, status: KafkaHealthStatus, reason: String) {
+        self.status = self.status.max(status);
+        self.reasons.push(reason);
+    }
+
+    /// is_healthy
+    ///
+    /// # Returns
+    ///
+    /// ``true`` if every check passed
+    ///
+    pub fn is_healthy(&self) -> bool {
+        self.status == KafkaHealthStatus::Healthy
+    }
+
+    /// is_ready
+    ///
+    /// # Returns
+    ///
+    /// ``true`` if the threadpool can publish (``Healthy`` or
+    /// ``Degraded``) for use as a readiness probe
+    ///
+    pub fn is_ready(&self) -> bool {
+        self.status != KafkaHealthStatus::Unhealthy
+    }
+}
+
+impl std::fmt::Display for KafkaHealthReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "status={} workers={} queue_depth={} \
+            since_last_delivery={:?} shutting_down={}",
+            self.status,
+            self.workers.len(),
+            self.queue_depth,
+            self.since_last_delivery,
+            self.is_shutting_down
+        )?;
+        if !self.reasons.is_empty() {
+            write!(f, " reasons=[{}]", self.reasons.join(", "))?;
+        }
+        Ok(())
+    }
+}
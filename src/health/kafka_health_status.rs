@@ -0,0 +1,41 @@
+//! enum for the overall result of a
+//! [`KafkaHealthReport`](crate::health::kafka_health_report::KafkaHealthReport)
+//!
+
+/// KafkaHealthStatus
+///
+/// Overall threadpool health ordered from best to worst so the
+/// worst finding wins with ``max()``
+///
+/// - ``Healthy`` - every worker thread is publishing or idle with
+/// the brokers reachable
+/// - ``Degraded`` - the threadpool can publish but some workers
+/// exited, are retrying or cannot reach the brokers, or a
+/// ``Degraded`` threshold was crossed
+/// - ``Unhealthy`` - the threadpool cannot publish (disabled,
+/// shutting down, every worker exited or cannot reach the brokers)
+/// or an ``Unhealthy`` threshold was crossed
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KafkaHealthStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+impl Default for KafkaHealthStatus {
+    fn default() -> Self {
+        KafkaHealthStatus::Healthy
+    }
+}
+
+impl std::fmt::Display for KafkaHealthStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            KafkaHealthStatus::Healthy => write!(f, "healthy"),
+            KafkaHealthStatus::Degraded => write!(f, "degraded"),
+            KafkaHealthStatus::Unhealthy => write!(f, "unhealthy"),
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Thread-safe tracker for the state of each worker thread that
+//! the worker threads and their ``librdkafka`` client contexts
+//! update and the health check reads
+//!
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::health::kafka_worker_health::KafkaWorkerHealth;
+use crate::health::kafka_worker_state::KafkaWorkerState;
+
+/// KafkaWorkerHealthRecord
+///
+/// Internal per-worker state with the ``Instant`` of each event
+///
+#[derive(Debug, Clone)]
+struct KafkaWorkerHealthRecord {
+    state: KafkaWorkerState,
+    state_changed: Instant,
+    brokers_up: Option<bool>,
+    broker_error: Option<String>,
+    broker_event: Option<Instant>,
+    last_delivery: Option<Instant>,
+}
+
+impl KafkaWorkerHealthRecord {
+    fn new() -> Self {
+        KafkaWorkerHealthRecord {
+            state: KafkaWorkerState::Starting,
+            state_changed: Instant::now(),
+            brokers_up: None,
+            broker_error: None,
+            broker_event: None,
+            last_delivery: None,
+        }
+    }
+}
+
+/// KafkaHealthTracker
+///
+/// Latest health for each worker thread keyed by the worker thread
+/// log label
+///
+/// * `started` - when the threadpool started (the delivery age for
+/// a threadpool that never delivered a message)
+///
+#[derive(Debug)]
+pub struct KafkaHealthTracker {
+    pub started: Instant,
+    workers: RwLock<HashMap<String, KafkaWorkerHealthRecord>>,
+}
+
+impl Default for KafkaHealthTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KafkaHealthTracker {
+    /// new
+    ///
+    /// Create a tracker with no workers
+    ///
+    pub fn new() -> Self {
+        KafkaHealthTracker {
+            started: Instant::now(),
+            workers: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// update
+    ///
+    /// Internal helper for changing a worker's record (adding the
+    /// worker if it is not tracked yet)
+    ///
+    fn update<F: FnOnce(&mut KafkaWorkerHealthRecord)>(
+        &self,
+        label: &str,
+        f: F,
+    ) {
+        if let Ok(mut workers) = self.workers.write() {
+            f(workers
+                .entry(label.to_string())
+                .or_insert_with(KafkaWorkerHealthRecord::new));
+        }
+    }
+
+    /// register_worker
+    ///
+    /// Track a new worker thread in the ``Starting`` state
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - worker thread log label
+    ///
+    pub fn register_worker(&self, label: &str) {
+        if let Ok(mut workers) = self.workers.write() {
+            workers.insert(label.to_string(), KafkaWorkerHealthRecord::new());
+        }
+    }
+
+    /// set_state
+    ///
+    /// Change a worker's [`KafkaWorkerState`]
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - worker thread log label
+    /// * `state` - new state
+    ///
+    pub fn set_state(&self, label: &str, state: KafkaWorkerState) {
+        self.update(label, |record| {
+            if record.state != state {
+                record.state = state;
+                record.state_changed = Instant::now();
+            }
+        });
+    }
+
+    /// record_delivery
+    ///
+    /// Count a successful delivery which also proves the worker's
+    /// producer can reach the brokers
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - worker thread log label
+    ///
+    pub fn record_delivery(&self, label: &str) {
+        self.update(label, |record| {
+            let now = Instant::now();
+            record.last_delivery = Some(now);
+            record.brokers_up = Some(true);
+            record.broker_error = None;
+            record.broker_event = Some(now);
+        });
+    }
+
+    /// record_brokers_up
+    ///
+    /// Mark the worker's producer as connected to at least one
+    /// broker
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - worker thread log label
+    ///
+    pub fn record_brokers_up(&self, label: &str) {
+        self.update(label, |record| {
+            record.brokers_up = Some(true);
+            record.broker_error = None;
+            record.broker_event = Some(Instant::now());
+        });
+    }
+
+    /// record_brokers_down
+    ///
+    /// Mark the worker's producer as unable to reach any broker
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - worker thread log label
+    /// * `reason` - error reason from ``librdkafka``
+    ///
+    pub fn record_brokers_down(&self, label: &str, reason: &str) {
+        self.update(label, |record| {
+            record.brokers_up = Some(false);
+            record.broker_error = Some(reason.to_string());
+            record.broker_event = Some(Instant::now());
+        });
+    }
+
+    /// refresh_brokers_down
+    ///
+    /// Keep a worker's brokers down state current after another
+    /// connection failure (``librdkafka`` only reports all brokers
+    /// down once per outage)
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - worker thread log label
+    /// * `reason` - error reason from ``librdkafka``
+    ///
+    pub fn refresh_brokers_down(&self, label: &str, reason: &str) {
+        self.update(label, |record| {
+            if record.brokers_up == Some(false) {
+                record.broker_error = Some(reason.to_string());
+                record.broker_event = Some(Instant::now());
+            }
+        });
+    }
+
+    /// since_last_delivery
+    ///
+    /// # Returns
+    ///
+    /// time since the most recent successful delivery across every
+    /// worker thread or ``None`` if no message was delivered
+    ///
+    pub fn since_last_delivery(&self) -> Option<Duration> {
+        match self.workers.read() {
+            Ok(workers) => workers
+                .values()
+                .filter_map(|record| record.last_delivery)
+                .max()
+                .map(|last_delivery| last_delivery.elapsed()),
+            Err(_) => None,
+        }
+    }
+
+    /// snapshot
+    ///
+    /// # Arguments
+    ///
+    /// * `broker_down_ttl` - max age of a brokers down event before
+    /// the worker's broker state is reported as unknown so an idle
+    /// worker without a reconnect signal does not stay down forever
+    ///
+    /// # Returns
+    ///
+    /// ``Vec<KafkaWorkerHealth>`` with the current health for each
+    /// worker sorted by the worker thread log label
+    ///
+    pub fn snapshot(
+        &self,
+        broker_down_ttl: Duration,
+    ) -> Vec<KafkaWorkerHealth> {
+        let mut workers: Vec<KafkaWorkerHealth> = match self.workers.read() {
+            Ok(workers) => workers
+                .iter()
+                .map(|(label, record)| {
+                    let since_broker_event = record
+                        .broker_event
+                        .map(|broker_event| broker_event.elapsed());
+                    let is_stale_down = record.brokers_up == Some(false)
+                        && !matches!(
+                            since_broker_event,
+                            Some(age) if age < broker_down_ttl
+                        );
+                    KafkaWorkerHealth {
+                        label: label.clone(),
+                        state: record.state,
+                        since_state_change: record.state_changed.elapsed(),
+                        brokers_up: if is_stale_down {
+                            None
+                        } else {
+                            record.brokers_up
+                        },
+                        broker_error: record.broker_error.clone(),
+                        since_broker_event,
+                        since_last_delivery: record
+                            .last_delivery
+                            .map(|last_delivery| last_delivery.elapsed()),
+                    }
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        workers.sort_by(|a, b| a.label.cmp(&b.label));
+        workers
+    }
+}
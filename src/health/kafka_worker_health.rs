@@ -0,0 +1,75 @@
+//! class definition for a worker thread in the
+//! [`KafkaHealthReport`](crate::health::kafka_health_report::KafkaHealthReport)
+//!
+use std::time::Duration;
+
+use crate::health::kafka_worker_state::KafkaWorkerState;
+
+/// KafkaWorkerHealth
+///
+/// Point-in-time health for one worker thread
+///
+/// * `label` - worker thread log label
+/// * `state` - [`KafkaWorkerState`] of the worker thread
+/// * `since_state_change` - time spent in the current ``state``
+/// * `brokers_up` - optional - ``false`` after ``librdkafka``
+/// reported all brokers down and ``true`` after a delivery or a
+/// statistics snapshot with a connected broker (``None`` until the
+/// first event or once a brokers down event is older than the
+/// ``broker_down_ttl`` threshold without another connection
+/// failure)
+/// * `broker_error` - optional - reason from the last all brokers
+/// down event
+/// * `since_broker_event` - optional - time since the last broker
+/// connectivity event
+/// * `since_last_delivery` - optional - time since the worker
+/// thread's last successful delivery (``None`` if it has not
+/// delivered a message)
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KafkaWorkerHealth {
+    pub label: String,
+    pub state: KafkaWorkerState,
+    pub since_state_change: Duration,
+    pub brokers_up: Option<bool>,
+    pub broker_error: Option<String>,
+    pub since_broker_event: Option<Duration>,
+    pub since_last_delivery: Option<Duration>,
+}
+
+impl KafkaWorkerHealth {
+    /// is_brokers_down
+    ///
+    /// # Returns
+    ///
+    /// ``true`` if ``librdkafka`` reported all brokers down for the
+    /// worker thread's producer and it has not reconnected
+    ///
+    pub fn is_brokers_down(&self) -> bool {
+        self.brokers_up == Some(false)
+    }
+}
+
+impl std::fmt::Display for KafkaWorkerHealth {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let brokers = match self.brokers_up {
+            Some(true) => "up",
+            Some(false) => "down",
+            None => "unknown",
+        };
+        write!(
+            f,
+            "worker={} state={} for={:?} brokers={brokers} \
+            since_last_delivery={:?}",
+            self.label,
+            self.state,
+            self.since_state_change,
+            self.since_last_delivery
+        )?;
+        if let Some(err) = &self.broker_error {
+            write!(f, " broker_error={err}")?;
+        }
+        Ok(())
+    }
+}
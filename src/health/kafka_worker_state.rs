@@ -0,0 +1,42 @@
+//! enum for the state of a worker thread in the threadpool
+//!
+
+/// KafkaWorkerState
+///
+/// What a worker thread is currently doing
+///
+/// - ``Starting`` - the worker thread is creating its producers
+/// - ``Running`` - the worker thread is publishing messages
+/// - ``Idle`` - the worker thread is waiting for messages
+/// - ``Retrying`` - the worker thread is waiting to retry failed
+/// publishes
+/// - ``Exited`` - the worker thread stopped (shutdown or it failed
+/// to start)
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KafkaWorkerState {
+    Starting,
+    Running,
+    Idle,
+    Retrying,
+    Exited,
+}
+
+impl Default for KafkaWorkerState {
+    fn default() -> Self {
+        KafkaWorkerState::Starting
+    }
+}
+
+impl std::fmt::Display for KafkaWorkerState {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            KafkaWorkerState::Starting => write!(f, "starting"),
+            KafkaWorkerState::Running => write!(f, "running"),
+            KafkaWorkerState::Idle => write!(f, "idle"),
+            KafkaWorkerState::Retrying => write!(f, "retrying"),
+            KafkaWorkerState::Exited => write!(f, "exited"),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Health check for the threadpool with the state of each worker
+//! thread, broker connectivity, work vec depth and time since the
+//! last successful delivery
+//!
+//! Use
+//! [`KafkaPublisher::health()`](crate::kafka_publisher::KafkaPublisher::health)
+//! for readiness probes and tune the
+//! [`KafkaHealthThresholds`](crate::config::kafka_health_thresholds::KafkaHealthThresholds)
+//! with the ``KAFKA_HEALTH_*`` environment variables.
+//!
+//! A worker is marked brokers down when ``librdkafka`` reports all
+//! brokers down and up again after its next delivery or a
+//! statistics snapshot (``KAFKA_STATISTICS_INTERVAL_MS``) with a
+//! connected broker. Without either signal the down state expires
+//! after ``KAFKA_HEALTH_BROKER_DOWN_SEC`` once ``librdkafka`` stops
+//! reporting connection failures.
+//!
+pub mod get_kafka_health;
+pub mod kafka_health_report;
+pub mod kafka_health_status;
+pub mod kafka_health_tracker;
+pub mod kafka_worker_health;
+pub mod kafka_worker_state;
@@ -3,29 +3,72 @@
 //! [`start_threadpool()`](crate::start_threadpool). The
 //! [`KafkaPublisher`](crate::kafka_publisher) is how
 //! callers interface with the ``kafka_threadpool``'s
-//! ``lockable work Vec`` called ``publish_msgs``
+//! ``lockable work Vecs`` called ``publish_msgs``
 //! and can gracefully shutdown the threadpool.
 //!
 //! Example for shutting down the threadpool:
 //!
 //! ```rust
-//! my_kafka_publisher.shutdown().await.unwrap();
+//! let report = my_kafka_publisher
+//!     .shutdown_and_wait(std::time::Duration::from_secs(10))
+//!     .await
+//!     .unwrap();
+//! println!("{report}");
 //! ```
 //!
+//! All fallible methods return a
+//! [`KafkaThreadpoolError`](crate::api::kafka_threadpool_error::KafkaThreadpoolError)
+//!
 use std::collections::HashMap;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::sync::Mutex;
+use std::time::Duration;
 
 use log::info;
+use log::warn;
+
+use tokio::task::JoinHandle;
 
 use crate::api::add_messages_to_locked_work_vec::add_messages_to_locked_work_vec;
 use crate::api::build_kafka_publish_message::build_kafka_publish_message;
-use crate::api::drain_messages_from_locked_work_vec::drain_messages_from_locked_work_vec;
-use crate::api::get_kafka_consumer::get_kafka_consumer;
+use crate::api::kafka_delivery_error::KafkaDeliveryError;
+use crate::api::kafka_delivery_notifier::KafkaDeliveryFuture;
+use crate::api::kafka_delivery_notifier::KafkaDeliveryNotifier;
+use crate::api::kafka_header::KafkaHeader;
 use crate::api::kafka_publish_message::KafkaPublishMessage;
 use crate::api::kafka_publish_message_type::KafkaPublishMessageType;
+use crate::api::kafka_queue_add_status::KafkaQueueAddStatus;
+use crate::api::kafka_queue_overflow_policy::KafkaQueueOverflowPolicy;
+use crate::api::kafka_shutdown_report::KafkaShutdownReport;
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
+use crate::api::kafka_transaction::KafkaTransaction;
+use crate::api::kafka_transaction_notifier::KafkaTransactionFuture;
+use crate::api::kafka_transaction_notifier::KafkaTransactionNotifier;
 use crate::config::kafka_client_config::KafkaClientConfig;
-use crate::metadata::get_kafka_metadata::get_kafka_metadata;
+use crate::health::get_kafka_health::get_kafka_health;
+use crate::health::kafka_health_report::KafkaHealthReport;
+use crate::metadata::get_kafka_consumer_group_lag::get_kafka_consumer_group_lag;
+use crate::metadata::get_kafka_metadata::get_kafka_metadata_from_config;
+use crate::metadata::kafka_cluster_metadata::KafkaClusterMetadata;
+use crate::metadata::kafka_consumer_group_lag::KafkaConsumerGroupLag;
+#[cfg(feature = "metrics")]
+use crate::metrics::kafka_metrics_registry::render_prometheus_metrics;
+#[cfg(feature = "metrics")]
+use crate::metrics::record_metrics::set_queue_depth;
+use crate::pool::kafka_pool_state::KafkaPoolState;
+use crate::pool::kafka_pool_state::KAFKA_ABANDON_TIMEOUT;
+use crate::pool::kafka_work_queues::KafkaWorkQueues;
+#[cfg(feature = "schema-registry")]
+use crate::schema::kafka_schema::KafkaSchema;
+#[cfg(feature = "schema-registry")]
+use crate::schema::kafka_schema_registry_client::KafkaSchemaRegistryClient;
+#[cfg(feature = "serde")]
+use crate::serialize::payload_serializer::PayloadSerializer;
+#[cfg(feature = "serde")]
+use crate::serialize::serialize_kafka_publish_message::serialize_kafka_publish_message;
+use crate::stats::kafka_producer_stats::KafkaProducerStats;
+#[cfg(feature = "tracing")]
+use crate::trace::publish_span::attach_current_span;
 
 /// KafkaPublishMessage
 ///
@@ -33,15 +76,20 @@ use crate::metadata::get_kafka_metadata::get_kafka_metadata;
 ///
 /// * `config` - holds the static configuration for each
 /// thread (connectivity endpoints, tls assets, etc.)
-/// * `publish_msgs` - lockable work Vec that is shared
+/// * `publish_msgs` - lockable work Vecs that are shared
 /// by any thread(s) that want to publish
 /// [`KafkaPublishMessage`]
-/// messages to Kafka
+/// messages to Kafka (one per worker thread with the ``Key`` and
+/// ``TopicPartition`` ordering modes, see
+/// [`KafkaWorkQueues`](crate::pool::kafka_work_queues::KafkaWorkQueues))
+/// * `pool_state` - shared signals between the publisher
+/// and the worker threads
 ///
 #[derive(Default, Clone)]
 pub struct KafkaPublisher {
     pub config: KafkaClientConfig,
-    pub publish_msgs: Arc<Mutex<Vec<KafkaPublishMessage>>>,
+    pub publish_msgs: Arc<KafkaWorkQueues>,
+    pub pool_state: Arc<KafkaPoolState>,
 }
 
 impl KafkaPublisher {
@@ -51,21 +99,28 @@ impl KafkaPublisher {
     /// [`KafkaPublisher`](crate::kafka_publisher::KafkaPublisher)
     /// for interfacing with the backend kafka publish threadpool
     ///
+    /// # Errors
+    ///
+    /// [`KafkaThreadpoolError`] if the environment configuration
+    /// is invalid
+    ///
     /// # Examples
     ///
     /// ```rust
     /// use crate::kafka_publisher::KafkaPublisher;
-    /// let kp = KafkaPublisher::new();
+    /// let kp = KafkaPublisher::new().unwrap();
     /// ```
     ///
-    pub fn new() -> Self {
-        KafkaPublisher {
-            config: KafkaClientConfig::new(
-                &std::env::var("KAFKA_LOG_LABEL")
-                    .unwrap_or_else(|_| "ktp".to_string()),
-            ),
-            publish_msgs: Arc::new(Mutex::new(Vec::new())),
-        }
+    pub fn new() -> Result<Self, KafkaThreadpoolError> {
+        let config = KafkaClientConfig::new(
+            &std::env::var("KAFKA_LOG_LABEL")
+                .unwrap_or_else(|_| "ktp".to_string()),
+        )?;
+        Ok(KafkaPublisher {
+            publish_msgs: Arc::new(KafkaWorkQueues::new(&config)),
+            config,
+            pool_state: Arc::new(KafkaPoolState::new()),
+        })
     }
 
     /// is_enabled
@@ -96,20 +151,23 @@ impl KafkaPublisher {
     /// # Arguments
     ///
     /// * `topic` - kafka topic to publish the message into
-    /// * `key` - kafka partition key
+    /// * `key` - kafka partition key (an empty ``key`` publishes
+    /// a null key)
     /// * `headers` - optional - headers for the kafka message
     /// * `payload` - data within the kafka message
     ///
     /// Uses the utility API method:
-    /// [`add_messages_to_locked_work_vec`](crate::api::add_messages_to_locked_work_vec)
+    /// [`add_messages_to_bounded_work_vec`](crate::api::add_messages_to_bounded_work_vec)
+    /// which applies the ``KAFKA_QUEUE_OVERFLOW_POLICY`` when the
+    /// lockable work vector is full
     ///
     /// # Returns
     ///
-    /// ``Result<usize, String>``
+    /// ``Result<usize, KafkaThreadpoolError>``
     /// where
     /// - ``usize`` = updated number of messages in ``self.publish_msgs``
     /// after adding the new ``msg``
-    /// - ``String`` = error reason
+    /// - ``KafkaThreadpoolError`` = error reason
     ///
     pub async fn add_data_msg(
         &self,
@@ -117,7 +175,7 @@ impl KafkaPublisher {
         key: &str,
         headers: Option<HashMap<String, String>>,
         payload: &str,
-    ) -> Result<usize, String> {
+    ) -> Result<usize, KafkaThreadpoolError> {
         if self.config.is_enabled {
             let msg = build_kafka_publish_message(
                 KafkaPublishMessageType::Data,
@@ -127,7 +185,213 @@ impl KafkaPublisher {
                 payload,
             );
             let pub_vec: Vec<KafkaPublishMessage> = vec![msg];
-            add_messages_to_locked_work_vec(&self.publish_msgs, pub_vec)
+            self.enqueue_msgs(pub_vec).await
+        } else {
+            Ok(0)
+        }
+    }
+
+    /// add_bytes_msg
+    ///
+    /// Build a publishable data message with a binary key and
+    /// payload (Protobuf, Avro, etc.) and add it to the lockable
+    /// publish vector.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - kafka topic to publish the message into
+    /// * `key` - optional - kafka partition key (``None`` publishes
+    /// a null key so ``librdkafka`` picks the partition)
+    /// * `headers` - ordered
+    /// [`KafkaHeader`](crate::api::kafka_header::KafkaHeader) list
+    /// with binary values for the kafka message
+    /// * `payload` - optional - data within the kafka message
+    /// (``None`` publishes a tombstone)
+    ///
+    /// # Returns
+    ///
+    /// ``Result<usize, KafkaThreadpoolError>``
+    /// where
+    /// - ``usize`` = updated number of messages in ``self.publish_msgs``
+    /// after adding the new ``msg``
+    /// - ``KafkaThreadpoolError`` = error reason
+    ///
+    pub async fn add_bytes_msg(
+        &self,
+        topic: &str,
+        key: Option<&[u8]>,
+        headers: Vec<KafkaHeader>,
+        payload: Option<&[u8]>,
+    ) -> Result<usize, KafkaThreadpoolError> {
+        if self.config.is_enabled {
+            let msg = KafkaPublishMessage::new_from_bytes(
+                KafkaPublishMessageType::Data,
+                topic,
+                key.map(|key| key.to_vec()),
+                headers,
+                payload.map(|payload| payload.to_vec()),
+            );
+            self.enqueue_msgs(vec![msg]).await
+        } else {
+            Ok(0)
+        }
+    }
+
+    /// add_serialized_msg
+    ///
+    /// Serialize a typed value with a
+    /// [`PayloadSerializer`](crate::serialize::payload_serializer::PayloadSerializer)
+    /// (JSON, MessagePack, CBOR or a custom encoding), set the
+    /// ``content-type`` header and add the data message to the
+    /// lockable publish vector (requires the ``serde`` feature).
+    ///
+    /// # Arguments
+    ///
+    /// * `serializer` - payload encoding
+    /// * `topic` - kafka topic to publish the message into
+    /// * `key` - kafka partition key (an empty ``key`` publishes
+    /// a null key)
+    /// * `headers` - ordered
+    /// [`KafkaHeader`](crate::api::kafka_header::KafkaHeader) list
+    /// for the kafka message (a caller-supplied ``content-type``
+    /// header is kept)
+    /// * `value` - any ``serde::Serialize`` value for the payload
+    ///
+    /// # Returns
+    ///
+    /// ``Result<usize, KafkaThreadpoolError>``
+    /// where
+    /// - ``usize`` = updated number of messages in ``self.publish_msgs``
+    /// after adding the new ``msg``
+    /// - ``KafkaThreadpoolError`` = error reason
+    /// (``Serialization`` if ``value`` cannot be encoded)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use kafka_threadpool::serialize::json_serializer::JsonSerializer;
+    /// my_kafka_publisher
+    ///     .add_serialized_msg(&JsonSerializer, "billing", "customer-1", vec![], &invoice)
+    ///     .await
+    ///     .unwrap();
+    /// ```
+    ///
+    #[cfg(feature = "serde")]
+    pub async fn add_serialized_msg<S, T>(
+        &self,
+        serializer: &S,
+        topic: &str,
+        key: &str,
+        headers: Vec<KafkaHeader>,
+        value: &T,
+    ) -> Result<usize, KafkaThreadpoolError>
+    where
+        S: PayloadSerializer,
+        T: serde::Serialize + ?Sized,
+    {
+        if self.config.is_enabled {
+            let msg = serialize_kafka_publish_message(
+                serializer,
+                KafkaPublishMessageType::Data,
+                topic,
+                key,
+                headers,
+                value,
+            )?;
+            self.enqueue_msgs(vec![msg]).await
+        } else {
+            Ok(0)
+        }
+    }
+
+    /// add_schema_msg
+    ///
+    /// Frame an already-encoded body (Avro binary, Protobuf or
+    /// JSON) with the Confluent wire format (magic byte, schema id
+    /// and the protobuf message indexes) and add the data message
+    /// to the lockable publish vector (requires the
+    /// ``schema-registry`` feature). The schema id is resolved
+    /// before the message enters the work vec so worker threads
+    /// never call the schema registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - [`KafkaSchemaRegistryClient`] with the cached
+    /// schema ids
+    /// * `schema` - [`KafkaSchema`] for the body
+    /// * `topic` - kafka topic to publish the message into
+    /// * `key` - kafka partition key (an empty ``key`` publishes
+    /// a null key)
+    /// * `headers` - ordered
+    /// [`KafkaHeader`](crate::api::kafka_header::KafkaHeader) list
+    /// for the kafka message
+    /// * `body` - encoded payload
+    ///
+    /// # Returns
+    ///
+    /// ``Result<usize, KafkaThreadpoolError>``
+    /// where
+    /// - ``usize`` = updated number of messages in ``self.publish_msgs``
+    /// after adding the new ``msg``
+    /// - ``KafkaThreadpoolError`` = error reason
+    /// (``SchemaRegistry`` if the schema id is not available)
+    ///
+    #[cfg(feature = "schema-registry")]
+    pub async fn add_schema_msg(
+        &self,
+        registry: &KafkaSchemaRegistryClient,
+        schema: &KafkaSchema,
+        topic: &str,
+        key: &str,
+        headers: Vec<KafkaHeader>,
+        body: &[u8],
+    ) -> Result<usize, KafkaThreadpoolError> {
+        if self.config.is_enabled {
+            let payload = registry.frame_payload(schema, body).await?;
+            let key = if key.is_empty() {
+                None
+            } else {
+                Some(key.as_bytes().to_vec())
+            };
+            let msg = KafkaPublishMessage::new_from_bytes(
+                KafkaPublishMessageType::Data,
+                topic,
+                key,
+                headers,
+                Some(payload),
+            );
+            self.enqueue_msgs(vec![msg]).await
+        } else {
+            Ok(0)
+        }
+    }
+
+    /// add_tombstone_msg
+    ///
+    /// Publish a tombstone (null payload) for deleting the ``key``
+    /// from a compacted topic
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - kafka topic to publish the tombstone into
+    /// * `key` - kafka partition key to delete
+    ///
+    /// # Returns
+    ///
+    /// ``Result<usize, KafkaThreadpoolError>``
+    /// where
+    /// - ``usize`` = updated number of messages in ``self.publish_msgs``
+    /// after adding the tombstone
+    /// - ``KafkaThreadpoolError`` = error reason
+    ///
+    pub async fn add_tombstone_msg(
+        &self,
+        topic: &str,
+        key: &[u8],
+    ) -> Result<usize, KafkaThreadpoolError> {
+        if self.config.is_enabled {
+            let msg = KafkaPublishMessage::new_tombstone(topic, key);
+            self.enqueue_msgs(vec![msg]).await
         } else {
             Ok(0)
         }
@@ -144,28 +408,229 @@ impl KafkaPublisher {
     /// to add to the lockable work vector: ``self.publish_msgs``
     ///
     /// Uses the utility API method:
-    /// [`add_messages_to_locked_work_vec`](crate::api::add_messages_to_locked_work_vec)
+    /// [`add_messages_to_bounded_work_vec`](crate::api::add_messages_to_bounded_work_vec)
+    /// which applies the ``KAFKA_QUEUE_OVERFLOW_POLICY`` when the
+    /// lockable work vector is full
     ///
     /// # Returns
     ///
-    /// ``Result<usize, String>``
+    /// ``Result<usize, KafkaThreadpoolError>``
     /// where
     /// - ``usize`` = updated number of messages in ``self.publish_msgs``
     /// after adding the new ``msg``
-    /// - ``String`` = error reason
+    /// - ``KafkaThreadpoolError`` = error reason
     ///
     pub async fn add_msg(
         &self,
         msg: KafkaPublishMessage,
-    ) -> Result<usize, String> {
+    ) -> Result<usize, KafkaThreadpoolError> {
         if self.config.is_enabled {
             let pub_vec: Vec<KafkaPublishMessage> = vec![msg];
-            add_messages_to_locked_work_vec(&self.publish_msgs, pub_vec)
+            self.enqueue_msgs(pub_vec).await
         } else {
             Ok(0)
         }
     }
 
+    /// add_data_msg_with_ack
+    ///
+    /// Build a publishable data message from function
+    /// arguments, add it to the lockable publish vector and
+    /// return a future that resolves once kafka acknowledges
+    /// the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - kafka topic to publish the message into
+    /// * `key` - kafka partition key (an empty ``key`` publishes
+    /// a null key)
+    /// * `headers` - optional - headers for the kafka message
+    /// * `payload` - data within the kafka message
+    ///
+    /// # Returns
+    ///
+    /// ``Result<KafkaDeliveryFuture, KafkaThreadpoolError>``
+    /// where
+    /// - ``KafkaDeliveryFuture`` = resolves with the
+    /// [`KafkaDeliveryReport`](crate::api::kafka_delivery_report::KafkaDeliveryReport)
+    /// (partition, offset and timestamp) or a
+    /// [`KafkaDeliveryError`](crate::api::kafka_delivery_error::KafkaDeliveryError)
+    /// - ``KafkaThreadpoolError`` = error reason
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let delivery = my_kafka_publisher
+    ///     .add_data_msg_with_ack("testing", "key", None, "payload")
+    ///     .await
+    ///     .unwrap();
+    /// let report = delivery.await.unwrap();
+    /// ```
+    ///
+    pub async fn add_data_msg_with_ack(
+        &self,
+        topic: &str,
+        key: &str,
+        headers: Option<HashMap<String, String>>,
+        payload: &str,
+    ) -> Result<KafkaDeliveryFuture, KafkaThreadpoolError> {
+        let msg = build_kafka_publish_message(
+            KafkaPublishMessageType::Data,
+            topic,
+            key,
+            headers,
+            payload,
+        );
+        self.add_msg_with_ack(msg).await
+    }
+
+    /// add_msg_with_ack
+    ///
+    /// Add a single message to the lockable publish vector and
+    /// return a future that resolves once kafka acknowledges
+    /// the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - an initialized
+    /// [`KafkaPublishMessage`](crate::api::kafka_publish_message::KafkaPublishMessage)
+    /// to add to the lockable work vector: ``self.publish_msgs``
+    ///
+    /// # Returns
+    ///
+    /// ``Result<KafkaDeliveryFuture, KafkaThreadpoolError>``
+    /// where
+    /// - ``KafkaDeliveryFuture`` = resolves with the
+    /// [`KafkaDeliveryReport`](crate::api::kafka_delivery_report::KafkaDeliveryReport)
+    /// (partition, offset and timestamp) or a
+    /// [`KafkaDeliveryError`](crate::api::kafka_delivery_error::KafkaDeliveryError)
+    /// - ``KafkaThreadpoolError`` = error reason
+    ///
+    pub async fn add_msg_with_ack(
+        &self,
+        msg: KafkaPublishMessage,
+    ) -> Result<KafkaDeliveryFuture, KafkaThreadpoolError> {
+        let mut deliveries = self.add_msgs_with_ack(vec![msg]).await?;
+        match deliveries.pop() {
+            Some(delivery) => Ok(delivery),
+            None => Err(KafkaThreadpoolError::NoMessages),
+        }
+    }
+
+    /// add_msgs_with_ack
+    ///
+    /// Add a vector of messages to the lockable publish vector
+    /// and return one future per message that resolves once
+    /// kafka acknowledges that message.
+    ///
+    /// # Arguments
+    ///
+    /// * `msgs` - vector of
+    /// [`KafkaPublishMessage`](crate::api::kafka_publish_message::KafkaPublishMessage)
+    /// to add to the lockable work vector: ``self.publish_msgs``
+    ///
+    /// # Returns
+    ///
+    /// ``Result<Vec<KafkaDeliveryFuture>, KafkaThreadpoolError>``
+    /// where
+    /// - ``Vec<KafkaDeliveryFuture>`` = delivery futures in the same
+    /// order as ``msgs``
+    /// - ``KafkaThreadpoolError`` = error reason (none of the
+    /// messages were added)
+    ///
+    pub async fn add_msgs_with_ack(
+        &self,
+        mut msgs: Vec<KafkaPublishMessage>,
+    ) -> Result<Vec<KafkaDeliveryFuture>, KafkaThreadpoolError> {
+        if !self.config.is_enabled {
+            return Ok(msgs
+                .iter()
+                .map(|_| {
+                    KafkaDeliveryFuture::failed(KafkaDeliveryError::Disabled)
+                })
+                .collect());
+        }
+        let mut deliveries: Vec<KafkaDeliveryFuture> =
+            Vec::with_capacity(msgs.len());
+        for msg in msgs.iter_mut() {
+            let (notifier, delivery) = KafkaDeliveryNotifier::new();
+            msg.delivery = Some(notifier);
+            deliveries.push(delivery);
+        }
+        self.enqueue_msgs(msgs).await?;
+        Ok(deliveries)
+    }
+
+    /// add_transaction_msgs
+    ///
+    /// Add a group of messages that one worker thread publishes
+    /// in a single kafka transaction (``begin_transaction``,
+    /// publish every message then ``commit_transaction`` or
+    /// ``abort_transaction``) and return a future that resolves
+    /// with the outcome. Consumers using
+    /// ``isolation.level=read_committed`` see every message or none
+    /// of them.
+    ///
+    /// Requires the transactional mode enabled with
+    /// ``KAFKA_TRANSACTIONAL_ID_PREFIX``. Aborted transactions are
+    /// not retried or sent to the dead-letter sinks so the caller
+    /// can decide to resubmit them.
+    ///
+    /// # Arguments
+    ///
+    /// * `msgs` - vector of ``Data`` or ``Sensitive``
+    /// [`KafkaPublishMessage`](crate::api::kafka_publish_message::KafkaPublishMessage)
+    /// to commit atomically (in publish order)
+    ///
+    /// # Returns
+    ///
+    /// ``Result<KafkaTransactionFuture, KafkaThreadpoolError>``
+    /// where
+    /// - ``KafkaTransactionFuture`` = resolves with one
+    /// [`KafkaDeliveryReport`](crate::api::kafka_delivery_report::KafkaDeliveryReport)
+    /// per message once the transaction is committed or a
+    /// [`KafkaDeliveryError`](crate::api::kafka_delivery_error::KafkaDeliveryError)
+    /// if the transaction was aborted
+    /// - ``KafkaThreadpoolError`` = error reason
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let transaction = my_kafka_publisher
+    ///     .add_transaction_msgs(vec![debit_msg, credit_msg])
+    ///     .await
+    ///     .unwrap();
+    /// let reports = transaction.await.unwrap();
+    /// ```
+    ///
+    pub async fn add_transaction_msgs(
+        &self,
+        msgs: Vec<KafkaPublishMessage>,
+    ) -> Result<KafkaTransactionFuture, KafkaThreadpoolError> {
+        if !self.config.is_enabled {
+            return Ok(KafkaTransactionFuture::failed(
+                KafkaDeliveryError::Disabled,
+            ));
+        }
+        if msgs.is_empty() {
+            return Err(KafkaThreadpoolError::NoMessages);
+        }
+        if self.config.transactional_id_prefix.is_empty() {
+            return Err(KafkaThreadpoolError::config_invalid(
+                "KAFKA_TRANSACTIONAL_ID_PREFIX",
+                "please set a transactional id prefix to publish \
+                transactions",
+            ));
+        }
+        let (notifier, transaction) = KafkaTransactionNotifier::new();
+        let msg = KafkaPublishMessage::new_transaction(KafkaTransaction::new(
+            msgs,
+            Some(notifier),
+        ));
+        self.enqueue_msgs(vec![msg]).await?;
+        Ok(transaction)
+    }
+
     /// add_msgs
     ///
     /// Add a vector of messages to the lockable publish vector
@@ -177,27 +642,112 @@ impl KafkaPublisher {
     /// to add to the lockable work vector: ``self.publish_msgs``
     ///
     /// Uses the utility API method:
-    /// [`add_messages_to_locked_work_vec`](crate::api::add_messages_to_locked_work_vec)
+    /// [`add_messages_to_bounded_work_vec`](crate::api::add_messages_to_bounded_work_vec)
+    /// which applies the ``KAFKA_QUEUE_OVERFLOW_POLICY`` when the
+    /// lockable work vector is full
     ///
     /// # Returns
     ///
-    /// ``Result<usize, String>``
+    /// ``Result<usize, KafkaThreadpoolError>``
     /// where
     /// - ``usize`` = updated number of messages in ``self.publish_msgs``
     /// after adding the new ``msgs``
-    /// - ``String`` = error reason
+    /// - ``KafkaThreadpoolError`` = error reason
     ///
     pub async fn add_msgs(
         &self,
         msgs: Vec<KafkaPublishMessage>,
-    ) -> Result<usize, String> {
+    ) -> Result<usize, KafkaThreadpoolError> {
         if self.config.is_enabled {
-            add_messages_to_locked_work_vec(&self.publish_msgs, msgs)
+            self.enqueue_msgs(msgs).await
         } else {
             Ok(0)
         }
     }
 
+    /// enqueue_msgs
+    ///
+    /// Internal helper for adding messages to the bounded
+    /// lockable work vectors. With the ``Block`` overflow policy
+    /// this waits for the worker threads to drain messages
+    /// before retrying. With the ``tracing`` feature the caller's
+    /// current span is attached to each message.
+    ///
+    /// With the ``Key`` and ``TopicPartition`` ordering modes each
+    /// message goes to the work vec of its worker thread. The add is
+    /// all-or-nothing (see
+    /// [`KafkaWorkQueues::add_messages`](crate::pool::kafka_work_queues::KafkaWorkQueues::add_messages))
+    /// so a ``QueueFull`` error means none of the messages were
+    /// added.
+    ///
+    /// # Arguments
+    ///
+    /// * `msgs` - vector of
+    /// [`KafkaPublishMessage`](crate::api::kafka_publish_message::KafkaPublishMessage)
+    /// to add to the lockable work vector: ``self.publish_msgs``
+    ///
+    /// # Returns
+    ///
+    /// ``Result<usize, KafkaThreadpoolError>``
+    /// where
+    /// - ``usize`` = updated number of messages in ``self.publish_msgs``
+    /// - ``KafkaThreadpoolError`` = error reason
+    ///
+    async fn enqueue_msgs(
+        &self,
+        msgs: Vec<KafkaPublishMessage>,
+    ) -> Result<usize, KafkaThreadpoolError> {
+        let mut pending_msgs = msgs;
+        #[cfg(feature = "tracing")]
+        attach_current_span(&mut pending_msgs);
+        loop {
+            if self.pool_state.is_shutting_down() {
+                return Err(KafkaThreadpoolError::PoolShutdown);
+            }
+            if self.publish_msgs.num_running() == 0 {
+                warn!(
+                    "{} - every worker thread exited - not adding msgs",
+                    self.config.label
+                );
+                return Err(KafkaThreadpoolError::PoolShutdown);
+            }
+            // register for the wakeup before checking for space
+            let space_available = self.pool_state.space_available.notified();
+            let full_msgs =
+                match self.publish_msgs.add_messages(pending_msgs)? {
+                    KafkaQueueAddStatus::Added { num_dropped, .. } => {
+                        // wake up any idle worker threads
+                        self.pool_state.work_available.notify_waiters();
+                        if num_dropped > 0 {
+                            warn!(
+                                "{} - work vec full - overflow={} \
+                                dropped {num_dropped} msgs",
+                                self.config.label,
+                                self.config.queue_overflow_policy
+                            );
+                        }
+                        let num_in_vec = self.publish_msgs.len();
+                        #[cfg(feature = "metrics")]
+                        set_queue_depth(&self.config.label, num_in_vec);
+                        return Ok(num_in_vec);
+                    }
+                    // nothing was added
+                    KafkaQueueAddStatus::Full(returned_msgs) => returned_msgs,
+                };
+            if self.config.queue_overflow_policy
+                != KafkaQueueOverflowPolicy::Block
+            {
+                return Err(KafkaThreadpoolError::QueueFull {
+                    num_msgs: full_msgs.len(),
+                    max_msgs: self.config.max_queue_msgs,
+                    max_bytes: self.config.max_queue_bytes,
+                });
+            }
+            pending_msgs = full_msgs;
+            space_available.await;
+        }
+    }
+
     /// drain_msgs
     ///
     /// Helper function for testing - allows draining
@@ -209,7 +759,7 @@ impl KafkaPublisher {
     ///
     pub async fn drain_msgs(&self) -> Vec<KafkaPublishMessage> {
         if self.config.is_enabled {
-            drain_messages_from_locked_work_vec(&self.publish_msgs)
+            self.publish_msgs.drain_all()
         } else {
             vec![]
         }
@@ -217,13 +767,17 @@ impl KafkaPublisher {
 
     /// shutdown
     ///
-    /// Gracefully shutdown the threadpool by
-    /// sending the ``Shutdown`` control
-    /// message to all worker threads
+    /// Start a graceful shutdown of the threadpool without
+    /// waiting. The publisher stops accepting new messages and
+    /// the worker threads exit after publishing every queued
+    /// message. Use
+    /// [`shutdown_and_wait`](crate::kafka_publisher::KafkaPublisher::shutdown_and_wait)
+    /// to wait for the worker threads.
     ///
     /// # Errors
     ///
-    /// Threads may get hung if something goes wrong.
+    /// [`KafkaThreadpoolError::LockPoisoned`] if the ``Shutdown``
+    /// control message cannot be added to the work vec
     ///
     /// # Examples
     ///
@@ -231,8 +785,9 @@ impl KafkaPublisher {
     /// my_threadpool.shutdown().await.unwrap();
     /// ```
     ///
-    pub async fn shutdown(&self) -> Result<String, String> {
+    pub async fn shutdown(&self) -> Result<String, KafkaThreadpoolError> {
         if self.config.is_enabled {
+            self.pool_state.start_shutdown();
             let shutdown_msg_vec: Vec<KafkaPublishMessage> =
                 vec![build_kafka_publish_message(
                     KafkaPublishMessageType::Shutdown,
@@ -242,11 +797,16 @@ impl KafkaPublisher {
                     "",
                 )];
             info!("sending shutdown msg");
+            let queue_num =
+                self.publish_msgs.get_queue_num(&shutdown_msg_vec[0]);
             match add_messages_to_locked_work_vec(
-                &self.publish_msgs,
+                &self.publish_msgs.queues[queue_num],
                 shutdown_msg_vec,
             ) {
-                Ok(_) => Ok("shutdown started".to_string()),
+                Ok(_) => {
+                    self.pool_state.work_available.notify_waiters();
+                    Ok("shutdown started".to_string())
+                }
                 Err(e) => Err(e),
             }
         } else {
@@ -254,6 +814,211 @@ impl KafkaPublisher {
         }
     }
 
+    /// shutdown_and_wait
+    ///
+    /// Gracefully shutdown the threadpool and wait for every
+    /// worker thread to exit:
+    ///
+    /// 1. stop accepting new messages (``add_*`` methods return
+    /// [`KafkaThreadpoolError::PoolShutdown`])
+    /// 2. publish every message already in the work vec
+    /// 3. flush each worker thread's producer (for at most the time
+    /// left until the ``deadline``)
+    /// 4. join every worker thread
+    ///
+    /// If the ``deadline`` expires first, the worker threads stop
+    /// retrying and every unpublished message is abandoned (callers
+    /// waiting on a
+    /// [`KafkaDeliveryFuture`](crate::api::kafka_delivery_notifier::KafkaDeliveryFuture)
+    /// receive an error). Worker threads that still have not exited
+    /// after another
+    /// [`KAFKA_ABANDON_TIMEOUT`](crate::pool::kafka_pool_state::KAFKA_ABANDON_TIMEOUT)
+    /// are aborted so the shutdown never waits on ``librdkafka``.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - maximum time to wait for the worker threads
+    /// to publish the queued messages
+    ///
+    /// # Returns
+    ///
+    /// ``Result<KafkaShutdownReport, KafkaThreadpoolError>``
+    /// where
+    /// - ``KafkaShutdownReport`` = number of delivered, failed and
+    /// abandoned messages
+    /// - ``KafkaThreadpoolError`` = error reason
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let report = my_threadpool
+    ///     .shutdown_and_wait(std::time::Duration::from_secs(10))
+    ///     .await
+    ///     .unwrap();
+    /// println!("{report}");
+    /// ```
+    ///
+    pub async fn shutdown_and_wait(
+        &self,
+        deadline: Duration,
+    ) -> Result<KafkaShutdownReport, KafkaThreadpoolError> {
+        if !self.config.is_enabled {
+            return Ok(KafkaShutdownReport::default());
+        }
+        self.pool_state.set_shutdown_deadline(deadline);
+        self.shutdown().await?;
+        let mut workers: Vec<JoinHandle<()>> =
+            match self.pool_state.workers.lock() {
+                Ok(mut locked_workers) => locked_workers.drain(..).collect(),
+                Err(e) => {
+                    return Err(KafkaThreadpoolError::LockPoisoned(
+                        e.to_string(),
+                    ))
+                }
+            };
+        let num_workers = workers.len();
+        info!(
+            "{} - waiting up to {deadline:?} for threads={num_workers}",
+            self.config.label
+        );
+        let mut num_aborted: usize = 0;
+        let timed_out = tokio::time::timeout(deadline, async {
+            for worker in workers.iter_mut() {
+                let _ = worker.await;
+            }
+        })
+        .await
+        .is_err();
+        if timed_out {
+            warn!(
+                "{} - shutdown deadline {deadline:?} expired - \
+                abandoning unpublished msgs",
+                self.config.label
+            );
+            // stop retrying and wake any idle or waiting threads
+            self.pool_state.start_abandoning();
+            let exited = tokio::time::timeout(KAFKA_ABANDON_TIMEOUT, async {
+                for worker in workers.iter_mut() {
+                    if !worker.is_finished() {
+                        let _ = worker.await;
+                    }
+                }
+            })
+            .await
+            .is_ok();
+            if !exited {
+                for worker in workers.iter() {
+                    if !worker.is_finished() {
+                        worker.abort();
+                        num_aborted += 1;
+                    }
+                }
+                warn!(
+                    "{} - aborted threads={num_aborted} that did not \
+                    exit within {KAFKA_ABANDON_TIMEOUT:?} of the deadline",
+                    self.config.label
+                );
+            }
+        }
+        // abandon anything added after the threads exited
+        for msg in self.publish_msgs.drain_all() {
+            if let Some(delivery) = &msg.delivery {
+                delivery.notify(Err(KafkaDeliveryError::Dropped));
+            }
+            if msg.msg_type == KafkaPublishMessageType::Data
+                || msg.msg_type == KafkaPublishMessageType::Sensitive
+            {
+                self.pool_state.record_abandoned();
+            }
+            if let Some(transaction) = &msg.transaction {
+                transaction.notify_dropped();
+                for _ in transaction.msgs.iter() {
+                    self.pool_state.record_abandoned();
+                }
+            }
+        }
+        let report = KafkaShutdownReport {
+            num_delivered: self.pool_state.num_delivered.load(Ordering::SeqCst),
+            num_failed: self.pool_state.num_failed.load(Ordering::SeqCst),
+            num_abandoned: self.pool_state.num_abandoned.load(Ordering::SeqCst),
+            num_workers,
+            num_aborted,
+            timed_out,
+        };
+        info!("{} - shutdown complete {report}", self.config.label);
+        Ok(report)
+    }
+
+    /// render_metrics
+    ///
+    /// Render the threadpool metrics in the Prometheus text
+    /// exposition format (requires the ``metrics`` feature)
+    ///
+    /// # Returns
+    ///
+    /// ``String`` for a ``/metrics`` endpoint with the
+    /// ``content-type``: ``text/plain; version=0.0.4``
+    ///
+    #[cfg(feature = "metrics")]
+    pub fn render_metrics(&self) -> String {
+        render_prometheus_metrics()
+    }
+
+    /// stats
+    ///
+    /// Get the latest ``librdkafka`` statistics for each worker
+    /// thread's producer. Statistics are only collected when
+    /// ``KAFKA_STATISTICS_INTERVAL_MS`` is positive and the first
+    /// snapshot arrives after one interval.
+    ///
+    /// # Returns
+    ///
+    /// ``Vec<KafkaProducerStats>`` sorted by the worker thread log
+    /// label (transactional producers use the ``-txn`` suffix)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// for stats in my_kafka_publisher.stats() {
+    ///     println!("{stats}");
+    ///     for broker in stats.brokers.iter() {
+    ///         println!("{broker}");
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub fn stats(&self) -> Vec<KafkaProducerStats> {
+        self.pool_state.stats.snapshot()
+    }
+
+    /// health
+    ///
+    /// Check if the threadpool can publish with the state of each
+    /// worker thread (running, idle, retrying or exited), broker
+    /// connectivity from the ``librdkafka`` events, the work vec
+    /// depth and the time since the last successful delivery
+    /// compared against the
+    /// [`KafkaHealthThresholds`](crate::config::kafka_health_thresholds::KafkaHealthThresholds)
+    ///
+    /// # Returns
+    ///
+    /// [`KafkaHealthReport`](crate::health::kafka_health_report::KafkaHealthReport)
+    /// with a ``Healthy``, ``Degraded`` or ``Unhealthy`` status and
+    /// the reasons for it
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let report = my_kafka_publisher.health();
+    /// if !report.is_ready() {
+    ///     println!("threadpool cannot publish {report}");
+    /// }
+    /// ```
+    ///
+    pub fn health(&self) -> KafkaHealthReport {
+        get_kafka_health(&self.config, &self.pool_state, &self.publish_msgs)
+    }
+
     /// get_metadata
     ///
     /// Get kafka cluster information by all topics or for
@@ -266,13 +1031,132 @@ impl KafkaPublisher {
     /// * `topic` - If set, only get the details for that specific topic if set to ``None``
     /// get details for all topics
     ///
-    pub async fn get_metadata(&self, fetch_offsets: bool, topic: Option<&str>) {
+    /// # Returns
+    ///
+    /// [`KafkaClusterMetadata`](crate::metadata::kafka_cluster_metadata::KafkaClusterMetadata)
+    /// with the brokers, topics and partitions (empty if the
+    /// threadpool is not enabled)
+    ///
+    /// # Errors
+    ///
+    /// [`KafkaThreadpoolError::Broker`] if the consumer cannot be
+    /// created or the metadata cannot be fetched
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use kafka_threadpool::metadata::log_kafka_cluster_metadata::log_kafka_cluster_metadata;
+    /// let metadata = my_kafka_publisher
+    ///     .get_metadata(true, Some("testing"))
+    ///     .await
+    ///     .unwrap();
+    /// log_kafka_cluster_metadata(&metadata);
+    /// ```
+    ///
+    pub async fn get_metadata(
+        &self,
+        fetch_offsets: bool,
+        topic: Option<&str>,
+    ) -> Result<KafkaClusterMetadata, KafkaThreadpoolError> {
         if self.config.is_enabled {
             info!("creating consumer");
-            let consumer = get_kafka_consumer(&self.config);
-            get_kafka_metadata(&self.config, consumer, fetch_offsets, topic)
+            // the metadata and watermarks calls block
+            let metadata_config = self.config.clone();
+            let metadata_topic = topic.map(str::to_string);
+            match tokio::task::spawn_blocking(move || {
+                get_kafka_metadata_from_config(
+                    &metadata_config,
+                    fetch_offsets,
+                    metadata_topic.as_deref(),
+                )
+            })
+            .await
+            {
+                Ok(res) => res,
+                Err(e) => Err(KafkaThreadpoolError::Broker {
+                    code: None,
+                    reason: format!("failed to get metadata with err={e}"),
+                }),
+            }
+        } else {
+            info!("kafka not enabled KAFKA_ENABLED={}", self.config.is_enabled);
+            Ok(KafkaClusterMetadata::default())
+        }
+    }
+
+    /// get_consumer_group_lag
+    ///
+    /// Get the committed offsets and lag for a consumer group
+    ///
+    /// # Arguments
+    ///
+    /// * `group_id` - consumer group id
+    /// * `topics` - If set, report every partition of these topics
+    /// if set to ``None`` report the topics the group committed
+    /// offsets on
+    ///
+    /// # Returns
+    ///
+    /// [`KafkaConsumerGroupLag`](crate::metadata::kafka_consumer_group_lag::KafkaConsumerGroupLag)
+    /// with the per-partition lag (empty if the threadpool is not
+    /// enabled)
+    ///
+    /// # Errors
+    ///
+    /// [`KafkaThreadpoolError::Broker`] if the consumer cannot be
+    /// created or the metadata or committed offsets cannot be fetched
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let group_lag = my_kafka_publisher
+    ///     .get_consumer_group_lag("my-consumer-group", None)
+    ///     .await
+    ///     .unwrap();
+    /// let summary = group_lag.summary();
+    /// if summary.is_lagging(10000) {
+    ///     println!("consumer group is behind {summary}");
+    /// }
+    /// ```
+    ///
+    pub async fn get_consumer_group_lag(
+        &self,
+        group_id: &str,
+        topics: Option<&[&str]>,
+    ) -> Result<KafkaConsumerGroupLag, KafkaThreadpoolError> {
+        if self.config.is_enabled {
+            // the metadata, committed offsets and watermarks calls block
+            let lag_config = self.config.clone();
+            let lag_group_id = group_id.to_string();
+            let lag_topics: Option<Vec<String>> = topics.map(|topics| {
+                topics.iter().map(|topic| topic.to_string()).collect()
+            });
+            match tokio::task::spawn_blocking(move || {
+                let lag_topics: Option<Vec<&str>> = lag_topics
+                    .as_ref()
+                    .map(|topics| topics.iter().map(String::as_str).collect());
+                get_kafka_consumer_group_lag(
+                    &lag_config,
+                    &lag_group_id,
+                    lag_topics.as_deref(),
+                )
+            })
+            .await
+            {
+                Ok(res) => res,
+                Err(e) => Err(KafkaThreadpoolError::Broker {
+                    code: None,
+                    reason: format!(
+                        "failed to get consumer group lag with err={e}"
+                    ),
+                }),
+            }
         } else {
             info!("kafka not enabled KAFKA_ENABLED={}", self.config.is_enabled);
+            Ok(KafkaConsumerGroupLag {
+                group_id: group_id.to_string(),
+                ..Default::default()
+            })
         }
     }
 }
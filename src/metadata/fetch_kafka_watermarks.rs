@@ -0,0 +1,97 @@
+//! Fetch the watermarks for every partition in a
+//! [`KafkaClusterMetadata`](crate::metadata::kafka_cluster_metadata::KafkaClusterMetadata)
+//!
+use std::time::Duration;
+
+use rdkafka::consumer::BaseConsumer;
+use rdkafka::consumer::Consumer;
+use rdkafka::consumer::ConsumerContext;
+
+use crate::config::kafka_client_config::KafkaClientConfig;
+use crate::metadata::kafka_cluster_metadata::KafkaClusterMetadata;
+use crate::metadata::kafka_partition_metadata::KafkaPartitionMetadata;
+use crate::metadata::kafka_watermarks::KafkaWatermarks;
+
+/// fetch_partition_watermarks
+///
+/// Internal helper for fetching one partition's watermarks and
+/// recording the error if the fetch fails
+///
+/// # Arguments
+///
+/// * `consumer` - consumer used to fetch the watermarks
+/// * `topic` - kafka topic
+/// * `partition` - partition metadata to update
+/// * `timeout` - max time to wait for the watermarks
+///
+fn fetch_partition_watermarks<C: ConsumerContext>(
+    consumer: &BaseConsumer<C>,
+    topic: &str,
+    partition: &mut KafkaPartitionMetadata,
+    timeout: Duration,
+) {
+    match consumer.fetch_watermarks(topic, partition.id, timeout) {
+        Ok((low, high)) => {
+            partition.watermarks = Some(KafkaWatermarks { low, high });
+            partition.watermark_error = None;
+        }
+        Err(e) => {
+            partition.watermarks = None;
+            partition.watermark_error = Some(e.to_string());
+        }
+    }
+}
+
+/// fetch_kafka_watermarks
+///
+/// Fetch the watermarks for every partition in the metadata.
+/// Each partition waits up to ``config.watermark_timeout`` with up
+/// to ``config.watermark_concurrency`` partitions fetched at the
+/// same time. Partitions where the fetch failed get a
+/// ``watermark_error`` instead of ``watermarks``.
+///
+/// # Arguments
+///
+/// * `config` - initialized [`KafkaClientConfig`]
+/// * `consumer` - consumer used to fetch the watermarks
+/// * `cluster_metadata` - metadata to update
+///
+pub fn fetch_kafka_watermarks<C: ConsumerContext>(
+    config: &KafkaClientConfig,
+    consumer: &BaseConsumer<C>,
+    cluster_metadata: &mut KafkaClusterMetadata,
+) {
+    let mut partitions: Vec<(&str, &mut KafkaPartitionMetadata)> =
+        cluster_metadata
+            .topics
+            .iter_mut()
+            .flat_map(|found_topic| {
+                let name = found_topic.name.as_str();
+                found_topic
+                    .partitions
+                    .iter_mut()
+                    .map(move |partition| (name, partition))
+            })
+            .collect();
+    let timeout = config.watermark_timeout;
+    let concurrency = config.watermark_concurrency.max(1);
+    if concurrency == 1 || partitions.len() <= 1 {
+        for (name, partition) in partitions.into_iter() {
+            fetch_partition_watermarks(consumer, name, partition, timeout);
+        }
+    } else {
+        // split the partitions into one chunk per fetching thread
+        let chunk_size = partitions.len().div_ceil(concurrency);
+        std::thread::scope(|scope| {
+            for chunk in partitions.chunks_mut(chunk_size) {
+                scope.spawn(move || {
+                    for (name, partition) in chunk.iter_mut() {
+                        fetch_partition_watermarks(
+                            consumer, name, partition, timeout,
+                        );
+                    }
+                });
+            }
+        });
+    }
+}
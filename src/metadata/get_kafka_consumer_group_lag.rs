@@ -0,0 +1,163 @@
+//! Get the committed offsets and lag for a consumer group
+//!
+use std::collections::HashMap;
+
+use log::info;
+
+use rdkafka::consumer::BaseConsumer;
+use rdkafka::consumer::Consumer;
+use rdkafka::consumer::ConsumerContext;
+use rdkafka::Offset;
+use rdkafka::TopicPartitionList;
+
+use crate::api::build_rdkafka_client_config::build_rdkafka_client_config;
+use crate::api::kafka_client_context::KafkaClientContext;
+use crate::api::kafka_oauth_client_context::KafkaOAuthClientContext;
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
+use crate::config::kafka_client_config::KafkaClientConfig;
+use crate::metadata::fetch_kafka_watermarks::fetch_kafka_watermarks;
+use crate::metadata::kafka_cluster_metadata::KafkaClusterMetadata;
+use crate::metadata::kafka_consumer_group_lag::KafkaConsumerGroupLag;
+use crate::metadata::kafka_partition_lag::KafkaPartitionLag;
+
+/// get_kafka_consumer_group_lag
+///
+/// Fetch the committed offsets for a consumer group and compute
+/// the lag against each partition's high watermark. The consumer
+/// joins the group with ``enable.auto.commit=false`` and never
+/// subscribes so it does not trigger a rebalance or move the
+/// group's offsets.
+///
+/// The metadata and committed offset requests wait up to
+/// ``config.metadata_timeout`` and the watermarks are fetched with
+/// [`fetch_kafka_watermarks`](crate::metadata::fetch_kafka_watermarks::fetch_kafka_watermarks).
+///
+/// # Arguments
+///
+/// * `config` - initialized [`KafkaClientConfig`](crate::config::KafkaClientConfig)
+/// * `group_id` - consumer group id
+/// * `topics` - If set, report every partition of these topics
+/// (partitions without a committed offset have no lag). If set to
+/// ``None`` report the topics where the group committed an offset
+/// on at least one partition.
+///
+/// # Returns
+///
+/// [`KafkaConsumerGroupLag`] with the per-partition lag sorted by
+/// topic and partition id (use
+/// [`summary`](crate::metadata::kafka_consumer_group_lag::KafkaConsumerGroupLag::summary)
+/// for alerting)
+///
+/// # Errors
+///
+/// [`KafkaThreadpoolError::Broker`] if the consumer cannot be
+/// created or the metadata or committed offsets cannot be fetched
+///
+pub fn get_kafka_consumer_group_lag(
+    config: &KafkaClientConfig,
+    group_id: &str,
+    topics: Option<&[&str]>,
+) -> Result<KafkaConsumerGroupLag, KafkaThreadpoolError> {
+    info!("getting consumer group lag group={group_id} config={config}");
+    let mut client_config = build_rdkafka_client_config(config);
+    client_config
+        .set("group.id", group_id)
+        .set("enable.auto.commit", "false");
+    match &config.oauth_token_provider {
+        Some(token_provider) => {
+            let consumer: BaseConsumer<KafkaOAuthClientContext> = client_config
+                .create_with_context(KafkaOAuthClientContext::new(
+                    KafkaClientContext::default(),
+                    token_provider.clone(),
+                ))?;
+            get_consumer_group_lag(config, &consumer, group_id, topics)
+        }
+        None => {
+            let consumer: BaseConsumer<KafkaClientContext> = client_config
+                .create_with_context(KafkaClientContext::default())?;
+            get_consumer_group_lag(config, &consumer, group_id, topics)
+        }
+    }
+}
+
+/// get_consumer_group_lag
+///
+/// Internal helper for [`get_kafka_consumer_group_lag`] with a
+/// consumer that joined the group
+///
+fn get_consumer_group_lag<C: ConsumerContext>(
+    config: &KafkaClientConfig,
+    consumer: &BaseConsumer<C>,
+    group_id: &str,
+    topics: Option<&[&str]>,
+) -> Result<KafkaConsumerGroupLag, KafkaThreadpoolError> {
+    let metadata = consumer.fetch_metadata(None, config.metadata_timeout)?;
+    let mut cluster_metadata = KafkaClusterMetadata::from(&metadata);
+    if let Some(topics) = topics {
+        cluster_metadata
+            .topics
+            .retain(|topic| topics.contains(&topic.name.as_str()));
+    }
+
+    let mut tpl = TopicPartitionList::new();
+    for topic in cluster_metadata.topics.iter() {
+        for partition in topic.partitions.iter() {
+            tpl.add_partition(&topic.name, partition.id);
+        }
+    }
+    let committed_tpl =
+        consumer.committed_offsets(tpl, config.metadata_timeout)?;
+    let mut committed: HashMap<(String, i32), (Option<i64>, Option<String>)> =
+        HashMap::new();
+    for elem in committed_tpl.elements().iter() {
+        let offset = match elem.offset() {
+            Offset::Offset(offset) => Some(offset),
+            _ => None,
+        };
+        let error = elem.error().err().map(|err| err.to_string());
+        committed.insert(
+            (elem.topic().to_string(), elem.partition()),
+            (offset, error),
+        );
+    }
+
+    if topics.is_none() {
+        // only keep the topics the group consumes from
+        cluster_metadata.topics.retain(|topic| {
+            topic.partitions.iter().any(|partition| {
+                matches!(
+                    committed.get(&(topic.name.clone(), partition.id)),
+                    Some((Some(_), _))
+                )
+            })
+        });
+    }
+    fetch_kafka_watermarks(config, consumer, &mut cluster_metadata);
+
+    let mut partitions = vec![];
+    for topic in cluster_metadata.topics.iter() {
+        for partition in topic.partitions.iter() {
+            let (committed_offset, commit_error) = committed
+                .remove(&(topic.name.clone(), partition.id))
+                .unwrap_or((None, None));
+            let mut partition_lag = KafkaPartitionLag {
+                topic: topic.name.clone(),
+                partition: partition.id,
+                committed_offset,
+                high_watermark: partition
+                    .watermarks
+                    .as_ref()
+                    .map(|watermarks| watermarks.high),
+                lag: None,
+                error: commit_error
+                    .or_else(|| partition.watermark_error.clone()),
+            };
+            partition_lag.compute_lag();
+            partitions.push(partition_lag);
+        }
+    }
+    Ok(KafkaConsumerGroupLag {
+        group_id: group_id.to_string(),
+        partitions,
+    })
+}
@@ -4,8 +4,15 @@ use log::info;
 
 use rdkafka::consumer::BaseConsumer;
 use rdkafka::consumer::Consumer;
+use rdkafka::consumer::ConsumerContext;
 
+use crate::api::get_kafka_consumer::get_kafka_consumer_with_context;
+use crate::api::kafka_client_context::KafkaClientContext;
+use crate::api::kafka_oauth_client_context::KafkaOAuthClientContext;
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
 use crate::config::kafka_client_config::KafkaClientConfig;
+use crate::metadata::fetch_kafka_watermarks::fetch_kafka_watermarks;
+use crate::metadata::kafka_cluster_metadata::KafkaClusterMetadata;
 
 /// get_kafka_metadata
 ///
@@ -13,86 +20,91 @@ use crate::config::kafka_client_config::KafkaClientConfig;
 ///
 /// [Original source metadata.rs](https://github.com/fede1024/rust-rdkafka/blob/master/examples/metadata.rs)
 ///
+/// The metadata request waits up to ``config.metadata_timeout``
+/// and the watermarks are fetched with
+/// [`fetch_kafka_watermarks`](crate::metadata::fetch_kafka_watermarks::fetch_kafka_watermarks).
+///
 /// # Arguments
 ///
 /// * `config` - initialized [`KafkaClientConfig`](crate::config::KafkaClientConfig)
 /// * `consumer` - initialized [`BaseConsumer`](rdkafka::consumer::BaseConsumer) used to
 /// fetch the metadata from the kafka cluster
-/// * `fetch_offsets` - when ``true`` this function will count the total number
-/// of messages in each topic
+/// * `fetch_offsets` - when ``true`` this function will fetch the
+/// watermarks for each partition to count the number of messages
+/// in each partition and topic
 /// * `topic` - If set, only get the details for that specific topic if set to ``None``
 /// get details for all topics
 ///
-pub fn get_kafka_metadata(
+/// # Returns
+///
+/// [`KafkaClusterMetadata`] with the brokers, topics and partitions
+/// (log it with
+/// [`log_kafka_cluster_metadata`](crate::metadata::log_kafka_cluster_metadata::log_kafka_cluster_metadata)).
+/// Partitions where the watermark fetch failed have a
+/// ``watermark_error`` instead of ``watermarks``.
+///
+/// # Errors
+///
+/// [`KafkaThreadpoolError::Broker`] if the metadata cannot be fetched
+///
+pub fn get_kafka_metadata<C: ConsumerContext>(
     config: &KafkaClientConfig,
-    consumer: BaseConsumer,
+    consumer: BaseConsumer<C>,
     fetch_offsets: bool,
     topic: Option<&str>,
-) {
+) -> Result<KafkaClusterMetadata, KafkaThreadpoolError> {
     info!("getting metadata config={config}");
-    let fetch_timeout = std::time::Duration::from_millis(30000);
     let metadata = consumer
         // https://docs.rs/rdkafka/latest/rdkafka/consumer/struct.BaseConsumer.html#method.fetch_metadata
-        .fetch_metadata(topic, fetch_timeout)
-        .expect("Failed to fetch metadata");
-
-    let mut broker_listing: String = String::from("");
-    for broker in metadata.brokers() {
-        let cur_broker = format!(
-            "broker.id={} address={}:{} ",
-            broker.id(),
-            broker.host(),
-            broker.port()
-        );
-        broker_listing = broker_listing + &cur_broker;
+        .fetch_metadata(topic, config.metadata_timeout)?;
+    let mut cluster_metadata = KafkaClusterMetadata::from(&metadata);
+    if fetch_offsets {
+        fetch_kafka_watermarks(config, &consumer, &mut cluster_metadata);
     }
+    Ok(cluster_metadata)
+}
 
-    info!(
-        "cluster info brokers={} num_topics={} \
-        {broker_listing}",
-        metadata.brokers().len(),
-        metadata.topics().len()
-    );
-
-    let mut message_count = 0;
-    for found_topic in metadata.topics() {
-        info!("topic={} err={:?}", found_topic.name(), found_topic.error());
-        for partition in found_topic.partitions() {
-            info!(
-                "topic={} - partition={} \
-                leader={} replicas={:?} \
-                ISR={:?} err={:?}",
-                found_topic.name(),
-                partition.id(),
-                partition.leader(),
-                partition.replicas(),
-                partition.isr(),
-                partition.error()
-            );
-            if fetch_offsets {
-                let (low, high) = consumer
-                    .fetch_watermarks(
-                        found_topic.name(),
-                        partition.id(),
-                        std::time::Duration::from_secs(1),
-                    )
-                    .unwrap_or((-1, -1));
-                info!(
-                    "topic={} - watermark low={} \
-                    high={} (difference={})",
-                    found_topic.name(),
-                    low,
-                    high,
-                    high - low
-                );
-                message_count += high - low;
-            }
+/// get_kafka_metadata_from_config
+///
+/// Same as [`get_kafka_metadata`] with a new consumer that uses
+/// the [`KafkaOAuthClientContext`] when
+/// ``config.oauth_token_provider`` is set
+///
+/// # Arguments
+///
+/// * `config` - initialized [`KafkaClientConfig`](crate::config::KafkaClientConfig)
+/// * `fetch_offsets` - when ``true`` fetch the watermarks for each
+/// partition
+/// * `topic` - If set, only get the details for that specific topic if set to ``None``
+/// get details for all topics
+///
+/// # Errors
+///
+/// [`KafkaThreadpoolError::Broker`] if the consumer cannot be
+/// created or the metadata cannot be fetched
+///
+pub fn get_kafka_metadata_from_config(
+    config: &KafkaClientConfig,
+    fetch_offsets: bool,
+    topic: Option<&str>,
+) -> Result<KafkaClusterMetadata, KafkaThreadpoolError> {
+    match &config.oauth_token_provider {
+        Some(token_provider) => {
+            let consumer = get_kafka_consumer_with_context(
+                config,
+                KafkaOAuthClientContext::new(
+                    KafkaClientContext::default(),
+                    token_provider.clone(),
+                ),
+            )?;
+            get_kafka_metadata(config, consumer, fetch_offsets, topic)
         }
-        if fetch_offsets {
-            info!(
-                "topic={} - message offset={message_count}",
-                found_topic.name()
-            );
+        None => {
+            let consumer = get_kafka_consumer_with_context(
+                config,
+                KafkaClientContext::default(),
+            )?;
+            get_kafka_metadata(config, consumer, fetch_offsets, topic)
         }
     }
 }
@@ -0,0 +1,40 @@
+//! class definition for a broker in the
+//! [`KafkaClusterMetadata`](crate::metadata::kafka_cluster_metadata::KafkaClusterMetadata)
+//!
+use rdkafka::metadata::MetadataBroker;
+
+/// KafkaBrokerMetadata
+///
+/// Broker address from the cluster metadata
+///
+/// * `id` - broker id
+/// * `host` - broker host
+/// * `port` - broker port
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KafkaBrokerMetadata {
+    pub id: i32,
+    pub host: String,
+    pub port: i32,
+}
+
+impl From<&MetadataBroker> for KafkaBrokerMetadata {
+    fn from(broker: &MetadataBroker) -> Self {
+        KafkaBrokerMetadata {
+            id: broker.id(),
+            host: broker.host().to_string(),
+            port: broker.port(),
+        }
+    }
+}
+
+impl std::fmt::Display for KafkaBrokerMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "broker.id={} address={}:{}",
+            self.id, self.host, self.port
+        )
+    }
+}
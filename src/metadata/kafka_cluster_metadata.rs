@@ -0,0 +1,112 @@
+//! class definition for the kafka cluster metadata returned by
+//! [`get_kafka_metadata`](crate::metadata::get_kafka_metadata::get_kafka_metadata)
+//!
+use rdkafka::metadata::Metadata;
+
+use crate::metadata::kafka_broker_metadata::KafkaBrokerMetadata;
+use crate::metadata::kafka_topic_metadata::KafkaTopicMetadata;
+
+/// KafkaClusterMetadata
+///
+/// Brokers, topics and partitions in the kafka cluster with
+/// optional partition watermarks
+///
+/// * `orig_broker_id` - id of the broker that returned the metadata
+/// * `orig_broker_name` - name of the broker that returned the
+/// metadata
+/// * `brokers` - brokers sorted by id
+/// * `topics` - topics sorted by name
+///
+/// Use
+/// [`log_kafka_cluster_metadata`](crate::metadata::log_kafka_cluster_metadata::log_kafka_cluster_metadata)
+/// to log the details.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KafkaClusterMetadata {
+    pub orig_broker_id: i32,
+    pub orig_broker_name: String,
+    pub brokers: Vec<KafkaBrokerMetadata>,
+    pub topics: Vec<KafkaTopicMetadata>,
+}
+
+impl KafkaClusterMetadata {
+    /// get_topic
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - topic name
+    ///
+    /// # Returns
+    ///
+    /// the topic or ``None`` if the topic is not in the metadata
+    ///
+    pub fn get_topic(&self, name: &str) -> Option<&KafkaTopicMetadata> {
+        self.topics.iter().find(|topic| topic.name == name)
+    }
+
+    /// message_count
+    ///
+    /// # Returns
+    ///
+    /// total number of messages across every topic or ``None`` if
+    /// any partition is missing its watermarks
+    ///
+    pub fn message_count(&self) -> Option<i64> {
+        self.topics.iter().map(|topic| topic.message_count()).sum()
+    }
+
+    /// num_watermark_errors
+    ///
+    /// # Returns
+    ///
+    /// number of partitions across every topic where fetching the
+    /// watermarks failed
+    ///
+    pub fn num_watermark_errors(&self) -> usize {
+        self.topics
+            .iter()
+            .map(|topic| topic.num_watermark_errors())
+            .sum()
+    }
+}
+
+impl From<&Metadata> for KafkaClusterMetadata {
+    fn from(metadata: &Metadata) -> Self {
+        let mut brokers: Vec<KafkaBrokerMetadata> = metadata
+            .brokers()
+            .iter()
+            .map(KafkaBrokerMetadata::from)
+            .collect();
+        brokers.sort_by_key(|broker| broker.id);
+        let mut topics: Vec<KafkaTopicMetadata> = metadata
+            .topics()
+            .iter()
+            .map(KafkaTopicMetadata::from)
+            .collect();
+        topics.sort_by(|a, b| a.name.cmp(&b.name));
+        KafkaClusterMetadata {
+            orig_broker_id: metadata.orig_broker_id(),
+            orig_broker_name: metadata.orig_broker_name().to_string(),
+            brokers,
+            topics,
+        }
+    }
+}
+
+impl std::fmt::Display for KafkaClusterMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "KafkaClusterMetadata brokers={} num_topics={} \
+            num_partitions={} message_count={:?}",
+            self.brokers.len(),
+            self.topics.len(),
+            self.topics
+                .iter()
+                .map(|topic| topic.num_partitions())
+                .sum::<usize>(),
+            self.message_count()
+        )
+    }
+}
@@ -0,0 +1,206 @@
+//! class definition for the consumer group lag report returned by
+//! [`get_kafka_consumer_group_lag`](crate::metadata::get_kafka_consumer_group_lag::get_kafka_consumer_group_lag)
+//!
+use crate::metadata::kafka_lag_summary::KafkaLagSummary;
+use crate::metadata::kafka_partition_lag::KafkaPartitionLag;
+
+/// KafkaConsumerGroupLag
+///
+/// Per-partition committed offsets and lag for a consumer group
+///
+/// * `group_id` - consumer group id
+/// * `partitions` - partitions sorted by topic and partition id
+///
+/// Use
+/// [`summary`](crate::metadata::kafka_consumer_group_lag::KafkaConsumerGroupLag::summary)
+/// for alerting.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KafkaConsumerGroupLag {
+    pub group_id: String,
+    pub partitions: Vec<KafkaPartitionLag>,
+}
+
+impl KafkaConsumerGroupLag {
+    /// get_topic_partitions
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - topic name
+    ///
+    /// # Returns
+    ///
+    /// the partitions in the report for the ``topic``
+    ///
+    pub fn get_topic_partitions(&self, topic: &str) -> Vec<&KafkaPartitionLag> {
+        self.partitions
+            .iter()
+            .filter(|partition| partition.topic == topic)
+            .collect()
+    }
+
+    /// topic_lag
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - topic name
+    ///
+    /// # Returns
+    ///
+    /// total lag across the ``topic``'s partitions or ``None`` if
+    /// the topic is not in the report or any of its partitions has
+    /// an unknown lag
+    ///
+    pub fn topic_lag(&self, topic: &str) -> Option<i64> {
+        let partitions = self.get_topic_partitions(topic);
+        if partitions.is_empty() {
+            return None;
+        }
+        partitions.iter().map(|partition| partition.lag).sum()
+    }
+
+    /// summary
+    ///
+    /// # Returns
+    ///
+    /// [`KafkaLagSummary`] with the total and max lag and the
+    /// number of lagging, uncommitted and failed partitions
+    ///
+    pub fn summary(&self) -> KafkaLagSummary {
+        let mut summary = KafkaLagSummary {
+            group_id: self.group_id.clone(),
+            num_partitions: self.partitions.len(),
+            ..Default::default()
+        };
+        for partition in self.partitions.iter() {
+            if partition.error.is_some() {
+                summary.num_errors += 1;
+            }
+            if !partition.is_committed() {
+                summary.num_uncommitted += 1;
+            }
+            if let Some(lag) = partition.lag {
+                summary.total_lag += lag;
+                if lag > 0 {
+                    summary.num_partitions_with_lag += 1;
+                }
+                if lag > summary.max_lag {
+                    summary.max_lag = lag;
+                    summary.max_lag_topic = Some(partition.topic.clone());
+                    summary.max_lag_partition = Some(partition.partition);
+                }
+            }
+        }
+        summary
+    }
+}
+
+impl std::fmt::Display for KafkaConsumerGroupLag {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.summary())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partition_lag(
+        topic: &str,
+        partition: i32,
+        committed_offset: Option<i64>,
+        high_watermark: Option<i64>,
+    ) -> KafkaPartitionLag {
+        let mut partition = KafkaPartitionLag {
+            topic: topic.to_string(),
+            partition,
+            committed_offset,
+            high_watermark,
+            ..Default::default()
+        };
+        partition.compute_lag();
+        partition
+    }
+
+    fn group_lag(partitions: Vec<KafkaPartitionLag>) -> KafkaConsumerGroupLag {
+        KafkaConsumerGroupLag {
+            group_id: "my-group".to_string(),
+            partitions,
+        }
+    }
+
+    #[test]
+    fn summary_selects_the_max_lag_topic_and_partition() {
+        let summary = group_lag(vec![
+            partition_lag("orders", 0, Some(90), Some(100)),
+            partition_lag("orders", 1, Some(50), Some(300)),
+            partition_lag("payments", 0, Some(10), Some(260)),
+            partition_lag("payments", 1, Some(80), Some(80)),
+        ])
+        .summary();
+        assert_eq!(summary.group_id, "my-group");
+        assert_eq!(summary.total_lag, 510);
+        assert_eq!(summary.max_lag, 250);
+        // ties keep the first partition with the max lag
+        assert_eq!(summary.max_lag_topic.as_deref(), Some("orders"));
+        assert_eq!(summary.max_lag_partition, Some(1));
+        assert_eq!(summary.num_partitions, 4);
+        assert_eq!(summary.num_partitions_with_lag, 3);
+        assert_eq!(summary.num_uncommitted, 0);
+        assert_eq!(summary.num_errors, 0);
+        assert!(summary.is_lagging(249));
+        assert!(!summary.is_lagging(250));
+        assert_eq!(
+            summary.to_string(),
+            "group=my-group total_lag=510 max_lag=250 (orders:1) \
+            partitions=4 lagging=3 uncommitted=0 errors=0"
+        );
+    }
+
+    #[test]
+    fn summary_skips_uncommitted_and_failed_partitions() {
+        let mut failed = partition_lag("orders", 2, Some(10), None);
+        failed.error = Some("Local: Timed out".to_string());
+        let summary = group_lag(vec![
+            partition_lag("orders", 0, Some(95), Some(100)),
+            partition_lag("orders", 1, None, Some(1000)),
+            failed,
+        ])
+        .summary();
+        assert_eq!(summary.total_lag, 5);
+        assert_eq!(summary.max_lag, 5);
+        assert_eq!(summary.max_lag_partition, Some(0));
+        assert_eq!(summary.num_partitions_with_lag, 1);
+        assert_eq!(summary.num_uncommitted, 1);
+        assert_eq!(summary.num_errors, 1);
+        assert!(summary.has_errors());
+    }
+
+    #[test]
+    fn summary_without_lag_has_no_max_lag_partition() {
+        let summary = group_lag(vec![
+            partition_lag("orders", 0, Some(100), Some(100)),
+            partition_lag("orders", 1, Some(500), Some(20)),
+        ])
+        .summary();
+        assert_eq!(summary.total_lag, 0);
+        assert_eq!(summary.max_lag, 0);
+        assert_eq!(summary.max_lag_topic, None);
+        assert_eq!(summary.max_lag_partition, None);
+        assert_eq!(summary.num_partitions_with_lag, 0);
+        assert!(!summary.is_lagging(0));
+    }
+
+    #[test]
+    fn topic_lag_is_unknown_if_any_partition_is_unknown() {
+        let report = group_lag(vec![
+            partition_lag("orders", 0, Some(90), Some(100)),
+            partition_lag("orders", 1, Some(80), Some(100)),
+            partition_lag("payments", 0, None, Some(100)),
+        ]);
+        assert_eq!(report.topic_lag("orders"), Some(30));
+        assert_eq!(report.topic_lag("payments"), None);
+        assert_eq!(report.topic_lag("missing"), None);
+    }
+}
@@ -0,0 +1,88 @@
+//! class definition for the alerting summary of a
+//! [`KafkaConsumerGroupLag`](crate::metadata::kafka_consumer_group_lag::KafkaConsumerGroupLag)
+//!
+
+/// KafkaLagSummary
+///
+/// Totals for a consumer group's lag report sized for alerting
+/// and dashboards
+///
+/// * `group_id` - consumer group id
+/// * `total_lag` - sum of the lag across every partition with a
+/// known lag
+/// * `max_lag` - largest lag on a single partition
+/// * `max_lag_topic` - optional - topic with the largest lag
+/// * `max_lag_partition` - optional - partition with the largest
+/// lag
+/// * `num_partitions` - number of partitions in the report
+/// * `num_partitions_with_lag` - number of partitions with a lag
+/// greater than ``0``
+/// * `num_uncommitted` - number of partitions where the group
+/// never committed an offset
+/// * `num_errors` - number of partitions where fetching the
+/// committed offset or watermarks failed
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KafkaLagSummary {
+    pub group_id: String,
+    pub total_lag: i64,
+    pub max_lag: i64,
+    pub max_lag_topic: Option<String>,
+    pub max_lag_partition: Option<i32>,
+    pub num_partitions: usize,
+    pub num_partitions_with_lag: usize,
+    pub num_uncommitted: usize,
+    pub num_errors: usize,
+}
+
+impl KafkaLagSummary {
+    /// is_lagging
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - max lag allowed on a single partition
+    ///
+    /// # Returns
+    ///
+    /// ``true`` if any partition's lag is greater than the
+    /// ``threshold``
+    ///
+    pub fn is_lagging(&self, threshold: i64) -> bool {
+        self.max_lag > threshold
+    }
+
+    /// has_errors
+    ///
+    /// # Returns
+    ///
+    /// ``true`` if the lag is unknown for any committed partition
+    /// so the totals may be understated
+    ///
+    pub fn has_errors(&self) -> bool {
+        self.num_errors > 0
+    }
+}
+
+impl std::fmt::Display for KafkaLagSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "group={} total_lag={} max_lag={}",
+            self.group_id, self.total_lag, self.max_lag
+        )?;
+        if let (Some(topic), Some(partition)) =
+            (&self.max_lag_topic, self.max_lag_partition)
+        {
+            write!(f, " ({topic}:{partition})")?;
+        }
+        write!(
+            f,
+            " partitions={} lagging={} uncommitted={} errors={}",
+            self.num_partitions,
+            self.num_partitions_with_lag,
+            self.num_uncommitted,
+            self.num_errors
+        )
+    }
+}
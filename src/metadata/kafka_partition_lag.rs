@@ -0,0 +1,141 @@
+//! class definition for a partition in the
+//! [`KafkaConsumerGroupLag`](crate::metadata::kafka_consumer_group_lag::KafkaConsumerGroupLag)
+//!
+
+/// KafkaPartitionLag
+///
+/// Committed offset and lag for one partition consumed by a
+/// consumer group
+///
+/// * `topic` - topic name
+/// * `partition` - partition id
+/// * `committed_offset` - optional - offset the group committed
+/// (``None`` if the group never committed on the partition)
+/// * `high_watermark` - optional - offset the next message is
+/// written to (``None`` if the watermark fetch failed)
+/// * `lag` - optional - number of messages the group has not
+/// consumed (``None`` if the group never committed or the
+/// watermark fetch failed)
+/// * `error` - optional - why fetching the committed offset or
+/// the watermarks failed
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KafkaPartitionLag {
+    pub topic: String,
+    pub partition: i32,
+    pub committed_offset: Option<i64>,
+    pub high_watermark: Option<i64>,
+    pub lag: Option<i64>,
+    pub error: Option<String>,
+}
+
+impl KafkaPartitionLag {
+    /// compute_lag
+    ///
+    /// Set ``lag`` from the ``committed_offset`` and
+    /// ``high_watermark``. A committed offset past the high
+    /// watermark (after a topic is truncated or recreated) is
+    /// reported as no lag.
+    ///
+    pub fn compute_lag(&mut self) {
+        self.lag = match (self.committed_offset, self.high_watermark) {
+            (Some(committed), Some(high)) => Some((high - committed).max(0)),
+            _ => None,
+        };
+    }
+
+    /// is_committed
+    ///
+    /// # Returns
+    ///
+    /// ``true`` if the group committed an offset on the partition
+    ///
+    pub fn is_committed(&self) -> bool {
+        self.committed_offset.is_some()
+    }
+}
+
+impl std::fmt::Display for KafkaPartitionLag {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let committed = match self.committed_offset {
+            Some(offset) => offset.to_string(),
+            None => "none".to_string(),
+        };
+        let high = match self.high_watermark {
+            Some(offset) => offset.to_string(),
+            None => "unknown".to_string(),
+        };
+        let lag = match self.lag {
+            Some(lag) => lag.to_string(),
+            None => "unknown".to_string(),
+        };
+        write!(
+            f,
+            "topic={} partition={} committed={committed} high={high} \
+            lag={lag}",
+            self.topic, self.partition
+        )?;
+        if let Some(err) = &self.error {
+            write!(f, " error={err}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partition_lag(
+        committed_offset: Option<i64>,
+        high_watermark: Option<i64>,
+    ) -> KafkaPartitionLag {
+        let mut partition = KafkaPartitionLag {
+            topic: "orders".to_string(),
+            partition: 0,
+            committed_offset,
+            high_watermark,
+            ..Default::default()
+        };
+        partition.compute_lag();
+        partition
+    }
+
+    #[test]
+    fn compute_lag_is_high_watermark_minus_committed() {
+        assert_eq!(partition_lag(Some(40), Some(100)).lag, Some(60));
+        assert_eq!(partition_lag(Some(100), Some(100)).lag, Some(0));
+    }
+
+    #[test]
+    fn compute_lag_clamps_a_committed_offset_past_the_high_watermark() {
+        // the topic was truncated or recreated
+        assert_eq!(partition_lag(Some(500), Some(20)).lag, Some(0));
+    }
+
+    #[test]
+    fn compute_lag_is_unknown_when_uncommitted() {
+        let partition = partition_lag(None, Some(100));
+        assert!(!partition.is_committed());
+        assert_eq!(partition.lag, None);
+    }
+
+    #[test]
+    fn compute_lag_is_unknown_without_a_high_watermark() {
+        let partition = partition_lag(Some(40), None);
+        assert!(partition.is_committed());
+        assert_eq!(partition.lag, None);
+    }
+
+    #[test]
+    fn display_marks_unknown_offsets() {
+        let mut partition = partition_lag(None, None);
+        partition.error = Some("Local: Timed out".to_string());
+        assert_eq!(
+            partition.to_string(),
+            "topic=orders partition=0 committed=none high=unknown \
+            lag=unknown error=Local: Timed out"
+        );
+    }
+}
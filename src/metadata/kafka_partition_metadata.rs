@@ -0,0 +1,65 @@
+//! class definition for a partition in the
+//! [`KafkaTopicMetadata`](crate::metadata::kafka_topic_metadata::KafkaTopicMetadata)
+//!
+use rdkafka::metadata::MetadataPartition;
+use rdkafka::types::RDKafkaErrorCode;
+
+use crate::metadata::kafka_watermarks::KafkaWatermarks;
+
+/// KafkaPartitionMetadata
+///
+/// Partition leadership and replication from the cluster metadata
+///
+/// * `id` - partition id
+/// * `leader` - leader broker id
+/// * `replicas` - broker ids holding a replica
+/// * `isr` - broker ids in the in-sync replica set
+/// * `error` - optional - partition error reported by the broker
+/// * `watermarks` - optional - low and high offsets (only set when
+/// the offsets are fetched)
+/// * `watermark_error` - optional - why fetching the watermarks
+/// failed so a failed partition is not counted as empty
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KafkaPartitionMetadata {
+    pub id: i32,
+    pub leader: i32,
+    pub replicas: Vec<i32>,
+    pub isr: Vec<i32>,
+    pub error: Option<String>,
+    pub watermarks: Option<KafkaWatermarks>,
+    pub watermark_error: Option<String>,
+}
+
+impl KafkaPartitionMetadata {
+    /// message_count
+    ///
+    /// # Returns
+    ///
+    /// number of messages in the partition (``Some(0)`` for an
+    /// empty partition) or ``None`` if the watermarks were not
+    /// fetched or the fetch failed
+    ///
+    pub fn message_count(&self) -> Option<i64> {
+        self.watermarks
+            .as_ref()
+            .map(|watermarks| watermarks.message_count())
+    }
+}
+
+impl From<&MetadataPartition> for KafkaPartitionMetadata {
+    fn from(partition: &MetadataPartition) -> Self {
+        KafkaPartitionMetadata {
+            id: partition.id(),
+            leader: partition.leader(),
+            replicas: partition.replicas().to_vec(),
+            isr: partition.isr().to_vec(),
+            error: partition
+                .error()
+                .map(|err| RDKafkaErrorCode::from(err).to_string()),
+            watermarks: None,
+            watermark_error: None,
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! class definition for a topic in the
+//! [`KafkaClusterMetadata`](crate::metadata::kafka_cluster_metadata::KafkaClusterMetadata)
+//!
+use rdkafka::metadata::MetadataTopic;
+use rdkafka::types::RDKafkaErrorCode;
+
+use crate::metadata::kafka_partition_metadata::KafkaPartitionMetadata;
+
+/// KafkaTopicMetadata
+///
+/// Topic partitions from the cluster metadata
+///
+/// * `name` - topic name
+/// * `error` - optional - topic error reported by the broker
+/// * `partitions` - partitions sorted by id
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KafkaTopicMetadata {
+    pub name: String,
+    pub error: Option<String>,
+    pub partitions: Vec<KafkaPartitionMetadata>,
+}
+
+impl KafkaTopicMetadata {
+    /// num_partitions
+    ///
+    /// # Returns
+    ///
+    /// number of partitions in the topic
+    ///
+    pub fn num_partitions(&self) -> usize {
+        self.partitions.len()
+    }
+
+    /// num_watermark_errors
+    ///
+    /// # Returns
+    ///
+    /// number of partitions where fetching the watermarks failed
+    ///
+    pub fn num_watermark_errors(&self) -> usize {
+        self.partitions
+            .iter()
+            .filter(|partition| partition.watermark_error.is_some())
+            .count()
+    }
+
+    /// message_count
+    ///
+    /// # Returns
+    ///
+    /// total number of messages across the topic's partitions or
+    /// ``None`` if any partition is missing its watermarks (not
+    /// fetched or the fetch failed) so a partial count is never
+    /// reported as the topic total
+    ///
+    pub fn message_count(&self) -> Option<i64> {
+        self.partitions
+            .iter()
+            .map(|partition| partition.message_count())
+            .sum()
+    }
+}
+
+impl From<&MetadataTopic> for KafkaTopicMetadata {
+    fn from(topic: &MetadataTopic) -> Self {
+        let mut partitions: Vec<KafkaPartitionMetadata> = topic
+            .partitions()
+            .iter()
+            .map(KafkaPartitionMetadata::from)
+            .collect();
+        partitions.sort_by_key(|partition| partition.id);
+        KafkaTopicMetadata {
+            name: topic.name().to_string(),
+            error: topic
+                .error()
+                .map(|err| RDKafkaErrorCode::from(err).to_string()),
+            partitions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::kafka_cluster_metadata::KafkaClusterMetadata;
+    use crate::metadata::kafka_watermarks::KafkaWatermarks;
+
+    fn fetched(id: i32, low: i64, high: i64) -> KafkaPartitionMetadata {
+        KafkaPartitionMetadata {
+            id,
+            watermarks: Some(KafkaWatermarks { low, high }),
+            ..Default::default()
+        }
+    }
+
+    fn failed(id: i32) -> KafkaPartitionMetadata {
+        KafkaPartitionMetadata {
+            id,
+            watermark_error: Some("Local: Timed out".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn topic(
+        name: &str,
+        partitions: Vec<KafkaPartitionMetadata>,
+    ) -> KafkaTopicMetadata {
+        KafkaTopicMetadata {
+            name: name.to_string(),
+            error: None,
+            partitions,
+        }
+    }
+
+    #[test]
+    fn message_count_sums_the_partitions() {
+        let found_topic =
+            topic("orders", vec![fetched(0, 0, 10), fetched(1, 5, 25)]);
+        assert_eq!(found_topic.num_partitions(), 2);
+        assert_eq!(found_topic.num_watermark_errors(), 0);
+        assert_eq!(found_topic.message_count(), Some(30));
+    }
+
+    #[test]
+    fn empty_partitions_count_as_zero() {
+        let found_topic =
+            topic("orders", vec![fetched(0, 0, 0), fetched(1, 8, 8)]);
+        assert_eq!(found_topic.partitions[0].message_count(), Some(0));
+        assert_eq!(found_topic.message_count(), Some(0));
+    }
+
+    #[test]
+    fn failed_partition_is_not_counted_as_empty() {
+        let found_topic = topic("orders", vec![fetched(0, 0, 10), failed(1)]);
+        assert_eq!(found_topic.partitions[1].watermarks, None);
+        assert_eq!(found_topic.partitions[1].message_count(), None);
+        assert_eq!(found_topic.num_watermark_errors(), 1);
+        // a partial count is never reported as the topic total
+        assert_eq!(found_topic.message_count(), None);
+    }
+
+    #[test]
+    fn unfetched_partitions_have_no_count() {
+        let found_topic = topic(
+            "orders",
+            vec![KafkaPartitionMetadata {
+                id: 0,
+                ..Default::default()
+            }],
+        );
+        assert_eq!(found_topic.num_watermark_errors(), 0);
+        assert_eq!(found_topic.message_count(), None);
+    }
+
+    #[test]
+    fn topic_totals_do_not_accumulate_across_topics() {
+        let cluster_metadata = KafkaClusterMetadata {
+            topics: vec![
+                topic("orders", vec![fetched(0, 0, 10), fetched(1, 0, 20)]),
+                topic("payments", vec![fetched(0, 0, 3)]),
+                topic("users", vec![fetched(0, 0, 7), failed(1)]),
+            ],
+            ..Default::default()
+        };
+        let message_count = |name: &str| {
+            cluster_metadata.get_topic(name).unwrap().message_count()
+        };
+        assert_eq!(message_count("orders"), Some(30));
+        assert_eq!(message_count("payments"), Some(3));
+        assert_eq!(message_count("users"), None);
+        assert_eq!(cluster_metadata.num_watermark_errors(), 1);
+        assert_eq!(cluster_metadata.message_count(), None);
+    }
+}
@@ -0,0 +1,71 @@
+//! class definition for a partition's low and high offsets
+//!
+
+/// KafkaWatermarks
+///
+/// Low and high watermark offsets for a partition where the
+/// difference is the number of messages kafka still retains
+///
+/// * `low` - oldest retained offset
+/// * `high` - offset the next message is written to
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KafkaWatermarks {
+    pub low: i64,
+    pub high: i64,
+}
+
+impl KafkaWatermarks {
+    /// message_count
+    ///
+    /// # Returns
+    ///
+    /// number of messages between the ``low`` and ``high``
+    /// watermarks
+    ///
+    pub fn message_count(&self) -> i64 {
+        self.high - self.low
+    }
+}
+
+impl std::fmt::Display for KafkaWatermarks {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "watermark low={} high={} (difference={})",
+            self.low,
+            self.high,
+            self.message_count()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_count_is_the_watermark_difference() {
+        let watermarks = KafkaWatermarks { low: 40, high: 100 };
+        assert_eq!(watermarks.message_count(), 60);
+    }
+
+    #[test]
+    fn empty_partition_has_no_messages() {
+        let watermarks = KafkaWatermarks { low: 0, high: 0 };
+        assert_eq!(watermarks.message_count(), 0);
+        // retention deleted every message
+        let watermarks = KafkaWatermarks { low: 75, high: 75 };
+        assert_eq!(watermarks.message_count(), 0);
+    }
+
+    #[test]
+    fn display_includes_the_difference() {
+        let watermarks = KafkaWatermarks { low: 5, high: 12 };
+        assert_eq!(
+            watermarks.to_string(),
+            "watermark low=5 high=12 (difference=7)"
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! Log formatter for the
+//! [`KafkaClusterMetadata`](crate::metadata::kafka_cluster_metadata::KafkaClusterMetadata)
+//!
+use log::info;
+use log::warn;
+
+use crate::metadata::kafka_cluster_metadata::KafkaClusterMetadata;
+
+/// log_kafka_cluster_metadata
+///
+/// Log the brokers, topics, partitions and watermarks with
+/// ``info!``
+///
+/// # Arguments
+///
+/// * `metadata` - cluster metadata from
+/// [`get_kafka_metadata`](crate::metadata::get_kafka_metadata::get_kafka_metadata)
+///
+pub fn log_kafka_cluster_metadata(metadata: &KafkaClusterMetadata) {
+    let broker_listing: Vec<String> = metadata
+        .brokers
+        .iter()
+        .map(|broker| broker.to_string())
+        .collect();
+    info!(
+        "cluster info brokers={} num_topics={} {}",
+        metadata.brokers.len(),
+        metadata.topics.len(),
+        broker_listing.join(" ")
+    );
+    for topic in metadata.topics.iter() {
+        info!("topic={} err={:?}", topic.name, topic.error);
+        for partition in topic.partitions.iter() {
+            info!(
+                "topic={} - partition={} \
+                leader={} replicas={:?} \
+                ISR={:?} err={:?}",
+                topic.name,
+                partition.id,
+                partition.leader,
+                partition.replicas,
+                partition.isr,
+                partition.error
+            );
+            if let Some(watermarks) = &partition.watermarks {
+                info!(
+                    "topic={} - partition={} {watermarks}",
+                    topic.name, partition.id
+                );
+            }
+            if let Some(watermark_error) = &partition.watermark_error {
+                warn!(
+                    "topic={} - partition={} \
+                    failed to fetch watermarks with err={watermark_error}",
+                    topic.name, partition.id
+                );
+            }
+        }
+        match topic.message_count() {
+            Some(message_count) => {
+                info!("topic={} - message offset={message_count}", topic.name)
+            }
+            None if topic.num_watermark_errors() > 0 => warn!(
+                "topic={} - message offset=unknown \
+                watermark_errors={}",
+                topic.name,
+                topic.num_watermark_errors()
+            ),
+            None => {}
+        }
+    }
+}
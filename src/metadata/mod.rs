@@ -1,3 +1,14 @@
 //! APIs for getting information and stats from the kafka cluster
 //!
+pub mod fetch_kafka_watermarks;
+pub mod get_kafka_consumer_group_lag;
 pub mod get_kafka_metadata;
+pub mod kafka_broker_metadata;
+pub mod kafka_cluster_metadata;
+pub mod kafka_consumer_group_lag;
+pub mod kafka_lag_summary;
+pub mod kafka_partition_lag;
+pub mod kafka_partition_metadata;
+pub mod kafka_topic_metadata;
+pub mod kafka_watermarks;
+pub mod log_kafka_cluster_metadata;
@@ -0,0 +1,95 @@
+//! Metric names, help text and histogram buckets for the
+//! [`KafkaMetricsRegistry`](crate::metrics::kafka_metrics_registry::KafkaMetricsRegistry)
+//!
+
+/// METRIC_MSGS_ENQUEUED
+///
+/// counter - messages added to the work vec
+///
+pub const METRIC_MSGS_ENQUEUED: &str =
+    "kafka_threadpool_messages_enqueued_total";
+
+/// METRIC_MSGS_DROPPED
+///
+/// counter - messages dropped by the queue overflow policy
+///
+pub const METRIC_MSGS_DROPPED: &str = "kafka_threadpool_messages_dropped_total";
+
+/// METRIC_MSGS_PUBLISHED
+///
+/// counter - messages acknowledged by kafka
+///
+pub const METRIC_MSGS_PUBLISHED: &str =
+    "kafka_threadpool_messages_published_total";
+
+/// METRIC_MSGS_RETRIED
+///
+/// counter - publish attempts that failed and were retried
+///
+pub const METRIC_MSGS_RETRIED: &str = "kafka_threadpool_messages_retried_total";
+
+/// METRIC_MSGS_FAILED
+///
+/// counter - messages that failed with a fatal error, exhausted
+/// the retry policy or were in an aborted transaction
+///
+pub const METRIC_MSGS_FAILED: &str = "kafka_threadpool_messages_failed_total";
+
+/// METRIC_MSGS_ABANDONED
+///
+/// counter - messages abandoned after the shutdown deadline
+///
+pub const METRIC_MSGS_ABANDONED: &str =
+    "kafka_threadpool_messages_abandoned_total";
+
+/// METRIC_QUEUE_DEPTH
+///
+/// gauge - messages waiting in the work vec
+///
+pub const METRIC_QUEUE_DEPTH: &str = "kafka_threadpool_queue_depth";
+
+/// METRIC_PUBLISH_LATENCY
+///
+/// histogram - seconds from enqueueing a message in the producer
+/// until the broker acknowledgement
+///
+pub const METRIC_PUBLISH_LATENCY: &str =
+    "kafka_threadpool_publish_latency_seconds";
+
+/// LATENCY_BUCKETS
+///
+/// Upper bounds (in seconds) for the publish latency histogram
+///
+pub const LATENCY_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// metric_help
+///
+/// # Returns
+///
+/// the ``# HELP`` text for a metric name
+///
+/// # Arguments
+///
+/// * `name` - metric name
+///
+pub fn metric_help(name: &str) -> &'static str {
+    match name {
+        METRIC_MSGS_ENQUEUED => "Messages added to the work vec",
+        METRIC_MSGS_DROPPED => "Messages dropped by the queue overflow policy",
+        METRIC_MSGS_PUBLISHED => "Messages acknowledged by kafka",
+        METRIC_MSGS_RETRIED => "Publish attempts that failed and were retried",
+        METRIC_MSGS_FAILED => {
+            "Messages that failed to publish after the retry policy"
+        }
+        METRIC_MSGS_ABANDONED => {
+            "Messages abandoned after the shutdown deadline"
+        }
+        METRIC_QUEUE_DEPTH => "Messages waiting in the work vec",
+        METRIC_PUBLISH_LATENCY => {
+            "Seconds from producing a message until the broker acknowledgement"
+        }
+        _ => "kafka_threadpool metric",
+    }
+}
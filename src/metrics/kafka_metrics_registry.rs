@@ -0,0 +1,498 @@
+//! Built-in metrics registry with counters, gauges and histograms
+//! and a Prometheus text exposition renderer
+//!
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+
+use crate::metrics::kafka_metric_names::metric_help;
+use crate::metrics::kafka_metric_names::LATENCY_BUCKETS;
+
+/// KafkaMetricKey
+///
+/// Internal metric identity - the name and the sorted label pairs
+///
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct KafkaMetricKey {
+    name: String,
+    labels: Vec<(String, String)>,
+}
+
+impl KafkaMetricKey {
+    fn new(name: &str, labels: &[(&str, &str)]) -> Self {
+        let mut labels: Vec<(String, String)> = labels
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        labels.sort();
+        KafkaMetricKey {
+            name: name.to_string(),
+            labels,
+        }
+    }
+}
+
+/// KafkaMetricValue
+///
+/// Internal metric value where histograms hold one cumulative
+/// count per bucket in
+/// [`LATENCY_BUCKETS`](crate::metrics::kafka_metric_names::LATENCY_BUCKETS)
+///
+#[derive(Debug, Clone)]
+enum KafkaMetricValue {
+    Counter(u64),
+    Gauge(f64),
+    Histogram {
+        buckets: Vec<u64>,
+        sum: f64,
+        count: u64,
+    },
+}
+
+/// escape_label_value
+///
+/// Internal helper for escaping a label value for the text
+/// exposition format
+///
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// format_labels
+///
+/// Internal helper for rendering ``{key="value",...}`` with an
+/// optional extra label (the histogram ``le``)
+///
+fn format_labels(
+    labels: &[(String, String)],
+    extra: Option<(&str, &str)>,
+) -> String {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| format!("{key}=\"{}\"", escape_label_value(value)))
+        .collect();
+    if let Some((key, value)) = extra {
+        pairs.push(format!("{key}=\"{value}\""));
+    }
+    if pairs.is_empty() {
+        "".to_string()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+/// KafkaMetricsRegistry
+///
+/// Thread-safe registry for the threadpool metrics. Most callers
+/// use the process-wide registry from
+/// [`global_metrics()`](crate::metrics::kafka_metrics_registry::global_metrics).
+///
+#[derive(Debug, Default)]
+pub struct KafkaMetricsRegistry {
+    metrics: Mutex<BTreeMap<KafkaMetricKey, KafkaMetricValue>>,
+}
+
+impl KafkaMetricsRegistry {
+    /// new
+    ///
+    /// Create an empty registry
+    ///
+    pub fn new() -> Self {
+        KafkaMetricsRegistry {
+            metrics: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// increment_counter
+    ///
+    /// Add to a counter (created at ``0``)
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - metric name
+    /// * `labels` - label key/value pairs
+    /// * `value` - amount to add
+    ///
+    pub fn increment_counter(
+        &self,
+        name: &str,
+        labels: &[(&str, &str)],
+        value: u64,
+    ) {
+        if let Ok(mut metrics) = self.metrics.lock() {
+            let entry = metrics
+                .entry(KafkaMetricKey::new(name, labels))
+                .or_insert(KafkaMetricValue::Counter(0));
+            if let KafkaMetricValue::Counter(counter) = entry {
+                *counter += value;
+            }
+        }
+    }
+
+    /// set_gauge
+    ///
+    /// Set a gauge to the current value
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - metric name
+    /// * `labels` - label key/value pairs
+    /// * `value` - current value
+    ///
+    pub fn set_gauge(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        if let Ok(mut metrics) = self.metrics.lock() {
+            metrics.insert(
+                KafkaMetricKey::new(name, labels),
+                KafkaMetricValue::Gauge(value),
+            );
+        }
+    }
+
+    /// observe_histogram
+    ///
+    /// Add an observation to a histogram with the
+    /// [`LATENCY_BUCKETS`](crate::metrics::kafka_metric_names::LATENCY_BUCKETS)
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - metric name
+    /// * `labels` - label key/value pairs
+    /// * `value` - observed value
+    ///
+    pub fn observe_histogram(
+        &self,
+        name: &str,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        if let Ok(mut metrics) = self.metrics.lock() {
+            let entry = metrics
+                .entry(KafkaMetricKey::new(name, labels))
+                .or_insert_with(|| KafkaMetricValue::Histogram {
+                    buckets: vec![0; LATENCY_BUCKETS.len()],
+                    sum: 0.0,
+                    count: 0,
+                });
+            if let KafkaMetricValue::Histogram {
+                buckets,
+                sum,
+                count,
+            } = entry
+            {
+                for (idx, upper_bound) in LATENCY_BUCKETS.iter().enumerate() {
+                    if value <= *upper_bound {
+                        buckets[idx] += 1;
+                    }
+                }
+                *sum += value;
+                *count += 1;
+            }
+        }
+    }
+
+    /// counter_value
+    ///
+    /// # Returns
+    ///
+    /// the current counter value or ``None`` if the counter was
+    /// never incremented
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - metric name
+    /// * `labels` - label key/value pairs
+    ///
+    pub fn counter_value(
+        &self,
+        name: &str,
+        labels: &[(&str, &str)],
+    ) -> Option<u64> {
+        let metrics = self.metrics.lock().ok()?;
+        match metrics.get(&KafkaMetricKey::new(name, labels)) {
+            Some(KafkaMetricValue::Counter(counter)) => Some(*counter),
+            _ => None,
+        }
+    }
+
+    /// gauge_value
+    ///
+    /// # Returns
+    ///
+    /// the current gauge value or ``None`` if the gauge was
+    /// never set
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - metric name
+    /// * `labels` - label key/value pairs
+    ///
+    pub fn gauge_value(
+        &self,
+        name: &str,
+        labels: &[(&str, &str)],
+    ) -> Option<f64> {
+        let metrics = self.metrics.lock().ok()?;
+        match metrics.get(&KafkaMetricKey::new(name, labels)) {
+            Some(KafkaMetricValue::Gauge(gauge)) => Some(*gauge),
+            _ => None,
+        }
+    }
+
+    /// reset
+    ///
+    /// Remove every metric
+    ///
+    pub fn reset(&self) {
+        if let Ok(mut metrics) = self.metrics.lock() {
+            metrics.clear();
+        }
+    }
+
+    /// render_prometheus
+    ///
+    /// Render every metric in the Prometheus text exposition
+    /// format (``text/plain; version=0.0.4``)
+    ///
+    /// # Returns
+    ///
+    /// ``String`` with the ``# HELP`` and ``# TYPE`` lines and one
+    /// sample per line
+    ///
+    pub fn render_prometheus(&self) -> String {
+        let metrics = match self.metrics.lock() {
+            Ok(metrics) => metrics.clone(),
+            Err(_) => return "".to_string(),
+        };
+        let mut output = String::new();
+        let mut last_name: Option<&str> = None;
+        for (key, value) in metrics.iter() {
+            if last_name != Some(key.name.as_str()) {
+                let metric_type = match value {
+                    KafkaMetricValue::Counter(_) => "counter",
+                    KafkaMetricValue::Gauge(_) => "gauge",
+                    KafkaMetricValue::Histogram { .. } => "histogram",
+                };
+                let _ = writeln!(
+                    output,
+                    "# HELP {} {}",
+                    key.name,
+                    metric_help(&key.name)
+                );
+                let _ = writeln!(output, "# TYPE {} {metric_type}", key.name);
+                last_name = Some(key.name.as_str());
+            }
+            match value {
+                KafkaMetricValue::Counter(counter) => {
+                    let _ = writeln!(
+                        output,
+                        "{}{} {counter}",
+                        key.name,
+                        format_labels(&key.labels, None)
+                    );
+                }
+                KafkaMetricValue::Gauge(gauge) => {
+                    let _ = writeln!(
+                        output,
+                        "{}{} {gauge}",
+                        key.name,
+                        format_labels(&key.labels, None)
+                    );
+                }
+                KafkaMetricValue::Histogram {
+                    buckets,
+                    sum,
+                    count,
+                } => {
+                    for (idx, upper_bound) in LATENCY_BUCKETS.iter().enumerate()
+                    {
+                        let _ = writeln!(
+                            output,
+                            "{}_bucket{} {}",
+                            key.name,
+                            format_labels(
+                                &key.labels,
+                                Some(("le", &upper_bound.to_string()))
+                            ),
+                            buckets[idx]
+                        );
+                    }
+                    let _ = writeln!(
+                        output,
+                        "{}_bucket{} {count}",
+                        key.name,
+                        format_labels(&key.labels, Some(("le", "+Inf")))
+                    );
+                    let _ = writeln!(
+                        output,
+                        "{}_sum{} {sum}",
+                        key.name,
+                        format_labels(&key.labels, None)
+                    );
+                    let _ = writeln!(
+                        output,
+                        "{}_count{} {count}",
+                        key.name,
+                        format_labels(&key.labels, None)
+                    );
+                }
+            }
+        }
+        output
+    }
+}
+
+/// global_metrics
+///
+/// # Returns
+///
+/// the process-wide [`KafkaMetricsRegistry`] shared by every
+/// threadpool
+///
+pub fn global_metrics() -> &'static KafkaMetricsRegistry {
+    static REGISTRY: OnceLock<KafkaMetricsRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(KafkaMetricsRegistry::new)
+}
+
+/// render_prometheus_metrics
+///
+/// # Returns
+///
+/// the [`global_metrics()`](crate::metrics::kafka_metrics_registry::global_metrics)
+/// in the Prometheus text exposition format
+///
+pub fn render_prometheus_metrics() -> String {
+    global_metrics().render_prometheus()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::kafka_metric_names::METRIC_MSGS_ENQUEUED;
+    use crate::metrics::kafka_metric_names::METRIC_PUBLISH_LATENCY;
+    use crate::metrics::kafka_metric_names::METRIC_QUEUE_DEPTH;
+
+    #[test]
+    fn counters_accumulate_per_label_set() {
+        let registry = KafkaMetricsRegistry::new();
+        let orders = [("pool", "ktp"), ("topic", "orders")];
+        assert_eq!(registry.counter_value(METRIC_MSGS_ENQUEUED, &orders), None);
+        registry.increment_counter(METRIC_MSGS_ENQUEUED, &orders, 2);
+        registry.increment_counter(METRIC_MSGS_ENQUEUED, &orders, 3);
+        registry.increment_counter(
+            METRIC_MSGS_ENQUEUED,
+            &[("pool", "ktp"), ("topic", "payments")],
+            1,
+        );
+        assert_eq!(
+            registry.counter_value(METRIC_MSGS_ENQUEUED, &orders),
+            Some(5)
+        );
+        // the label order does not change the metric identity
+        assert_eq!(
+            registry.counter_value(
+                METRIC_MSGS_ENQUEUED,
+                &[("topic", "payments"), ("pool", "ktp")]
+            ),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn gauges_keep_the_latest_value() {
+        let registry = KafkaMetricsRegistry::new();
+        let labels = [("pool", "ktp")];
+        registry.set_gauge(METRIC_QUEUE_DEPTH, &labels, 10.0);
+        registry.set_gauge(METRIC_QUEUE_DEPTH, &labels, 4.0);
+        assert_eq!(
+            registry.gauge_value(METRIC_QUEUE_DEPTH, &labels),
+            Some(4.0)
+        );
+        // a gauge is not a counter
+        assert_eq!(registry.counter_value(METRIC_QUEUE_DEPTH, &labels), None);
+        registry.reset();
+        assert_eq!(registry.gauge_value(METRIC_QUEUE_DEPTH, &labels), None);
+        assert_eq!(registry.render_prometheus(), "");
+    }
+
+    #[test]
+    fn render_counters_and_gauges() {
+        let registry = KafkaMetricsRegistry::new();
+        registry.increment_counter(
+            METRIC_MSGS_ENQUEUED,
+            &[("pool", "ktp"), ("topic", "orders")],
+            7,
+        );
+        registry.increment_counter(
+            METRIC_MSGS_ENQUEUED,
+            &[("pool", "ktp"), ("topic", "payments")],
+            2,
+        );
+        registry.set_gauge(METRIC_QUEUE_DEPTH, &[("pool", "ktp")], 3.0);
+        assert_eq!(
+            registry.render_prometheus(),
+            "# HELP kafka_threadpool_messages_enqueued_total \
+            Messages added to the work vec\n\
+            # TYPE kafka_threadpool_messages_enqueued_total counter\n\
+            kafka_threadpool_messages_enqueued_total\
+            {pool=\"ktp\",topic=\"orders\"} 7\n\
+            kafka_threadpool_messages_enqueued_total\
+            {pool=\"ktp\",topic=\"payments\"} 2\n\
+            # HELP kafka_threadpool_queue_depth \
+            Messages waiting in the work vec\n\
+            # TYPE kafka_threadpool_queue_depth gauge\n\
+            kafka_threadpool_queue_depth{pool=\"ktp\"} 3\n"
+        );
+    }
+
+    #[test]
+    fn render_histogram_buckets_are_cumulative() {
+        let registry = KafkaMetricsRegistry::new();
+        let labels = [("pool", "ktp")];
+        registry.observe_histogram(METRIC_PUBLISH_LATENCY, &labels, 0.003);
+        registry.observe_histogram(METRIC_PUBLISH_LATENCY, &labels, 0.2);
+        registry.observe_histogram(METRIC_PUBLISH_LATENCY, &labels, 60.0);
+        let output = registry.render_prometheus();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[1],
+            "# TYPE kafka_threadpool_publish_latency_seconds histogram"
+        );
+        assert_eq!(lines.len(), 2 + LATENCY_BUCKETS.len() + 3);
+        assert!(lines.contains(
+            &"kafka_threadpool_publish_latency_seconds_bucket\
+            {pool=\"ktp\",le=\"0.001\"} 0"
+        ));
+        assert!(lines.contains(
+            &"kafka_threadpool_publish_latency_seconds_bucket\
+            {pool=\"ktp\",le=\"0.005\"} 1"
+        ));
+        assert!(lines.contains(
+            &"kafka_threadpool_publish_latency_seconds_bucket\
+            {pool=\"ktp\",le=\"0.25\"} 2"
+        ));
+        assert!(lines.contains(
+            &"kafka_threadpool_publish_latency_seconds_bucket\
+            {pool=\"ktp\",le=\"10\"} 2"
+        ));
+        assert!(lines.contains(
+            &"kafka_threadpool_publish_latency_seconds_bucket\
+            {pool=\"ktp\",le=\"+Inf\"} 3"
+        ));
+        assert!(lines.contains(
+            &"kafka_threadpool_publish_latency_seconds_count{pool=\"ktp\"} 3"
+        ));
+    }
+
+    #[test]
+    fn render_escapes_label_values() {
+        let registry = KafkaMetricsRegistry::new();
+        registry.set_gauge(METRIC_QUEUE_DEPTH, &[("pool", "a\"b\\c\nd")], 1.0);
+        assert!(registry.render_prometheus().contains(
+            "kafka_threadpool_queue_depth{pool=\"a\\\"b\\\\c\\nd\"} 1\n"
+        ));
+    }
+}
@@ -0,0 +1,29 @@
+//! Prometheus-style metrics for the threadpool (requires the
+//! ``metrics`` feature) using a built-in registry so no metrics
+//! backend is required:
+//!
+//! | Metric                                          | Type      | Labels               |
+//! | ----------------------------------------------- | --------- | -------------------- |
+//! | kafka_threadpool_messages_enqueued_total        | counter   | pool, topic          |
+//! | kafka_threadpool_messages_dropped_total         | counter   | pool                 |
+//! | kafka_threadpool_messages_published_total       | counter   | pool, thread, topic  |
+//! | kafka_threadpool_messages_retried_total         | counter   | pool, thread, topic  |
+//! | kafka_threadpool_messages_failed_total          | counter   | pool, thread, topic  |
+//! | kafka_threadpool_messages_abandoned_total       | counter   | pool, thread         |
+//! | kafka_threadpool_queue_depth                    | gauge     | pool                 |
+//! | kafka_threadpool_publish_latency_seconds        | histogram | pool, thread, topic  |
+//!
+//! The ``pool`` label is the ``KAFKA_LOG_LABEL`` and the ``thread``
+//! label is the worker thread's log label (``ktp-tid-1``).
+//!
+//! Example for serving the text exposition format:
+//!
+//! ```rust
+//! use kafka_threadpool::metrics::kafka_metrics_registry::render_prometheus_metrics;
+//! let body: String = render_prometheus_metrics();
+//! // return body with content-type: text/plain; version=0.0.4
+//! ```
+//!
+pub mod kafka_metric_names;
+pub mod kafka_metrics_registry;
+pub mod record_metrics;
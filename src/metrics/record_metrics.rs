@@ -0,0 +1,286 @@
+//! Helpers for recording the threadpool metrics in the
+//! [`global_metrics()`](crate::metrics::kafka_metrics_registry::global_metrics)
+//! registry
+//!
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::api::kafka_publish_message::KafkaPublishMessage;
+use crate::metrics::kafka_metric_names::METRIC_MSGS_ABANDONED;
+use crate::metrics::kafka_metric_names::METRIC_MSGS_DROPPED;
+use crate::metrics::kafka_metric_names::METRIC_MSGS_ENQUEUED;
+use crate::metrics::kafka_metric_names::METRIC_MSGS_FAILED;
+use crate::metrics::kafka_metric_names::METRIC_MSGS_PUBLISHED;
+use crate::metrics::kafka_metric_names::METRIC_MSGS_RETRIED;
+use crate::metrics::kafka_metric_names::METRIC_PUBLISH_LATENCY;
+use crate::metrics::kafka_metric_names::METRIC_QUEUE_DEPTH;
+use crate::metrics::kafka_metrics_registry::global_metrics;
+
+/// count_msgs_by_topic
+///
+/// Count the publishable messages per topic (messages in a
+/// transaction are counted by their own topic)
+///
+/// # Arguments
+///
+/// * `msgs` - messages to count
+///
+pub fn count_msgs_by_topic(
+    msgs: &[KafkaPublishMessage],
+) -> BTreeMap<String, u64> {
+    let mut counts: BTreeMap<String, u64> = BTreeMap::new();
+    for msg in msgs.iter() {
+        match &msg.transaction {
+            Some(transaction) => {
+                for txn_msg in transaction.msgs.iter() {
+                    *counts.entry(txn_msg.topic.clone()).or_insert(0) += 1;
+                }
+            }
+            None => {
+                if !msg.topic.is_empty() {
+                    *counts.entry(msg.topic.clone()).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+    counts
+}
+
+/// record_enqueued
+///
+/// # Arguments
+///
+/// * `pool` - threadpool label
+/// * `counts` - number of messages added per topic from
+/// [`count_msgs_by_topic`]
+///
+pub fn record_enqueued(pool: &str, counts: &BTreeMap<String, u64>) {
+    for (topic, count) in counts.iter() {
+        global_metrics().increment_counter(
+            METRIC_MSGS_ENQUEUED,
+            &[("pool", pool), ("topic", topic)],
+            *count,
+        );
+    }
+}
+
+/// record_dropped
+///
+/// # Arguments
+///
+/// * `pool` - threadpool label
+/// * `num_dropped` - messages dropped by the overflow policy
+///
+pub fn record_dropped(pool: &str, num_dropped: usize) {
+    if num_dropped > 0 {
+        global_metrics().increment_counter(
+            METRIC_MSGS_DROPPED,
+            &[("pool", pool)],
+            num_dropped as u64,
+        );
+    }
+}
+
+/// set_queue_depth
+///
+/// # Arguments
+///
+/// * `pool` - threadpool label
+/// * `num_in_vec` - messages waiting in the work vec
+///
+pub fn set_queue_depth(pool: &str, num_in_vec: usize) {
+    global_metrics().set_gauge(
+        METRIC_QUEUE_DEPTH,
+        &[("pool", pool)],
+        num_in_vec as f64,
+    );
+}
+
+/// record_published
+///
+/// # Arguments
+///
+/// * `pool` - threadpool label
+/// * `thread` - worker thread log label
+/// * `topic` - kafka topic
+/// * `latency` - time from producing the message until the
+/// broker acknowledgement
+///
+pub fn record_published(
+    pool: &str,
+    thread: &str,
+    topic: &str,
+    latency: Duration,
+) {
+    let labels = [("pool", pool), ("thread", thread), ("topic", topic)];
+    global_metrics().increment_counter(METRIC_MSGS_PUBLISHED, &labels, 1);
+    global_metrics().observe_histogram(
+        METRIC_PUBLISH_LATENCY,
+        &labels,
+        latency.as_secs_f64(),
+    );
+}
+
+/// record_retried
+///
+/// # Arguments
+///
+/// * `pool` - threadpool label
+/// * `thread` - worker thread log label
+/// * `topic` - kafka topic
+///
+pub fn record_retried(pool: &str, thread: &str, topic: &str) {
+    global_metrics().increment_counter(
+        METRIC_MSGS_RETRIED,
+        &[("pool", pool), ("thread", thread), ("topic", topic)],
+        1,
+    );
+}
+
+/// record_failed
+///
+/// # Arguments
+///
+/// * `pool` - threadpool label
+/// * `thread` - worker thread log label
+/// * `topic` - kafka topic
+///
+pub fn record_failed(pool: &str, thread: &str, topic: &str) {
+    global_metrics().increment_counter(
+        METRIC_MSGS_FAILED,
+        &[("pool", pool), ("thread", thread), ("topic", topic)],
+        1,
+    );
+}
+
+/// record_abandoned
+///
+/// # Arguments
+///
+/// * `pool` - threadpool label
+/// * `thread` - worker thread log label
+/// * `num_abandoned` - messages abandoned after the shutdown
+/// deadline
+///
+pub fn record_abandoned(pool: &str, thread: &str, num_abandoned: usize) {
+    if num_abandoned > 0 {
+        global_metrics().increment_counter(
+            METRIC_MSGS_ABANDONED,
+            &[("pool", pool), ("thread", thread)],
+            num_abandoned as u64,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::kafka_publish_message_type::KafkaPublishMessageType;
+    use crate::api::kafka_transaction::KafkaTransaction;
+
+    fn data_msg(topic: &str) -> KafkaPublishMessage {
+        KafkaPublishMessage::new_from_bytes(
+            KafkaPublishMessageType::Data,
+            topic,
+            None,
+            Vec::new(),
+            Some(b"payload".to_vec()),
+        )
+    }
+
+    #[test]
+    fn count_msgs_by_topic_counts_transaction_msgs_by_topic() {
+        let mut shutdown = KafkaPublishMessage::new();
+        shutdown.msg_type = KafkaPublishMessageType::Shutdown;
+        let msgs = vec![
+            data_msg("orders"),
+            data_msg("orders"),
+            shutdown,
+            KafkaPublishMessage::new_transaction(KafkaTransaction::new(
+                vec![data_msg("orders"), data_msg("payments")],
+                None,
+            )),
+        ];
+        let counts = count_msgs_by_topic(&msgs);
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts.get("orders"), Some(&3));
+        assert_eq!(counts.get("payments"), Some(&1));
+    }
+
+    // each test uses its own pool label because the global
+    // registry is shared across the tests
+    #[test]
+    fn record_counters_in_the_global_registry() {
+        let pool = "record-counters";
+        let mut counts = BTreeMap::new();
+        counts.insert("orders".to_string(), 4);
+        record_enqueued(pool, &counts);
+        record_enqueued(pool, &counts);
+        record_dropped(pool, 0);
+        record_dropped(pool, 3);
+        record_retried(pool, "ktp-tid-1", "orders");
+        record_failed(pool, "ktp-tid-1", "orders");
+        record_abandoned(pool, "ktp-tid-1", 0);
+        let metrics = global_metrics();
+        assert_eq!(
+            metrics.counter_value(
+                METRIC_MSGS_ENQUEUED,
+                &[("pool", pool), ("topic", "orders")]
+            ),
+            Some(8)
+        );
+        assert_eq!(
+            metrics.counter_value(METRIC_MSGS_DROPPED, &[("pool", pool)]),
+            Some(3)
+        );
+        let labels =
+            [("pool", pool), ("thread", "ktp-tid-1"), ("topic", "orders")];
+        assert_eq!(
+            metrics.counter_value(METRIC_MSGS_RETRIED, &labels),
+            Some(1)
+        );
+        assert_eq!(metrics.counter_value(METRIC_MSGS_FAILED, &labels), Some(1));
+        // zero abandoned messages do not create the counter
+        assert_eq!(
+            metrics.counter_value(
+                METRIC_MSGS_ABANDONED,
+                &[("pool", pool), ("thread", "ktp-tid-1")]
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn record_published_counts_and_observes_latency() {
+        let pool = "record-published";
+        record_published(
+            pool,
+            "ktp-tid-1",
+            "orders",
+            Duration::from_millis(20),
+        );
+        assert_eq!(
+            global_metrics().counter_value(
+                METRIC_MSGS_PUBLISHED,
+                &[("pool", pool), ("thread", "ktp-tid-1"), ("topic", "orders")]
+            ),
+            Some(1)
+        );
+        let output = global_metrics().render_prometheus();
+        assert!(output.contains(&format!(
+            "{METRIC_PUBLISH_LATENCY}_count{{pool=\"{pool}\",\
+            thread=\"ktp-tid-1\",topic=\"orders\"}} 1\n"
+        )));
+    }
+
+    #[test]
+    fn set_queue_depth_overwrites_the_gauge() {
+        let pool = "set-queue-depth";
+        set_queue_depth(pool, 12);
+        set_queue_depth(pool, 0);
+        assert_eq!(
+            global_metrics().gauge_value(METRIC_QUEUE_DEPTH, &[("pool", pool)]),
+            Some(0.0)
+        );
+    }
+}
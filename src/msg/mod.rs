@@ -1,5 +1,9 @@
 //! Supported Kafka cluster functions:
 //!
 //! - publishing a message
+//! - publishing a batch of messages concurrently
+//! - publishing a group of messages in a transaction
 //!
 pub mod publish_message;
+pub mod publish_message_batch;
+pub mod publish_transaction;
@@ -1,11 +1,18 @@
 //! Publish a [`KafkaPublishMessage`](crate::api::kafka_publish_message)
 //! to a Kafka topic
 
+use rdkafka::client::ClientContext;
+use rdkafka::message::Header;
 use rdkafka::message::OwnedHeaders;
+use rdkafka::producer::DeliveryFuture;
 use rdkafka::producer::FutureProducer;
 use rdkafka::producer::FutureRecord;
 use std::collections::HashMap;
+use std::time::Instant;
 
+use crate::api::kafka_delivery_error::KafkaDeliveryError;
+use crate::api::kafka_delivery_report::KafkaDeliveryReport;
+use crate::api::kafka_header::KafkaHeader;
 use crate::api::kafka_publish_message::KafkaPublishMessage;
 
 /// now()
@@ -13,12 +20,10 @@ use crate::api::kafka_publish_message::KafkaPublishMessage;
 /// helper for setting a message timestamp
 ///
 fn now() -> i64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_millis()
-        .try_into()
-        .unwrap()
+    match std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+        Ok(since_epoch) => since_epoch.as_millis() as i64,
+        Err(_) => 0,
+    }
 }
 
 /// convert_hashmap_headers_to_ownedheaders
@@ -48,52 +53,302 @@ pub fn convert_hashmap_headers_to_ownedheaders(
     mut owned_headers: OwnedHeaders,
 ) -> OwnedHeaders {
     for (k, v) in hmap.iter() {
-        owned_headers = owned_headers.add(k, &v);
+        owned_headers = owned_headers.insert(Header {
+            key: k,
+            value: Some(v.as_str()),
+        });
     }
     owned_headers
 }
 
-/// publish_message
+/// convert_kafka_headers_to_ownedheaders
 ///
-/// Worker threads publish messages to kafka using this method
+/// Internal method to serialize the ordered
+/// [`KafkaPublishMessage.headers`](crate::api::kafka_publish_message::KafkaPublishMessage)
+/// into an [`rdkafka::message::OwnedHeaders`](rdkafka::message::OwnedHeaders) before
+/// publishing. Header order, repeated keys, binary values and null
+/// values are preserved.
 ///
-/// This function publishes
-/// [`KafkaPublishMessage`](crate::api::kafka_publish_message) where the ``msg_type``
-/// (of type: [`KafkaPublishMessageType`](crate::api::kafka_publish_message_type)) is set to
-/// ``Data`` or ``Sensitive``
+/// # Arguments
+///
+/// * `headers` - ordered list of
+/// [`KafkaHeader`](crate::api::kafka_header::KafkaHeader) to convert
+/// * `owned_headers` - initialized and mutable
+/// [`rdkafka::message::OwnedHeaders`](rdkafka::message::OwnedHeaders)
+/// for storing headers that are compliant with ``rdkafka``
+///
+/// # Returns
+///
+/// [`rdkafka::message::OwnedHeaders`](rdkafka::message::OwnedHeaders) containing
+/// all ``headers`` in order
+///
+pub fn convert_kafka_headers_to_ownedheaders(
+    headers: &[KafkaHeader],
+    mut owned_headers: OwnedHeaders,
+) -> OwnedHeaders {
+    for header in headers.iter() {
+        owned_headers = owned_headers.insert(Header {
+            key: &header.key,
+            value: header.value.as_deref(),
+        });
+    }
+    owned_headers
+}
+
+/// KafkaPendingDelivery
+///
+/// A message enqueued in the producer that is waiting for the
+/// broker acknowledgement
+///
+/// * `topic` - kafka topic the message was published into
+/// * `timestamp` - message timestamp in milliseconds since the
+/// unix epoch
+/// * `delivery_future` - ``rdkafka`` delivery future
+/// * `sent_at` - when the message was enqueued in the producer
+/// for measuring the publish latency
+///
+pub struct KafkaPendingDelivery {
+    pub topic: String,
+    pub timestamp: i64,
+    pub delivery_future: DeliveryFuture,
+    pub sent_at: Instant,
+}
+
+impl KafkaPendingDelivery {
+    /// wait
+    ///
+    /// Wait for the broker acknowledgement
+    ///
+    /// # Returns
+    ///
+    /// ``Result<KafkaDeliveryReport, KafkaDeliveryError>``
+    /// where
+    /// - ``KafkaDeliveryReport`` = partition, offset and timestamp
+    /// acknowledged by the broker
+    /// - ``KafkaDeliveryError`` = reason the message was not stored
+    ///
+    pub async fn wait(self) -> Result<KafkaDeliveryReport, KafkaDeliveryError> {
+        match self.delivery_future.await {
+            Ok(Ok((partition, offset))) => Ok(KafkaDeliveryReport {
+                topic: self.topic,
+                partition,
+                offset,
+                timestamp: Some(self.timestamp),
+            }),
+            Ok(Err((e, _owned_message))) => Err(KafkaDeliveryError::from(&e)),
+            // the producer dropped the message before delivery
+            Err(_canceled) => Err(KafkaDeliveryError::Dropped),
+        }
+    }
+}
+
+/// send_message
+///
+/// Enqueue a
+/// [`KafkaPublishMessage`](crate::api::kafka_publish_message) in the
+/// producer without waiting for the broker acknowledgement. Worker
+/// threads enqueue a whole batch before waiting so many messages
+/// are in flight at once.
+///
+/// The message is published into ``msg.partition`` if set and with
+/// ``msg.timestamp`` if set (otherwise the publish time).
 ///
 /// This uses the
 /// [`FutureProducer.send_result() function`][rdkafka::producer::future_producer::FutureProducer::send_result]
-/// method to publish the message and immediately return without
-/// waiting on the kafka queue.
+/// method to enqueue the message.
 ///
 /// # Arguments
 ///
-/// * `label` - calling thread's logging label
 /// * `producer` - initialized and connected
 /// [`rdkafka::producer::FutureProducer`](rdkafka::producer::FutureProducer)
 /// for publishing messages
 /// * `msg` - initialized
 /// [`KafkaPublishMessage`](crate::api::kafka_publish_message) containing
 /// all routing, metadata and payload information for the message
+/// * `owned_headers` - headers to add to the message
+///
+/// # Returns
 ///
-pub async fn publish_message(
-    producer: &FutureProducer,
+/// ``Result<KafkaPendingDelivery, KafkaDeliveryError>``
+/// where
+/// - ``KafkaPendingDelivery`` = the enqueued message waiting for the
+/// broker acknowledgement
+/// - ``KafkaDeliveryError`` = reason the producer rejected the message
+///
+pub fn send_message<C: ClientContext + 'static>(
+    producer: &FutureProducer<C>,
     msg: &KafkaPublishMessage,
     owned_headers: &OwnedHeaders,
-) -> i32 {
+) -> Result<KafkaPendingDelivery, KafkaDeliveryError> {
+    let timestamp = msg.timestamp.unwrap_or_else(now);
     // https://docs.rs/rdkafka/latest/rdkafka/producer/future_producer/struct.FutureProducer.html#method.send_result
-    let (delivery_status, _id) = producer
-        .send_result(
-            FutureRecord::to(&msg.topic)
-                .payload(&msg.payload)
-                .key(&msg.key)
-                .headers(owned_headers.to_owned())
-                .timestamp(now()),
-        )
-        .unwrap()
-        .await
-        .unwrap()
-        .unwrap();
-    delivery_status
+    let mut record: FutureRecord<[u8], [u8]> = FutureRecord::to(&msg.topic)
+        .headers(owned_headers.to_owned())
+        .timestamp(timestamp);
+    // a missing key is published as null and a missing payload
+    // is published as a tombstone
+    if let Some(partition) = msg.partition {
+        record = record.partition(partition);
+    }
+    if let Some(key) = &msg.key {
+        record = record.key(key.as_slice());
+    }
+    if let Some(payload) = &msg.payload {
+        record = record.payload(payload.as_slice());
+    }
+    match producer.send_result(record) {
+        Ok(delivery_future) => Ok(KafkaPendingDelivery {
+            topic: msg.topic.clone(),
+            timestamp,
+            delivery_future,
+            sent_at: Instant::now(),
+        }),
+        Err((e, _record)) => Err(KafkaDeliveryError::from(&e)),
+    }
+}
+
+/// publish_message
+///
+/// Publish a single message and wait for the broker
+/// acknowledgement
+///
+/// This function publishes
+/// [`KafkaPublishMessage`](crate::api::kafka_publish_message) where the ``msg_type``
+/// (of type: [`KafkaPublishMessageType`](crate::api::kafka_publish_message_type)) is set to
+/// ``Data`` or ``Sensitive`` with
+/// [`send_message`](crate::msg::publish_message::send_message)
+/// and then waits on the returned
+/// [`KafkaPendingDelivery`](crate::msg::publish_message::KafkaPendingDelivery)
+///
+/// # Arguments
+///
+/// * `producer` - initialized and connected
+/// [`rdkafka::producer::FutureProducer`](rdkafka::producer::FutureProducer)
+/// for publishing messages
+/// * `msg` - initialized
+/// [`KafkaPublishMessage`](crate::api::kafka_publish_message) containing
+/// all routing, metadata and payload information for the message
+/// * `owned_headers` - headers to add to the message
+///
+/// # Returns
+///
+/// ``Result<KafkaDeliveryReport, KafkaDeliveryError>``
+/// where
+/// - ``KafkaDeliveryReport`` = partition, offset and timestamp
+/// acknowledged by the broker
+/// - ``KafkaDeliveryError`` = reason the message was not stored
+///
+pub async fn publish_message<C: ClientContext + 'static>(
+    producer: &FutureProducer<C>,
+    msg: &KafkaPublishMessage,
+    owned_headers: &OwnedHeaders,
+) -> Result<KafkaDeliveryReport, KafkaDeliveryError> {
+    send_message(producer, msg, owned_headers)?.wait().await
+}
+
+#[cfg(test)]
+mod tests {
+    use rdkafka::config::ClientConfig;
+    use rdkafka::message::Headers;
+
+    use super::*;
+
+    fn header_pairs(
+        owned_headers: &OwnedHeaders,
+    ) -> Vec<(String, Option<Vec<u8>>)> {
+        owned_headers
+            .iter()
+            .map(|header| {
+                (
+                    header.key.to_string(),
+                    header.value.map(|value| value.to_vec()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn kafka_headers_keep_order_duplicates_and_binary_values() {
+        let headers = vec![
+            KafkaHeader::from_str_value("trace", "first"),
+            KafkaHeader::new("blob", Some(vec![0xff, 0x00, 0x7f])),
+            KafkaHeader::from_str_value("trace", "second"),
+            KafkaHeader::new("deleted", None),
+            KafkaHeader::new("blob", Some(vec![0x01])),
+        ];
+        let owned_headers = convert_kafka_headers_to_ownedheaders(
+            &headers,
+            OwnedHeaders::new(),
+        );
+        assert_eq!(
+            header_pairs(&owned_headers),
+            vec![
+                ("trace".to_string(), Some(b"first".to_vec())),
+                ("blob".to_string(), Some(vec![0xff, 0x00, 0x7f])),
+                ("trace".to_string(), Some(b"second".to_vec())),
+                ("deleted".to_string(), None),
+                ("blob".to_string(), Some(vec![0x01])),
+            ]
+        );
+    }
+
+    #[test]
+    fn kafka_headers_are_appended_to_existing_headers() {
+        let existing = OwnedHeaders::new().insert(Header {
+            key: "app",
+            value: Some("billing"),
+        });
+        let owned_headers = convert_kafka_headers_to_ownedheaders(
+            &[KafkaHeader::from_str_value("app", "override")],
+            existing,
+        );
+        assert_eq!(owned_headers.count(), 2);
+        assert_eq!(
+            header_pairs(&owned_headers)[1],
+            ("app".to_string(), Some(b"override".to_vec()))
+        );
+    }
+
+    #[test]
+    fn empty_kafka_headers_add_nothing() {
+        let owned_headers =
+            convert_kafka_headers_to_ownedheaders(&[], OwnedHeaders::new());
+        assert_eq!(owned_headers.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn tombstones_are_sent_with_a_null_payload() {
+        let tombstone =
+            KafkaPublishMessage::new_tombstone("orders", b"order-1");
+        assert_eq!(tombstone.key.as_deref(), Some(b"order-1".as_slice()));
+        assert_eq!(tombstone.payload, None);
+        // nothing listens on the broker port so librdkafka accepts the
+        // tombstone and then times it out
+        let producer: FutureProducer = ClientConfig::new()
+            .set("bootstrap.servers", "127.0.0.1:1")
+            .set("message.timeout.ms", "100")
+            .create()
+            .unwrap();
+        let pending =
+            send_message(&producer, &tombstone, &OwnedHeaders::new()).unwrap();
+        assert_eq!(pending.topic, "orders");
+        assert!(matches!(
+            pending.wait().await,
+            Err(KafkaDeliveryError::Broker { .. })
+        ));
+    }
+
+    #[test]
+    fn send_message_uses_the_message_timestamp() {
+        let producer: FutureProducer = ClientConfig::new()
+            .set("bootstrap.servers", "127.0.0.1:1")
+            .set("message.timeout.ms", "100")
+            .create()
+            .unwrap();
+        let mut msg = KafkaPublishMessage::new_tombstone("orders", b"order-1");
+        msg.timestamp = Some(1700000000000);
+        let pending =
+            send_message(&producer, &msg, &OwnedHeaders::new()).unwrap();
+        assert_eq!(pending.timestamp, 1700000000000);
+    }
 }
@@ -0,0 +1,281 @@
+//! Publish a batch of
+//! [`KafkaPublishMessage`](crate::api::kafka_publish_message)
+//! messages concurrently with the
+//! [`KafkaRetryPolicy`](crate::config::kafka_retry_policy::KafkaRetryPolicy)
+//!
+use std::time::Duration;
+use std::time::Instant;
+
+use log::error;
+use log::trace;
+use log::warn;
+
+use rdkafka::client::ClientContext;
+use rdkafka::message::OwnedHeaders;
+use rdkafka::producer::FutureProducer;
+
+use crate::api::kafka_delivery_error::KafkaDeliveryError;
+use crate::api::kafka_delivery_notifier::KafkaDeliveryNotifier;
+use crate::api::kafka_delivery_notifier::KafkaDeliveryResult;
+use crate::api::kafka_publish_message::KafkaPublishMessage;
+use crate::api::kafka_publish_message_type::KafkaPublishMessageType;
+use crate::config::kafka_client_config::KafkaClientConfig;
+use crate::dlq::kafka_dead_letter::KafkaDeadLetter;
+use crate::dlq::send_to_dead_letter_sinks::send_to_dead_letter_sinks;
+use crate::health::kafka_worker_state::KafkaWorkerState;
+#[cfg(feature = "metrics")]
+use crate::metrics::record_metrics::record_abandoned;
+#[cfg(feature = "metrics")]
+use crate::metrics::record_metrics::record_failed;
+#[cfg(feature = "metrics")]
+use crate::metrics::record_metrics::record_published;
+#[cfg(feature = "metrics")]
+use crate::metrics::record_metrics::record_retried;
+use crate::msg::publish_message::convert_kafka_headers_to_ownedheaders;
+use crate::msg::publish_message::send_message;
+use crate::pool::kafka_pool_state::KafkaPoolState;
+#[cfg(feature = "tracing")]
+use crate::trace::publish_span::record_publish_result;
+#[cfg(feature = "tracing")]
+use crate::trace::publish_span::start_publish_span;
+#[cfg(feature = "tracing")]
+use crate::trace::trace_context::inject_trace_headers;
+
+/// KafkaBatchEntry
+///
+/// Internal retry state for one message in the batch
+///
+struct KafkaBatchEntry {
+    idx: usize,
+    delivery: Option<KafkaDeliveryNotifier>,
+    msg: KafkaPublishMessage,
+    owned_headers: OwnedHeaders,
+    attempt: u32,
+    started: Instant,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+/// publish_message_batch
+///
+/// Worker threads publish a drained batch of ``Data`` and
+/// ``Sensitive`` messages with this method. Every message in the
+/// batch is enqueued in the producer before waiting on any delivery
+/// future so one worker thread can keep many messages in flight.
+/// Failed messages are retried together after the longest backoff
+/// from the
+/// [`KafkaRetryPolicy`](crate::config::kafka_retry_policy::KafkaRetryPolicy)
+/// (or abandoned without another attempt if the shutdown deadline
+/// expires during the backoff), and messages that fail with a fatal error or exhaust the retry
+/// policy go to the dead-letter sinks.
+///
+/// Each message's delivery notifier (if set) receives the
+/// outcome for that message.
+///
+/// With the ``tracing`` feature each message gets a
+/// ``kafka.publish`` span for all of its attempts and the span
+/// context is published in the W3C ``traceparent`` header.
+///
+/// # Arguments
+///
+/// * `log_label` - worker thread log label
+/// * `producer` - the worker thread's producer
+/// * `config` - initialized [`KafkaClientConfig`]
+/// * `pool_state` - shared [`KafkaPoolState`] for counting outcomes,
+/// tracking the worker's health and checking for an expired
+/// shutdown deadline
+/// * `msgs` - batch of messages to publish
+///
+/// # Returns
+///
+/// ``Vec<KafkaDeliveryResult>`` with the outcome for each message
+/// in the same order as ``msgs``
+///
+pub async fn publish_message_batch<C: ClientContext + 'static>(
+    log_label: &str,
+    producer: &FutureProducer<C>,
+    config: &KafkaClientConfig,
+    pool_state: &KafkaPoolState,
+    msgs: Vec<KafkaPublishMessage>,
+) -> Vec<KafkaDeliveryResult> {
+    let mut results: Vec<Option<KafkaDeliveryResult>> =
+        msgs.iter().map(|_| None).collect();
+    let mut pending: Vec<KafkaBatchEntry> = msgs
+        .into_iter()
+        .enumerate()
+        .map(|(idx, mut msg)| {
+            if msg.msg_type == KafkaPublishMessageType::Sensitive {
+                trace!("{log_label} pub topic={} data=REDACTED", msg.topic);
+            } else {
+                trace!(
+                    "{log_label} pub \
+                    topic={} data='{}'",
+                    msg.topic,
+                    msg.payload_preview(10)
+                );
+            }
+            #[cfg(feature = "tracing")]
+            let span = start_publish_span(log_label, &msg);
+            #[cfg(feature = "tracing")]
+            inject_trace_headers(&span, &mut msg.headers);
+            let owned_headers = convert_kafka_headers_to_ownedheaders(
+                &msg.headers,
+                OwnedHeaders::new(),
+            );
+            KafkaBatchEntry {
+                idx,
+                delivery: msg.delivery.take(),
+                msg,
+                owned_headers,
+                attempt: 0,
+                started: Instant::now(),
+                #[cfg(feature = "tracing")]
+                span,
+            }
+        })
+        .collect();
+    while !pending.is_empty() {
+        // enqueue the whole batch before waiting on any delivery
+        let in_flight: Vec<_> = pending
+            .into_iter()
+            .map(|mut entry| {
+                entry.attempt += 1;
+                #[cfg(feature = "tracing")]
+                entry.span.record("attempt", entry.attempt);
+                let sent =
+                    send_message(producer, &entry.msg, &entry.owned_headers);
+                (entry, sent)
+            })
+            .collect();
+        let mut retries: Vec<KafkaBatchEntry> = Vec::new();
+        let mut retry_delay = Duration::ZERO;
+        for (entry, sent) in in_flight {
+            let (res, latency) = match sent {
+                Ok(pending_delivery) => {
+                    let sent_at = pending_delivery.sent_at;
+                    (pending_delivery.wait().await, sent_at.elapsed())
+                }
+                Err(e) => (Err(e), Duration::ZERO),
+            };
+            let topic = entry.msg.topic.clone();
+            let attempt = entry.attempt;
+            let outcome = match res {
+                Ok(delivery_report) => {
+                    trace!(
+                        "published message topic={topic} \
+                        partition={} offset={} latency={latency:?}",
+                        delivery_report.partition,
+                        delivery_report.offset
+                    );
+                    pool_state.record_delivered();
+                    pool_state.health.record_delivery(log_label);
+                    #[cfg(feature = "metrics")]
+                    record_published(&config.label, log_label, &topic, latency);
+                    Some(Ok(delivery_report))
+                }
+                Err(e) if pool_state.is_abandoning() => {
+                    // the shutdown deadline expired
+                    error!(
+                        "{log_label} - abandoning msg after \
+                        shutdown deadline with err={e}"
+                    );
+                    pool_state.record_abandoned();
+                    #[cfg(feature = "metrics")]
+                    record_abandoned(&config.label, log_label, 1);
+                    Some(Err(e))
+                }
+                Err(e) => match config.retry_policy.next_retry(
+                    attempt,
+                    &e,
+                    entry.started.elapsed(),
+                ) {
+                    Some(delay) => {
+                        warn!(
+                            "{log_label} - failed to publish \
+                            attempt={attempt} with err={e} \
+                            retrying in {delay:?} topic={topic}"
+                        );
+                        retry_delay = retry_delay.max(delay);
+                        #[cfg(feature = "metrics")]
+                        record_retried(&config.label, log_label, &topic);
+                        None
+                    }
+                    None => {
+                        error!(
+                            "{log_label} - giving up on msg \
+                            topic={topic} after attempts={attempt} \
+                            elapsed={:?} retriable={} with err={e}",
+                            entry.started.elapsed(),
+                            e.is_retriable()
+                        );
+                        pool_state.record_failed();
+                        #[cfg(feature = "metrics")]
+                        record_failed(&config.label, log_label, &topic);
+                        let dead_letter = KafkaDeadLetter::new(
+                            entry.msg.clone(),
+                            e.clone(),
+                            attempt,
+                        );
+                        send_to_dead_letter_sinks(
+                            log_label,
+                            producer,
+                            config,
+                            &dead_letter,
+                        )
+                        .await;
+                        Some(Err(e))
+                    }
+                },
+            };
+            match outcome {
+                Some(outcome) => {
+                    #[cfg(feature = "tracing")]
+                    record_publish_result(&entry.span, &outcome);
+                    // acknowledge the caller as soon as the message is done
+                    if let Some(delivery) = &entry.delivery {
+                        delivery.notify(outcome.clone());
+                    }
+                    results[entry.idx] = Some(outcome);
+                }
+                None => retries.push(entry),
+            }
+        }
+        if !retries.is_empty() {
+            pool_state
+                .health
+                .set_state(log_label, KafkaWorkerState::Retrying);
+            tokio::select! {
+                _ = tokio::time::sleep(retry_delay) => {}
+                _ = pool_state.wait_until_abandoning() => {}
+            }
+            pool_state
+                .health
+                .set_state(log_label, KafkaWorkerState::Running);
+        }
+        if pool_state.is_abandoning() {
+            // the shutdown deadline expired while waiting to retry
+            for entry in retries.drain(..) {
+                error!(
+                    "{log_label} - abandoning msg topic={} after \
+                    shutdown deadline attempts={}",
+                    entry.msg.topic, entry.attempt
+                );
+                pool_state.record_abandoned();
+                #[cfg(feature = "metrics")]
+                record_abandoned(&config.label, log_label, 1);
+                let outcome = Err(KafkaDeliveryError::Dropped);
+                #[cfg(feature = "tracing")]
+                record_publish_result(&entry.span, &outcome);
+                if let Some(delivery) = &entry.delivery {
+                    delivery.notify(outcome.clone());
+                }
+                results[entry.idx] = Some(outcome);
+            }
+        }
+        pending = retries;
+    }
+    results
+        .into_iter()
+        .map(|res| res.unwrap_or(Err(KafkaDeliveryError::Dropped)))
+        .collect()
+}
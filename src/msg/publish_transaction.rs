@@ -0,0 +1,376 @@
+//! Publish a
+//! [`KafkaTransaction`](crate::api::kafka_transaction::KafkaTransaction)
+//! so every message is committed atomically or the whole group
+//! is aborted
+//!
+use std::time::Duration;
+use std::time::Instant;
+
+use log::error;
+use log::trace;
+
+use rdkafka::client::ClientContext;
+use rdkafka::error::KafkaError;
+use rdkafka::message::OwnedHeaders;
+use rdkafka::producer::FutureProducer;
+use rdkafka::producer::Producer;
+use rdkafka::types::RDKafkaErrorCode;
+use rdkafka::util::Timeout;
+
+use crate::api::kafka_delivery_error::KafkaDeliveryError;
+use crate::api::kafka_delivery_report::KafkaDeliveryReport;
+use crate::api::kafka_publish_message_type::KafkaPublishMessageType;
+use crate::api::kafka_transaction::KafkaTransaction;
+use crate::api::kafka_transaction_notifier::KafkaTransactionResult;
+use crate::config::kafka_client_config::KafkaClientConfig;
+#[cfg(feature = "metrics")]
+use crate::metrics::record_metrics::record_failed;
+#[cfg(feature = "metrics")]
+use crate::metrics::record_metrics::record_published;
+use crate::msg::publish_message::convert_kafka_headers_to_ownedheaders;
+use crate::msg::publish_message::send_message;
+use crate::pool::kafka_pool_state::KafkaPoolState;
+#[cfg(feature = "tracing")]
+use crate::trace::publish_span::record_publish_result;
+#[cfg(feature = "tracing")]
+use crate::trace::publish_span::start_publish_span;
+#[cfg(feature = "tracing")]
+use crate::trace::trace_context::inject_trace_headers;
+
+/// KAFKA_TRANSACTION_TIMEOUT
+///
+/// Max time to wait for the transaction coordinator when
+/// initializing, committing or aborting a transaction
+///
+pub const KAFKA_TRANSACTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// is_fatal_transaction_error
+///
+/// Check if a transaction error left the transactional producer
+/// unusable: the producer was fenced, ``librdkafka`` raised a fatal
+/// error or the error can neither be retried nor recovered with
+/// ``abort_transaction``
+///
+/// # Arguments
+///
+/// * `err` - error from ``begin_transaction``,
+/// ``commit_transaction`` or ``abort_transaction``
+///
+/// # Returns
+///
+/// ``true`` if the worker thread must recreate its transactional
+/// producer
+///
+pub fn is_fatal_transaction_error(err: &KafkaError) -> bool {
+    if let KafkaError::Transaction(txn_err) = err {
+        if txn_err.is_fatal()
+            || (!txn_err.is_retriable() && !txn_err.txn_requires_abort())
+        {
+            return true;
+        }
+    }
+    matches!(
+        err.rdkafka_error_code(),
+        Some(
+            RDKafkaErrorCode::ProducerFenced
+                | RDKafkaErrorCode::InvalidProducerEpoch
+                | RDKafkaErrorCode::Fatal
+        )
+    )
+}
+
+/// end_transaction
+///
+/// Internal helper for committing or aborting the producer's
+/// open transaction without blocking the executor. Stops waiting
+/// once the shutdown deadline expires.
+///
+/// # Arguments
+///
+/// * `producer` - transactional producer with an open transaction
+/// * `pool_state` - shared [`KafkaPoolState`] for the shutdown
+/// deadline
+/// * `commit` - ``true`` to commit and ``false`` to abort
+///
+/// # Errors
+///
+/// The [`KafkaDeliveryError`] and ``true`` if the error is
+/// fatal for the producer (see [`is_fatal_transaction_error`])
+///
+async fn end_transaction<C: ClientContext + 'static>(
+    producer: &FutureProducer<C>,
+    pool_state: &KafkaPoolState,
+    commit: bool,
+) -> Result<(), (KafkaDeliveryError, bool)> {
+    if pool_state.is_abandoning() {
+        return Err((KafkaDeliveryError::Dropped, false));
+    }
+    let txn_producer = producer.clone();
+    let ended = tokio::task::spawn_blocking(move || {
+        if commit {
+            txn_producer
+                .commit_transaction(Timeout::After(KAFKA_TRANSACTION_TIMEOUT))
+        } else {
+            txn_producer
+                .abort_transaction(Timeout::After(KAFKA_TRANSACTION_TIMEOUT))
+        }
+    });
+    tokio::select! {
+        res = ended => match res {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => Err((
+                KafkaDeliveryError::from(&e),
+                is_fatal_transaction_error(&e),
+            )),
+            Err(_) => Err((KafkaDeliveryError::Dropped, false)),
+        },
+        _ = pool_state.wait_until_abandoning() => {
+            Err((KafkaDeliveryError::Dropped, false))
+        }
+    }
+}
+
+/// send_transaction_msgs
+///
+/// Internal helper for publishing every message in the open
+/// transaction and waiting for all broker acknowledgements
+///
+/// # Arguments
+///
+/// * `log_label` - worker thread log label
+/// * `producer` - transactional producer with an open transaction
+/// * `transaction` - messages to publish
+///
+async fn send_transaction_msgs<C: ClientContext + 'static>(
+    log_label: &str,
+    producer: &FutureProducer<C>,
+    transaction: &KafkaTransaction,
+) -> KafkaTransactionResult {
+    // enqueue every message before waiting on any delivery
+    let mut in_flight = Vec::with_capacity(transaction.msgs.len());
+    #[cfg(feature = "tracing")]
+    let mut spans = Vec::with_capacity(transaction.msgs.len());
+    for msg in transaction.msgs.iter() {
+        if msg.msg_type == KafkaPublishMessageType::Sensitive {
+            trace!("{log_label} txn pub topic={} data=REDACTED", msg.topic);
+        } else {
+            trace!(
+                "{log_label} txn pub \
+                topic={} data='{}'",
+                msg.topic,
+                msg.payload_preview(10)
+            );
+        }
+        #[cfg(feature = "tracing")]
+        let span = start_publish_span(log_label, msg);
+        #[cfg(feature = "tracing")]
+        let traced_headers = {
+            span.record("attempt", 1u32);
+            let mut headers = msg.headers.clone();
+            inject_trace_headers(&span, &mut headers);
+            headers
+        };
+        #[cfg(feature = "tracing")]
+        let headers = &traced_headers;
+        #[cfg(not(feature = "tracing"))]
+        let headers = &msg.headers;
+        let owned_headers =
+            convert_kafka_headers_to_ownedheaders(headers, OwnedHeaders::new());
+        in_flight.push(send_message(producer, msg, &owned_headers)?);
+        #[cfg(feature = "tracing")]
+        spans.push(span);
+    }
+    let mut reports: Vec<KafkaDeliveryReport> =
+        Vec::with_capacity(in_flight.len());
+    #[cfg(feature = "tracing")]
+    let mut spans = spans.into_iter();
+    for pending_delivery in in_flight {
+        let res = pending_delivery.wait().await;
+        #[cfg(feature = "tracing")]
+        if let Some(span) = spans.next() {
+            record_publish_result(&span, &res);
+        }
+        reports.push(res?);
+    }
+    Ok(reports)
+}
+
+/// publish_transaction
+///
+/// Worker threads publish a ``Transaction`` message with this
+/// method using the thread's transactional producer:
+///
+/// 1. ``begin_transaction``
+/// 2. publish every message and wait for the broker
+/// acknowledgements
+/// 3. ``commit_transaction`` if every message was acknowledged
+/// otherwise ``abort_transaction``
+///
+/// Transactions are not retried and do not use the dead-letter
+/// sinks. The caller receives the outcome with the transaction
+/// notifier (if set) and can resubmit an aborted transaction.
+/// Each message's delivery notifier (if set) receives the outcome
+/// for that message.
+///
+/// # Arguments
+///
+/// * `log_label` - worker thread log label
+/// * `producer` - the worker thread's transactional producer
+/// created with
+/// [`get_kafka_transactional_producer`](crate::api::get_kafka_transactional_producer::get_kafka_transactional_producer)
+/// * `config` - initialized [`KafkaClientConfig`]
+/// * `pool_state` - shared [`KafkaPoolState`] for counting outcomes
+/// * `transaction` - messages to commit atomically
+///
+/// # Returns
+///
+/// A tuple with:
+///
+/// - ``KafkaTransactionResult`` with one delivery report per message
+/// (in order) for a committed transaction or the error that
+/// aborted the transaction
+/// - ``true`` if the producer hit a fatal error (see
+/// [`is_fatal_transaction_error`]) or failed to abort the
+/// transaction, so the worker thread must recreate the producer
+/// before the next transaction
+///
+pub async fn publish_transaction<C: ClientContext + 'static>(
+    log_label: &str,
+    producer: &FutureProducer<C>,
+    config: &KafkaClientConfig,
+    pool_state: &KafkaPoolState,
+    transaction: KafkaTransaction,
+) -> (KafkaTransactionResult, bool) {
+    let num_msgs = transaction.msgs.len();
+    let started = Instant::now();
+    let mut fatal = false;
+    let result = match producer.begin_transaction() {
+        Ok(()) => {
+            let sent =
+                send_transaction_msgs(log_label, producer, &transaction).await;
+            match sent {
+                Ok(reports) => {
+                    match end_transaction(producer, pool_state, true).await {
+                        Ok(()) => Ok(reports),
+                        Err((e, commit_fatal)) => {
+                            error!(
+                                "{log_label} - failed to commit transaction \
+                            msgs={num_msgs} with err={e} - aborting"
+                            );
+                            fatal = commit_fatal;
+                            Err(e)
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(
+                        "{log_label} - failed to publish transaction \
+                        msgs={num_msgs} with err={e} - aborting"
+                    );
+                    Err(e)
+                }
+            }
+        }
+        Err(e) => {
+            error!(
+                "{log_label} - failed to begin transaction \
+                msgs={num_msgs} with err={e}"
+            );
+            let result = notify_transaction(
+                log_label,
+                config,
+                pool_state,
+                transaction,
+                Err(KafkaDeliveryError::from(&e)),
+                started,
+            );
+            return (result, is_fatal_transaction_error(&e));
+        }
+    };
+    // a fatal error also fails the abort so skip it
+    if result.is_err() && !fatal {
+        match end_transaction(producer, pool_state, false).await {
+            Ok(()) => {}
+            // the shutdown deadline expired
+            Err((KafkaDeliveryError::Dropped, _)) => {}
+            Err((e, _)) => {
+                // the producer still has an open transaction
+                error!(
+                    "{log_label} - failed to abort transaction \
+                    msgs={num_msgs} with err={e}"
+                );
+                fatal = true;
+            }
+        }
+    } else if result.is_ok() {
+        trace!(
+            "{log_label} - committed transaction msgs={num_msgs} \
+            latency={:?}",
+            started.elapsed()
+        );
+    }
+    let result = notify_transaction(
+        log_label,
+        config,
+        pool_state,
+        transaction,
+        result,
+        started,
+    );
+    (result, fatal)
+}
+
+/// notify_transaction
+///
+/// Internal helper for counting the outcome and notifying the
+/// caller and each message's delivery notifier
+///
+/// # Arguments
+///
+/// * `log_label` - worker thread log label
+/// * `config` - initialized [`KafkaClientConfig`]
+/// * `pool_state` - shared [`KafkaPoolState`] for counting outcomes
+/// * `transaction` - the published or aborted transaction
+/// * `result` - transaction outcome
+/// * `started` - when the transaction began for measuring the
+/// publish latency
+///
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+fn notify_transaction(
+    log_label: &str,
+    config: &KafkaClientConfig,
+    pool_state: &KafkaPoolState,
+    transaction: KafkaTransaction,
+    result: KafkaTransactionResult,
+    started: Instant,
+) -> KafkaTransactionResult {
+    for (idx, msg) in transaction.msgs.iter().enumerate() {
+        let msg_result = match &result {
+            Ok(reports) => {
+                pool_state.record_delivered();
+                pool_state.health.record_delivery(log_label);
+                #[cfg(feature = "metrics")]
+                record_published(
+                    &config.label,
+                    log_label,
+                    &msg.topic,
+                    started.elapsed(),
+                );
+                Ok(reports[idx].clone())
+            }
+            Err(e) => {
+                pool_state.record_failed();
+                #[cfg(feature = "metrics")]
+                record_failed(&config.label, log_label, &msg.topic);
+                Err(e.clone())
+            }
+        };
+        if let Some(delivery) = &msg.delivery {
+            delivery.notify(msg_result);
+        }
+    }
+    if let Some(notifier) = &transaction.notifier {
+        notifier.notify(result.clone());
+    }
+    result
+}
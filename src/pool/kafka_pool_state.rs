@@ -0,0 +1,264 @@
+//! Shared state between the
+//! [`KafkaPublisher`](crate::kafka_publisher::KafkaPublisher)
+//! and the worker threads
+//!
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+use crate::health::kafka_health_tracker::KafkaHealthTracker;
+use crate::stats::kafka_stats_store::KafkaStatsStore;
+
+/// KAFKA_ABANDON_TIMEOUT
+///
+/// Max time to wait for the worker threads to exit after the
+/// shutdown deadline expires before aborting them
+///
+pub const KAFKA_ABANDON_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// KAFKA_FLUSH_TIMEOUT
+///
+/// Max time a worker thread waits to flush its producers before
+/// exiting when there is no shutdown deadline (a
+/// [`shutdown`](crate::kafka_publisher::KafkaPublisher::shutdown)
+/// without waiting or a worker thread stopping on its own)
+///
+pub const KAFKA_FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// KafkaPoolState
+///
+/// Thread-safe signals shared by the
+/// [`KafkaPublisher`](crate::kafka_publisher::KafkaPublisher)
+/// and every worker thread in the pool
+///
+/// * `work_available` - the publisher notifies this after
+/// adding messages so idle worker threads wake up immediately
+/// * `space_available` - worker threads notify this after
+/// draining messages so callers blocked on a full work vec
+/// can retry
+/// * `shutting_down` - set once a shutdown starts so the
+/// publisher stops accepting messages and worker threads exit
+/// after the work vec is empty
+/// * `abandoning` - set once the shutdown deadline expires so
+/// worker threads stop retrying and drop any remaining messages
+/// * `abandon` - notified once the shutdown deadline expires so
+/// worker threads waiting to retry or for a transaction wake up
+/// * `shutdown_deadline` - optional - when the shutdown deadline
+/// from
+/// [`shutdown_and_wait`](crate::kafka_publisher::KafkaPublisher::shutdown_and_wait)
+/// expires so the worker threads never flush past it
+/// * `num_delivered` - number of messages kafka acknowledged
+/// * `num_failed` - number of messages that failed with a fatal
+/// error or exhausted the retry policy
+/// * `num_abandoned` - number of messages dropped during shutdown
+/// * `stats` - latest ``librdkafka`` statistics for each worker
+/// thread's producer (only populated when
+/// ``config.statistics_interval_ms`` is positive)
+/// * `health` - state, broker connectivity and last delivery for
+/// each worker thread
+/// * `workers` - ``JoinHandle`` for each worker thread
+///
+#[derive(Default)]
+pub struct KafkaPoolState {
+    pub work_available: Notify,
+    pub space_available: Notify,
+    pub shutting_down: AtomicBool,
+    pub abandoning: AtomicBool,
+    pub abandon: Notify,
+    pub shutdown_deadline: Mutex<Option<Instant>>,
+    pub num_delivered: AtomicUsize,
+    pub num_failed: AtomicUsize,
+    pub num_abandoned: AtomicUsize,
+    pub stats: Arc<KafkaStatsStore>,
+    pub health: Arc<KafkaHealthTracker>,
+    pub workers: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl KafkaPoolState {
+    /// new
+    ///
+    /// Create the shared state for a new threadpool
+    ///
+    pub fn new() -> Self {
+        KafkaPoolState {
+            work_available: Notify::new(),
+            space_available: Notify::new(),
+            shutting_down: AtomicBool::new(false),
+            abandoning: AtomicBool::new(false),
+            abandon: Notify::new(),
+            shutdown_deadline: Mutex::new(None),
+            num_delivered: AtomicUsize::new(0),
+            num_failed: AtomicUsize::new(0),
+            num_abandoned: AtomicUsize::new(0),
+            stats: Arc::new(KafkaStatsStore::new()),
+            health: Arc::new(KafkaHealthTracker::new()),
+            workers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// is_shutting_down
+    ///
+    /// # Returns
+    ///
+    /// ``true`` once a shutdown has started
+    ///
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::SeqCst)
+    }
+
+    /// is_abandoning
+    ///
+    /// # Returns
+    ///
+    /// ``true`` once the shutdown deadline expired
+    ///
+    pub fn is_abandoning(&self) -> bool {
+        self.abandoning.load(Ordering::SeqCst)
+    }
+
+    /// start_shutdown
+    ///
+    /// Stop accepting new messages and wake up every idle
+    /// worker thread and blocked caller
+    ///
+    pub fn start_shutdown(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+        self.work_available.notify_waiters();
+        self.space_available.notify_waiters();
+    }
+
+    /// set_shutdown_deadline
+    ///
+    /// Record when the shutdown deadline expires
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - maximum time from now for the worker threads
+    /// to publish the queued messages
+    ///
+    pub fn set_shutdown_deadline(&self, deadline: Duration) {
+        if let Ok(mut shutdown_deadline) = self.shutdown_deadline.lock() {
+            *shutdown_deadline = Instant::now().checked_add(deadline);
+        }
+    }
+
+    /// flush_timeout
+    ///
+    /// # Returns
+    ///
+    /// max time a worker thread may wait to flush its producers:
+    /// ``0`` once the shutdown deadline expired, the time left
+    /// until the shutdown deadline or [`KAFKA_FLUSH_TIMEOUT`]
+    /// without a shutdown deadline
+    ///
+    pub fn flush_timeout(&self) -> Duration {
+        if self.is_abandoning() {
+            return Duration::ZERO;
+        }
+        match self.shutdown_deadline.lock() {
+            Ok(shutdown_deadline) => match *shutdown_deadline {
+                Some(deadline) => {
+                    deadline.saturating_duration_since(Instant::now())
+                }
+                None => KAFKA_FLUSH_TIMEOUT,
+            },
+            Err(_) => KAFKA_FLUSH_TIMEOUT,
+        }
+    }
+
+    /// start_abandoning
+    ///
+    /// Stop retrying, drop any remaining messages and wake every
+    /// idle or waiting worker thread once the shutdown deadline
+    /// expires
+    ///
+    pub fn start_abandoning(&self) {
+        self.abandoning.store(true, Ordering::SeqCst);
+        self.abandon.notify_waiters();
+        self.work_available.notify_waiters();
+    }
+
+    /// wait_until_abandoning
+    ///
+    /// Wait until the shutdown deadline expires (returns
+    /// immediately if it already expired)
+    ///
+    pub async fn wait_until_abandoning(&self) {
+        // register for the wakeup before checking the flag so a
+        // concurrent start_abandoning is not missed
+        let abandon = self.abandon.notified();
+        if self.is_abandoning() {
+            return;
+        }
+        abandon.await;
+    }
+
+    /// record_delivered
+    ///
+    /// Count a message that kafka acknowledged
+    ///
+    pub fn record_delivered(&self) {
+        self.num_delivered.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// record_failed
+    ///
+    /// Count a message that failed with a fatal error or
+    /// exhausted the retry policy
+    ///
+    pub fn record_failed(&self) {
+        self.num_failed.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// record_abandoned
+    ///
+    /// Count a message dropped during shutdown
+    ///
+    pub fn record_abandoned(&self) {
+        self.num_abandoned.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flush_timeout_without_a_deadline() {
+        let pool_state = KafkaPoolState::new();
+        assert_eq!(pool_state.flush_timeout(), KAFKA_FLUSH_TIMEOUT);
+        pool_state.start_shutdown();
+        assert_eq!(pool_state.flush_timeout(), KAFKA_FLUSH_TIMEOUT);
+    }
+
+    #[test]
+    fn flush_timeout_is_the_time_left_until_the_deadline() {
+        let pool_state = KafkaPoolState::new();
+        pool_state.set_shutdown_deadline(Duration::from_secs(30));
+        let flush_timeout = pool_state.flush_timeout();
+        assert!(flush_timeout <= Duration::from_secs(30));
+        assert!(flush_timeout > Duration::from_secs(29));
+
+        // a deadline shorter than the default is never exceeded
+        pool_state.set_shutdown_deadline(Duration::from_millis(200));
+        assert!(pool_state.flush_timeout() <= Duration::from_millis(200));
+
+        pool_state.set_shutdown_deadline(Duration::ZERO);
+        assert_eq!(pool_state.flush_timeout(), Duration::ZERO);
+    }
+
+    #[test]
+    fn flush_timeout_is_zero_once_abandoning() {
+        let pool_state = KafkaPoolState::new();
+        pool_state.set_shutdown_deadline(Duration::from_secs(30));
+        pool_state.start_abandoning();
+        assert_eq!(pool_state.flush_timeout(), Duration::ZERO);
+    }
+}
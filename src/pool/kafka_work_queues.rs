@@ -0,0 +1,676 @@
+//! Lockable work vecs shared by the
+//! [`KafkaPublisher`](crate::kafka_publisher::KafkaPublisher)
+//! and the worker threads
+//!
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use log::error;
+
+use crate::api::add_messages_to_bounded_work_vec::add_messages_to_locked_bounded_work_vec;
+use crate::api::add_messages_to_bounded_work_vec::check_work_vec_capacity;
+use crate::api::add_messages_to_bounded_work_vec::has_space_in_work_vec;
+use crate::api::drain_messages_from_locked_work_vec::drain_messages_from_locked_work_vec;
+use crate::api::get_message_shard::get_message_shard;
+use crate::api::kafka_ordering_mode::KafkaOrderingMode;
+use crate::api::kafka_publish_message::KafkaPublishMessage;
+use crate::api::kafka_queue_add_status::KafkaQueueAddStatus;
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
+use crate::config::kafka_client_config::KafkaClientConfig;
+
+/// KafkaWorkQueues
+///
+/// The lockable work vecs the worker threads drain
+///
+/// With ``KafkaOrderingMode::Unordered`` (or one worker thread)
+/// every worker thread drains one shared work vec. The ``Key`` and
+/// ``TopicPartition`` ordering modes give each worker thread its
+/// own work vec and the publisher adds each message to the work vec
+/// of its shard (see
+/// [`get_message_shard`](crate::api::get_message_shard::get_message_shard))
+/// so a worker thread only locks and drains its own messages.
+/// Messages without an ordering requirement are spread across the
+/// work vecs.
+///
+/// Once a worker thread exits, the next running worker thread by
+/// thread number also drains the exited thread's work vec so its
+/// messages are published in order instead of being stranded.
+///
+/// * `queues` - one lockable work vec per shard
+/// * `exited` - set once each worker thread exits
+/// * `next_queue` - round robin counter for messages without an
+/// ordering requirement
+/// * `ordering_mode` - configured [`KafkaOrderingMode`]
+/// * `queue_config` - the config with the capacity of each work
+/// vec (``config.max_queue_msgs`` and ``config.max_queue_bytes``
+/// are split evenly across the work vecs)
+///
+pub struct KafkaWorkQueues {
+    pub queues: Vec<Arc<Mutex<Vec<KafkaPublishMessage>>>>,
+    pub exited: Vec<AtomicBool>,
+    pub next_queue: AtomicUsize,
+    pub ordering_mode: KafkaOrderingMode,
+    pub queue_config: KafkaClientConfig,
+}
+
+impl Default for KafkaWorkQueues {
+    fn default() -> Self {
+        Self::new(&KafkaClientConfig::default())
+    }
+}
+
+impl KafkaWorkQueues {
+    /// new
+    ///
+    /// Create the work vecs for a new threadpool
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - initialized [`KafkaClientConfig`] with the
+    /// number of worker threads, ordering mode and capacity
+    ///
+    pub fn new(config: &KafkaClientConfig) -> Self {
+        let num_threads = config.num_threads.max(1) as usize;
+        let num_queues = if config.ordering_mode == KafkaOrderingMode::Unordered
+        {
+            1
+        } else {
+            num_threads
+        };
+        let mut queue_config = config.clone();
+        queue_config.max_queue_msgs =
+            config.max_queue_msgs.div_ceil(num_queues);
+        queue_config.max_queue_bytes =
+            config.max_queue_bytes.div_ceil(num_queues);
+        KafkaWorkQueues {
+            queues: (0..num_queues)
+                .map(|_| Arc::new(Mutex::new(Vec::new())))
+                .collect(),
+            exited: (0..num_threads).map(|_| AtomicBool::new(false)).collect(),
+            next_queue: AtomicUsize::new(0),
+            ordering_mode: config.ordering_mode,
+            queue_config,
+        }
+    }
+
+    /// get_queue_num
+    ///
+    /// # Returns
+    ///
+    /// The index of the work vec for the message
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - the [`KafkaPublishMessage`] to add
+    ///
+    pub fn get_queue_num(&self, msg: &KafkaPublishMessage) -> usize {
+        let num_queues = self.queues.len();
+        if num_queues <= 1 {
+            return 0;
+        }
+        match get_message_shard(msg, self.ordering_mode, num_queues as u8) {
+            Some(shard) => shard as usize,
+            None => self.next_queue.fetch_add(1, Ordering::SeqCst) % num_queues,
+        }
+    }
+
+    /// split_by_queue
+    ///
+    /// Group messages by their work vec while keeping their order
+    ///
+    /// # Returns
+    ///
+    /// ``Vec<(queue_num, msgs)>`` without any empty groups
+    ///
+    /// # Arguments
+    ///
+    /// * `msgs` - messages to add
+    ///
+    pub fn split_by_queue(
+        &self,
+        msgs: Vec<KafkaPublishMessage>,
+    ) -> Vec<(usize, Vec<KafkaPublishMessage>)> {
+        if self.queues.len() <= 1 {
+            return vec![(0, msgs)];
+        }
+        let mut groups: Vec<Vec<KafkaPublishMessage>> =
+            (0..self.queues.len()).map(|_| Vec::new()).collect();
+        for msg in msgs {
+            let queue_num = self.get_queue_num(&msg);
+            groups[queue_num].push(msg);
+        }
+        groups
+            .into_iter()
+            .enumerate()
+            .filter(|(_, group)| !group.is_empty())
+            .collect()
+    }
+
+    /// add_messages
+    ///
+    /// Add messages to the work vecs of their shards with the
+    /// overflow policy. The add is all-or-nothing: every target work
+    /// vec is locked (in index order) and checked before any message
+    /// is added so a full work vec never leaves the messages for the
+    /// other shards queued while the caller sees an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `msgs` - messages to add
+    ///
+    /// # Returns
+    ///
+    /// ``Result<KafkaQueueAddStatus, KafkaThreadpoolError>``
+    ///
+    /// where:
+    ///
+    /// Ok - ``KafkaQueueAddStatus::Added`` with the number of
+    /// messages in the target work vecs and the number of messages
+    /// the overflow policy dropped or ``KafkaQueueAddStatus::Full``
+    /// with every message if any target work vec is full
+    /// Error - ``Err(KafkaThreadpoolError::NoMessages)``,
+    /// ``Err(KafkaThreadpoolError::QueueFull)`` if the messages for a
+    /// work vec can never fit in it or
+    /// ``Err(KafkaThreadpoolError::LockPoisoned)``
+    ///
+    pub fn add_messages(
+        &self,
+        msgs: Vec<KafkaPublishMessage>,
+    ) -> Result<KafkaQueueAddStatus, KafkaThreadpoolError> {
+        if msgs.is_empty() {
+            let err = KafkaThreadpoolError::NoMessages;
+            error!("{err}");
+            return Err(err);
+        }
+        let groups = self.split_by_queue(msgs);
+        for (_, queue_msgs) in groups.iter() {
+            check_work_vec_capacity(queue_msgs, &self.queue_config)?;
+        }
+        // split_by_queue returns the work vecs in index order so
+        // concurrent callers lock them in the same order
+        let mut locked_queues = Vec::with_capacity(groups.len());
+        for (queue_num, _) in groups.iter() {
+            match self.queues[*queue_num].lock() {
+                Ok(locked_queue) => locked_queues.push(locked_queue),
+                Err(e) => {
+                    let err = KafkaThreadpoolError::LockPoisoned(e.to_string());
+                    error!("{err}");
+                    return Err(err);
+                }
+            }
+        }
+        let has_space = groups.iter().zip(locked_queues.iter()).all(
+            |((_, queue_msgs), locked_queue)| {
+                has_space_in_work_vec(
+                    locked_queue,
+                    queue_msgs,
+                    &self.queue_config,
+                )
+            },
+        );
+        if !has_space {
+            return Ok(KafkaQueueAddStatus::Full(
+                groups
+                    .into_iter()
+                    .flat_map(|(_, queue_msgs)| queue_msgs)
+                    .collect(),
+            ));
+        }
+        let mut num_in_vec: usize = 0;
+        let mut num_dropped: usize = 0;
+        for ((_, queue_msgs), locked_queue) in
+            groups.into_iter().zip(locked_queues.iter_mut())
+        {
+            match add_messages_to_locked_bounded_work_vec(
+                locked_queue,
+                queue_msgs,
+                &self.queue_config,
+            ) {
+                KafkaQueueAddStatus::Added {
+                    num_in_vec: queue_num_in_vec,
+                    num_dropped: queue_num_dropped,
+                } => {
+                    num_in_vec += queue_num_in_vec;
+                    num_dropped += queue_num_dropped;
+                }
+                // has_space_in_work_vec checked every work vec
+                KafkaQueueAddStatus::Full(returned_msgs) => {
+                    error!(
+                        "work vec full after the space check - \
+                        dropping msgs={}",
+                        returned_msgs.len()
+                    );
+                    num_dropped += returned_msgs.len();
+                }
+            }
+        }
+        Ok(KafkaQueueAddStatus::Added {
+            num_in_vec,
+            num_dropped,
+        })
+    }
+
+    /// is_exited
+    ///
+    /// Check if a worker thread exited
+    ///
+    /// # Arguments
+    ///
+    /// * `thread_num` - worker thread number starting at ``0``
+    ///
+    pub fn is_exited(&self, thread_num: usize) -> bool {
+        match self.exited.get(thread_num) {
+            Some(exited) => exited.load(Ordering::SeqCst),
+            None => true,
+        }
+    }
+
+    /// mark_exited
+    ///
+    /// Record that a worker thread exited so the next running worker
+    /// thread drains its work vec
+    ///
+    /// # Arguments
+    ///
+    /// * `thread_num` - worker thread number starting at ``0``
+    ///
+    /// # Returns
+    ///
+    /// ``true`` if every worker thread exited
+    ///
+    pub fn mark_exited(&self, thread_num: u8) -> bool {
+        if let Some(exited) = self.exited.get(thread_num as usize) {
+            exited.store(true, Ordering::SeqCst);
+        }
+        self.num_running() == 0
+    }
+
+    /// num_running
+    ///
+    /// # Returns
+    ///
+    /// Number of worker threads that have not exited
+    ///
+    pub fn num_running(&self) -> usize {
+        self.exited
+            .iter()
+            .filter(|exited| !exited.load(Ordering::SeqCst))
+            .count()
+    }
+
+    /// get_drainer
+    ///
+    /// # Returns
+    ///
+    /// The worker thread that drains a work vec: its own worker
+    /// thread or the next running worker thread after it (``None``
+    /// once every worker thread exited)
+    ///
+    /// # Arguments
+    ///
+    /// * `queue_num` - index of the work vec
+    ///
+    pub fn get_drainer(&self, queue_num: usize) -> Option<usize> {
+        let num_threads = self.exited.len();
+        (0..num_threads)
+            .map(|offset| (queue_num + offset) % num_threads)
+            .find(|thread_num| !self.is_exited(*thread_num))
+    }
+
+    /// drain
+    ///
+    /// Drain the messages a worker thread publishes next
+    ///
+    /// # Returns
+    ///
+    /// Up to ``max_msgs`` messages from each work vec the worker
+    /// thread drains (its own work vec first)
+    ///
+    /// # Arguments
+    ///
+    /// * `cur_thread_num` - worker thread number starting at ``0``
+    /// * `max_msgs` - max number of messages to drain from each
+    /// work vec (``0`` drains all messages)
+    ///
+    pub fn drain(
+        &self,
+        cur_thread_num: u8,
+        max_msgs: usize,
+    ) -> Vec<KafkaPublishMessage> {
+        let num_queues = self.queues.len();
+        if num_queues <= 1 {
+            return drain_messages_from_locked_work_vec(
+                &self.queues[0],
+                max_msgs,
+            );
+        }
+        let cur_thread_num = cur_thread_num as usize;
+        let mut msgs = drain_messages_from_locked_work_vec(
+            &self.queues[cur_thread_num],
+            max_msgs,
+        );
+        // take over the work vecs of exited worker threads
+        for queue_num in 0..num_queues {
+            if queue_num != cur_thread_num
+                && self.get_drainer(queue_num) == Some(cur_thread_num)
+            {
+                msgs.extend(drain_messages_from_locked_work_vec(
+                    &self.queues[queue_num],
+                    max_msgs,
+                ));
+            }
+        }
+        msgs
+    }
+
+    /// requeue
+    ///
+    /// Put messages a worker thread drained but did not publish back
+    /// at the front of its work vec (in order) so the next running
+    /// worker thread publishes them
+    ///
+    /// # Arguments
+    ///
+    /// * `cur_thread_num` - worker thread number starting at ``0``
+    /// * `msgs` - drained messages to put back
+    ///
+    pub fn requeue(&self, cur_thread_num: u8, msgs: Vec<KafkaPublishMessage>) {
+        if msgs.is_empty() {
+            return;
+        }
+        let queue_num = if self.queues.len() <= 1 {
+            0
+        } else {
+            cur_thread_num as usize
+        };
+        match self.queues[queue_num].lock() {
+            Ok(mut locked_queue) => {
+                locked_queue.splice(0..0, msgs);
+            }
+            Err(e) => {
+                error!(
+                    "failed to requeue msgs={} to work vec={queue_num} \
+                    with err={e}",
+                    msgs.len()
+                );
+            }
+        }
+    }
+
+    /// drain_all
+    ///
+    /// Drain every message from every work vec
+    ///
+    pub fn drain_all(&self) -> Vec<KafkaPublishMessage> {
+        self.queues
+            .iter()
+            .flat_map(|queue| drain_messages_from_locked_work_vec(queue, 0))
+            .collect()
+    }
+
+    /// len
+    ///
+    /// # Returns
+    ///
+    /// Number of messages in every work vec
+    ///
+    pub fn len(&self) -> usize {
+        self.queues
+            .iter()
+            .map(|queue| match queue.lock() {
+                Ok(locked_queue) => locked_queue.len(),
+                Err(_) => 0,
+            })
+            .sum()
+    }
+
+    /// is_empty
+    ///
+    /// # Returns
+    ///
+    /// ``true`` if every work vec is empty
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::kafka_publish_message_type::KafkaPublishMessageType;
+    use crate::api::kafka_queue_overflow_policy::KafkaQueueOverflowPolicy;
+
+    fn build_queues(
+        num_threads: u8,
+        ordering_mode: KafkaOrderingMode,
+        max_queue_msgs: usize,
+    ) -> KafkaWorkQueues {
+        let mut config = KafkaClientConfig::default();
+        config.num_threads = num_threads;
+        config.ordering_mode = ordering_mode;
+        config.max_queue_msgs = max_queue_msgs;
+        config.queue_overflow_policy = KafkaQueueOverflowPolicy::Reject;
+        KafkaWorkQueues::new(&config)
+    }
+
+    fn keyed_msg(key: &str) -> KafkaPublishMessage {
+        KafkaPublishMessage::new_from_bytes(
+            KafkaPublishMessageType::Data,
+            "orders",
+            Some(key.as_bytes().to_vec()),
+            Vec::new(),
+            Some(key.as_bytes().to_vec()),
+        )
+    }
+
+    // first key (k0, k1, ...) that hashes to the work vec
+    fn key_for_queue(
+        work_queues: &KafkaWorkQueues,
+        queue_num: usize,
+    ) -> String {
+        (0..1000)
+            .map(|idx| format!("k{idx}"))
+            .find(|key| work_queues.get_queue_num(&keyed_msg(key)) == queue_num)
+            .unwrap()
+    }
+
+    fn queue_keys(
+        work_queues: &KafkaWorkQueues,
+        queue_num: usize,
+    ) -> Vec<String> {
+        work_queues.queues[queue_num]
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|msg| msg.key_as_string().unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn add_messages_is_all_or_nothing_across_shards() {
+        // 2 msgs split into 1 msg per work vec
+        let work_queues = build_queues(2, KafkaOrderingMode::Key, 2);
+        let key_0 = key_for_queue(&work_queues, 0);
+        let key_1 = key_for_queue(&work_queues, 1);
+        work_queues.queues[1]
+            .lock()
+            .unwrap()
+            .push(keyed_msg(&key_1));
+        match work_queues
+            .add_messages(vec![keyed_msg(&key_0), keyed_msg(&key_1)])
+            .unwrap()
+        {
+            KafkaQueueAddStatus::Full(msgs) => assert_eq!(msgs.len(), 2),
+            KafkaQueueAddStatus::Added { .. } => {
+                panic!("expected Full with a full shard")
+            }
+        }
+        // the shard with space did not keep its message
+        assert!(queue_keys(&work_queues, 0).is_empty());
+        assert_eq!(queue_keys(&work_queues, 1), vec![key_1.clone()]);
+        work_queues.queues[1].lock().unwrap().clear();
+        match work_queues
+            .add_messages(vec![keyed_msg(&key_0), keyed_msg(&key_1)])
+            .unwrap()
+        {
+            KafkaQueueAddStatus::Added {
+                num_in_vec,
+                num_dropped,
+            } => assert_eq!((num_in_vec, num_dropped), (2, 0)),
+            KafkaQueueAddStatus::Full(_) => panic!("expected Added"),
+        }
+        assert_eq!(queue_keys(&work_queues, 0), vec![key_0]);
+        assert_eq!(queue_keys(&work_queues, 1), vec![key_1]);
+    }
+
+    #[test]
+    fn add_messages_checks_the_capacity_of_each_shard() {
+        // each work vec holds 1 msg so 2 msgs for one key never fit
+        let work_queues = build_queues(2, KafkaOrderingMode::Key, 2);
+        let key_0 = key_for_queue(&work_queues, 0);
+        let res = work_queues
+            .add_messages(vec![keyed_msg(&key_0), keyed_msg(&key_0)]);
+        assert!(matches!(
+            res,
+            Err(KafkaThreadpoolError::QueueFull { num_msgs: 2, .. })
+        ));
+        assert!(matches!(
+            work_queues.add_messages(Vec::new()),
+            Err(KafkaThreadpoolError::NoMessages)
+        ));
+    }
+
+    #[test]
+    fn unordered_uses_one_shared_work_vec() {
+        let work_queues = build_queues(4, KafkaOrderingMode::Unordered, 8);
+        assert_eq!(work_queues.queues.len(), 1);
+        assert_eq!(work_queues.queue_config.max_queue_msgs, 8);
+        let groups =
+            work_queues.split_by_queue(vec![keyed_msg("a"), keyed_msg("b")]);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].1.len(), 2);
+    }
+
+    #[test]
+    fn ordering_modes_split_the_capacity() {
+        let work_queues = build_queues(3, KafkaOrderingMode::Key, 10);
+        assert_eq!(work_queues.queues.len(), 3);
+        assert_eq!(work_queues.queue_config.max_queue_msgs, 4);
+    }
+
+    #[test]
+    fn split_by_queue_keeps_the_order_per_work_vec() {
+        let work_queues = build_queues(2, KafkaOrderingMode::Key, 0);
+        let key_0 = key_for_queue(&work_queues, 0);
+        let key_1 = key_for_queue(&work_queues, 1);
+        let mut msgs = Vec::new();
+        for idx in 0..3 {
+            let mut msg_0 = keyed_msg(&key_0);
+            msg_0.payload = Some(format!("{key_0}-{idx}").into_bytes());
+            let mut msg_1 = keyed_msg(&key_1);
+            msg_1.payload = Some(format!("{key_1}-{idx}").into_bytes());
+            msgs.push(msg_0);
+            msgs.push(msg_1);
+        }
+        let groups = work_queues.split_by_queue(msgs);
+        let payloads: Vec<(usize, Vec<String>)> = groups
+            .into_iter()
+            .map(|(queue_num, queue_msgs)| {
+                (
+                    queue_num,
+                    queue_msgs
+                        .iter()
+                        .map(|msg| msg.payload_as_string().unwrap())
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            payloads,
+            vec![
+                (0, (0..3).map(|idx| format!("{key_0}-{idx}")).collect()),
+                (1, (0..3).map(|idx| format!("{key_1}-{idx}")).collect()),
+            ]
+        );
+    }
+
+    #[test]
+    fn messages_without_a_key_are_spread_round_robin() {
+        let work_queues = build_queues(3, KafkaOrderingMode::Key, 0);
+        let mut msg = keyed_msg("a");
+        msg.key = None;
+        let queue_nums: Vec<usize> =
+            (0..6).map(|_| work_queues.get_queue_num(&msg)).collect();
+        assert_eq!(queue_nums, vec![0, 1, 2, 0, 1, 2]);
+    }
+
+    #[test]
+    fn get_drainer_skips_exited_worker_threads() {
+        let work_queues = build_queues(3, KafkaOrderingMode::Key, 0);
+        assert_eq!(work_queues.num_running(), 3);
+        assert_eq!(work_queues.get_drainer(1), Some(1));
+        assert!(!work_queues.mark_exited(1));
+        assert!(work_queues.is_exited(1));
+        assert_eq!(work_queues.num_running(), 2);
+        assert_eq!(work_queues.get_drainer(1), Some(2));
+        assert!(!work_queues.mark_exited(2));
+        // wraps around to the first worker thread
+        assert_eq!(work_queues.get_drainer(1), Some(0));
+        assert_eq!(work_queues.get_drainer(2), Some(0));
+        assert!(work_queues.mark_exited(0));
+        assert_eq!(work_queues.get_drainer(0), None);
+        assert_eq!(work_queues.num_running(), 0);
+    }
+
+    #[test]
+    fn drain_takes_over_the_work_vecs_of_exited_worker_threads() {
+        let work_queues = build_queues(3, KafkaOrderingMode::Key, 0);
+        for queue_num in 0..3 {
+            let key = key_for_queue(&work_queues, queue_num);
+            work_queues.queues[queue_num]
+                .lock()
+                .unwrap()
+                .extend((0..2).map(|_| keyed_msg(&key)));
+        }
+        let key_1 = key_for_queue(&work_queues, 1);
+        let key_2 = key_for_queue(&work_queues, 2);
+        // max 1 msg per work vec
+        let drained = work_queues.drain(2, 1);
+        assert_eq!(drained.len(), 1);
+        work_queues.mark_exited(1);
+        let drained: Vec<String> = work_queues
+            .drain(2, 0)
+            .iter()
+            .map(|msg| msg.key_as_string().unwrap())
+            .collect();
+        // its own work vec first
+        assert_eq!(drained, vec![key_2, key_1.clone(), key_1]);
+        assert_eq!(work_queues.len(), 2);
+        assert_eq!(work_queues.drain_all().len(), 2);
+        assert!(work_queues.is_empty());
+    }
+
+    #[test]
+    fn requeue_puts_messages_back_in_front() {
+        let work_queues = build_queues(2, KafkaOrderingMode::Key, 0);
+        let key_1 = key_for_queue(&work_queues, 1);
+        work_queues.queues[1]
+            .lock()
+            .unwrap()
+            .push(keyed_msg("queued"));
+        work_queues.requeue(1, vec![keyed_msg(&key_1), keyed_msg("second")]);
+        work_queues.requeue(1, Vec::new());
+        assert_eq!(
+            queue_keys(&work_queues, 1),
+            vec![key_1, "second".to_string(), "queued".to_string()]
+        );
+        // one shared work vec ignores the thread number
+        let work_queues = build_queues(2, KafkaOrderingMode::Unordered, 0);
+        work_queues.requeue(1, vec![keyed_msg("a")]);
+        assert_eq!(queue_keys(&work_queues, 0), vec!["a".to_string()]);
+    }
+}
@@ -1,3 +1,5 @@
 //! Module to start the worker threads
 //!
+pub mod kafka_pool_state;
+pub mod kafka_work_queues;
 pub mod start_threads_from_config;
@@ -2,12 +2,20 @@
 //! ``tokio::spawn(async move {}))``  
 //!
 use std::sync::Arc;
-use std::sync::Mutex;
 
 use log::info;
 
+use crate::admin::get_topic_specs_from_config::get_topic_specs_from_config;
+use crate::admin::kafka_admin_client::KafkaAdminClient;
+use crate::api::get_kafka_producer::get_kafka_producer;
+use crate::api::get_kafka_producer::get_kafka_producer_with_context;
+use crate::api::kafka_client_context::KafkaClientContext;
+use crate::api::kafka_oauth_client_context::KafkaOAuthClientContext;
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
 use crate::config::kafka_client_config::KafkaClientConfig;
 use crate::kafka_publisher::KafkaPublisher;
+use crate::pool::kafka_pool_state::KafkaPoolState;
+use crate::pool::kafka_work_queues::KafkaWorkQueues;
 use crate::thread_process_messages_handler::thread_process_messages_handler;
 
 /// start_threads_from_config
@@ -16,46 +24,100 @@ use crate::thread_process_messages_handler::thread_process_messages_handler;
 ///
 /// * `config` - initialized [`KafkaClientConfig`] for the threadpool
 ///
+/// # Errors
+///
+/// [`KafkaThreadpoolError::ConfigInvalid`] if there are no brokers,
+/// [`KafkaThreadpoolError::Broker`] if ``rdkafka`` rejects the
+/// producer configuration or
+/// [`KafkaThreadpoolError::Admin`] if ``config.ensure_topics`` is
+/// ``true`` and a configured topic cannot be created
+///
 /// # Examples
 ///
 /// ```rust
 /// use kafka_threadpool::api::build_kafka_client_config::build_kafka_client_config;
 /// use kafka_threadpool::pool::start_threads_from_config::start_threads_from_config;
-/// let config = build_kafka_client_config("testing");
-/// let kafka_publisher = match start_threads_from_config(&config) {
+/// let config = build_kafka_client_config("testing").unwrap();
+/// let kafka_publisher = match start_threads_from_config(config).await {
 ///     Ok(kp) => kp,
 ///     Err(e) => panic!("failed to start threads with err={e}")
 /// };
 /// println!("shutting down kafka_threadpool");
-/// kafka_publisher.shutdown();
+/// let report = kafka_publisher
+///     .shutdown_and_wait(std::time::Duration::from_secs(10))
+///     .await
+///     .unwrap();
+/// println!("{report}");
 /// ```
 pub async fn start_threads_from_config(
     config: KafkaClientConfig,
-) -> Result<KafkaPublisher, String> {
+) -> Result<KafkaPublisher, KafkaThreadpoolError> {
     if !config.is_enabled {
         info!("{} - kafka-threadpool disabled", config.label);
-        return Ok(KafkaPublisher::new());
+        return Ok(KafkaPublisher {
+            config,
+            ..Default::default()
+        });
+    }
+    if config.broker_list.is_empty() || config.broker_list[0].is_empty() {
+        return Err(KafkaThreadpoolError::config_invalid(
+            "KAFKA_BROKERS",
+            &format!("no brokers to connect to {:?}", config.broker_list),
+        ));
+    }
+    // fail fast if rdkafka rejects the producer configuration
+    match &config.oauth_token_provider {
+        Some(token_provider) => {
+            get_kafka_producer_with_context(
+                &config,
+                KafkaOAuthClientContext::new(
+                    KafkaClientContext::default(),
+                    token_provider.clone(),
+                ),
+            )?;
+        }
+        None => {
+            get_kafka_producer(&config)?;
+        }
+    }
+    if config.ensure_topics {
+        let topic_specs = get_topic_specs_from_config(&config);
+        info!("{} - ensuring topics={}", config.label, topic_specs.len());
+        KafkaAdminClient::new(&config)?
+            .ensure_topics(&topic_specs)
+            .await?;
     }
     info!("{} - starting threads={}", config.label, config.num_threads);
     let new_publisher = KafkaPublisher {
         config: config.clone(),
-        // create the shared lockable vector of messages
-        publish_msgs: Arc::new(Mutex::new(Vec::new())),
+        // create the shared lockable vectors of messages
+        publish_msgs: Arc::new(KafkaWorkQueues::new(&config)),
+        // create the shared signals for the publisher and workers
+        pool_state: Arc::new(KafkaPoolState::new()),
     };
 
-    // start threads
+    // start threads and keep the handles for shutdown_and_wait
+    let mut workers = Vec::with_capacity(config.num_threads as usize);
     for cur_thread_num in 0..new_publisher.config.num_threads {
         info!("{} - creating thread={cur_thread_num}", config.label);
         let cloned_config = new_publisher.config.clone();
         let cloned_publishable_work_vec = new_publisher.publish_msgs.clone();
-        tokio::spawn(async move {
+        let cloned_pool_state = new_publisher.pool_state.clone();
+        workers.push(tokio::spawn(async move {
             thread_process_messages_handler(
                 cur_thread_num,
                 cloned_config,
                 cloned_publishable_work_vec,
+                cloned_pool_state,
             )
             .await;
-        });
+        }));
+    }
+    match new_publisher.pool_state.workers.lock() {
+        Ok(mut locked_workers) => locked_workers.extend(workers),
+        Err(e) => {
+            return Err(KafkaThreadpoolError::LockPoisoned(e.to_string()))
+        }
     }
     Ok(new_publisher)
 }
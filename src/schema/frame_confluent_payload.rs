@@ -0,0 +1,144 @@
+//! Frame an encoded payload with the Confluent wire format:
+//!
+//! ```text
+//! | magic byte 0 | schema id (4 bytes big-endian) | [protobuf message indexes] | body |
+//! ```
+//!
+use crate::schema::kafka_schema_type::KafkaSchemaType;
+
+/// CONFLUENT_MAGIC_BYTE
+///
+/// First byte of every Confluent wire format payload
+///
+pub const CONFLUENT_MAGIC_BYTE: u8 = 0;
+
+/// write_zigzag_varint
+///
+/// Internal helper for writing a protobuf message index as a
+/// zig-zag encoded varint (the same encoding as a protobuf
+/// ``sint32``)
+///
+/// # Arguments
+///
+/// * `buf` - output buffer
+/// * `value` - value to encode
+///
+fn write_zigzag_varint(buf: &mut Vec<u8>, value: i32) {
+    let mut zigzag = ((value << 1) ^ (value >> 31)) as u32;
+    while zigzag >= 0x80 {
+        buf.push((zigzag as u8) | 0x80);
+        zigzag >>= 7;
+    }
+    buf.push(zigzag as u8);
+}
+
+/// frame_confluent_payload
+///
+/// Prefix an already-encoded body (Avro binary, Protobuf or JSON)
+/// with the magic byte, the schema id and (for ``Protobuf``) the
+/// message indexes
+///
+/// # Arguments
+///
+/// * `schema_type` - [`KafkaSchemaType`] format of ``body``
+/// * `schema_id` - schema id from the schema registry
+/// * `message_indexes` - ``Protobuf`` only - path to the message
+/// type (empty means the first message and is written as a single
+/// ``0`` byte)
+/// * `body` - encoded payload
+///
+/// # Returns
+///
+/// ``Vec<u8>`` with the framed payload
+///
+/// # Examples
+///
+/// ```rust
+/// use kafka_threadpool::schema::frame_confluent_payload::frame_confluent_payload;
+/// use kafka_threadpool::schema::kafka_schema_type::KafkaSchemaType;
+/// let framed = frame_confluent_payload(
+///     KafkaSchemaType::Avro, 7, &[], &[0x02, 0x06]);
+/// assert_eq!(framed, vec![0, 0, 0, 0, 7, 0x02, 0x06]);
+/// ```
+///
+pub fn frame_confluent_payload(
+    schema_type: KafkaSchemaType,
+    schema_id: u32,
+    message_indexes: &[i32],
+    body: &[u8],
+) -> Vec<u8> {
+    let mut framed: Vec<u8> = Vec::with_capacity(body.len() + 6);
+    framed.push(CONFLUENT_MAGIC_BYTE);
+    framed.extend_from_slice(&schema_id.to_be_bytes());
+    if schema_type == KafkaSchemaType::Protobuf {
+        // [0] (the first message) is optimized to a single 0 byte
+        if message_indexes.is_empty() || message_indexes == [0] {
+            framed.push(0);
+        } else {
+            write_zigzag_varint(&mut framed, message_indexes.len() as i32);
+            for index in message_indexes.iter() {
+                write_zigzag_varint(&mut framed, *index);
+            }
+        }
+    }
+    framed.extend_from_slice(body);
+    framed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn framed_indexes(message_indexes: &[i32]) -> Vec<u8> {
+        let framed = frame_confluent_payload(
+            KafkaSchemaType::Protobuf,
+            258,
+            message_indexes,
+            &[0xaa],
+        );
+        assert_eq!(framed[..5], [CONFLUENT_MAGIC_BYTE, 0, 0, 1, 2]);
+        assert_eq!(framed.last(), Some(&0xaa));
+        framed[5..framed.len() - 1].to_vec()
+    }
+
+    #[test]
+    fn protobuf_first_message_is_a_single_zero_byte() {
+        assert_eq!(framed_indexes(&[]), vec![0]);
+        assert_eq!(framed_indexes(&[0]), vec![0]);
+    }
+
+    #[test]
+    fn protobuf_message_indexes_are_zigzag_varints() {
+        // count=1 index=1
+        assert_eq!(framed_indexes(&[1]), vec![2, 2]);
+        // count=2 index=1 index=0
+        assert_eq!(framed_indexes(&[1, 0]), vec![4, 2, 0]);
+        // count=3 index=0 index=2 index=3
+        assert_eq!(framed_indexes(&[0, 2, 3]), vec![6, 0, 4, 6]);
+    }
+
+    #[test]
+    fn protobuf_message_indexes_use_multiple_bytes() {
+        // 64 zig-zags to 128 which needs two varint bytes
+        assert_eq!(framed_indexes(&[64]), vec![2, 0x80, 0x01]);
+        assert_eq!(framed_indexes(&[300]), vec![2, 0xd8, 0x04]);
+    }
+
+    #[test]
+    fn write_zigzag_varint_encodes_negative_values() {
+        let mut buf = Vec::new();
+        write_zigzag_varint(&mut buf, -1);
+        write_zigzag_varint(&mut buf, -64);
+        write_zigzag_varint(&mut buf, i32::MIN);
+        assert_eq!(buf, vec![1, 0x7f, 0xff, 0xff, 0xff, 0xff, 0x0f]);
+    }
+
+    #[test]
+    fn non_protobuf_payloads_have_no_message_indexes() {
+        for schema_type in [KafkaSchemaType::Avro, KafkaSchemaType::Json] {
+            let framed =
+                frame_confluent_payload(schema_type, 7, &[1, 2], b"{}");
+            assert_eq!(framed, vec![0, 0, 0, 0, 7, b'{', b'}']);
+        }
+    }
+}
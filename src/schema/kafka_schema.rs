@@ -0,0 +1,74 @@
+//! class definition for a schema registered under a schema
+//! registry subject
+//!
+use crate::schema::kafka_schema_type::KafkaSchemaType;
+
+/// KafkaSchema
+///
+/// Schema for framing payloads with the Confluent wire format
+///
+/// * `subject` - schema registry subject (``<topic>-value`` with
+/// the default ``TopicNameStrategy``)
+/// * `schema_type` - [`KafkaSchemaType`] format
+/// * `schema` - schema definition (Avro JSON, ``.proto`` source or
+/// JSON Schema)
+/// * `message_indexes` - ``Protobuf`` only - path to the message
+/// type within the ``.proto`` file (empty means the first message)
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KafkaSchema {
+    pub subject: String,
+    pub schema_type: KafkaSchemaType,
+    pub schema: String,
+    pub message_indexes: Vec<i32>,
+}
+
+impl KafkaSchema {
+    /// new
+    ///
+    /// Create a schema for a subject
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - schema registry subject
+    /// * `schema_type` - [`KafkaSchemaType`] format
+    /// * `schema` - schema definition
+    ///
+    pub fn new(
+        subject: &str,
+        schema_type: KafkaSchemaType,
+        schema: &str,
+    ) -> Self {
+        KafkaSchema {
+            subject: subject.to_string(),
+            schema_type,
+            schema: schema.to_string(),
+            message_indexes: Vec::new(),
+        }
+    }
+
+    /// with_message_indexes
+    ///
+    /// Set the ``Protobuf`` message indexes for a message type
+    /// that is not the first message in the ``.proto`` file
+    ///
+    /// # Arguments
+    ///
+    /// * `message_indexes` - path to the message type (``[1, 0]``
+    /// is the first nested message of the second top-level message)
+    ///
+    pub fn with_message_indexes(mut self, message_indexes: Vec<i32>) -> Self {
+        self.message_indexes = message_indexes;
+        self
+    }
+}
+
+impl std::fmt::Display for KafkaSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "KafkaSchema subject={} type={} message_indexes={:?}",
+            self.subject, self.schema_type, self.message_indexes
+        )
+    }
+}
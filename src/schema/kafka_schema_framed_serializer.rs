@@ -0,0 +1,77 @@
+//! [`PayloadSerializer`](crate::serialize::payload_serializer::PayloadSerializer)
+//! that frames another serializer's output with the Confluent wire
+//! format
+//!
+use serde::Serialize;
+
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
+use crate::schema::frame_confluent_payload::frame_confluent_payload;
+use crate::schema::kafka_schema_type::KafkaSchemaType;
+use crate::serialize::payload_serializer::PayloadSerializer;
+
+/// KafkaSchemaFramedSerializer
+///
+/// Encode the body with ``inner`` and prefix the magic byte, the
+/// already-resolved schema id and (for ``Protobuf``) the message
+/// indexes. Create one with
+/// [`KafkaSchemaRegistryClient::framed_serializer`](crate::schema::kafka_schema_registry_client::KafkaSchemaRegistryClient::framed_serializer).
+///
+/// The ``content-type`` is the ``inner`` serializer's
+/// ``content-type``.
+///
+#[derive(Debug, Clone)]
+pub struct KafkaSchemaFramedSerializer<S: PayloadSerializer> {
+    pub inner: S,
+    pub schema_type: KafkaSchemaType,
+    pub schema_id: u32,
+    pub message_indexes: Vec<i32>,
+}
+
+impl<S: PayloadSerializer> KafkaSchemaFramedSerializer<S> {
+    /// new
+    ///
+    /// Wrap a serializer with a resolved schema id
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - serializer that encodes the body
+    /// * `schema_type` - [`KafkaSchemaType`] format of the body
+    /// * `schema_id` - schema id from the schema registry
+    /// * `message_indexes` - ``Protobuf`` only - path to the
+    /// message type
+    ///
+    pub fn new(
+        inner: S,
+        schema_type: KafkaSchemaType,
+        schema_id: u32,
+        message_indexes: Vec<i32>,
+    ) -> Self {
+        KafkaSchemaFramedSerializer {
+            inner,
+            schema_type,
+            schema_id,
+            message_indexes,
+        }
+    }
+}
+
+impl<S: PayloadSerializer> PayloadSerializer
+    for KafkaSchemaFramedSerializer<S>
+{
+    fn content_type(&self) -> &str {
+        self.inner.content_type()
+    }
+
+    fn serialize<T: Serialize + ?Sized>(
+        &self,
+        value: &T,
+    ) -> Result<Vec<u8>, KafkaThreadpoolError> {
+        let body = self.inner.serialize(value)?;
+        Ok(frame_confluent_payload(
+            self.schema_type,
+            self.schema_id,
+            &self.message_indexes,
+            &body,
+        ))
+    }
+}
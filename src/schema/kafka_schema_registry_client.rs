@@ -0,0 +1,530 @@
+//! Schema registry HTTP API client that registers (or looks up)
+//! schemas, caches the schema ids and falls back to the static
+//! schema ids when the registry is offline
+//!
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use log::info;
+use log::warn;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
+use crate::schema::frame_confluent_payload::frame_confluent_payload;
+use crate::schema::kafka_schema::KafkaSchema;
+use crate::schema::kafka_schema_framed_serializer::KafkaSchemaFramedSerializer;
+use crate::schema::kafka_schema_registry_config::KafkaSchemaRegistryConfig;
+use crate::schema::kafka_schema_type::KafkaSchemaType;
+use crate::serialize::payload_serializer::PayloadSerializer;
+
+/// SCHEMA_REGISTRY_CONTENT_TYPE
+///
+/// ``Content-Type`` for schema registry HTTP API requests
+///
+const SCHEMA_REGISTRY_CONTENT_TYPE: &str =
+    "application/vnd.schemaregistry.v1+json";
+
+/// SchemaRequest
+///
+/// Internal request body for registering or looking up a schema
+///
+#[derive(Serialize)]
+struct SchemaRequest<'a> {
+    schema: &'a str,
+    // older registries only support avro and reject the field
+    #[serde(rename = "schemaType", skip_serializing_if = "Option::is_none")]
+    schema_type: Option<&'a str>,
+}
+
+/// SchemaIdResponse
+///
+/// Internal response body with the registered schema id
+///
+#[derive(Deserialize)]
+struct SchemaIdResponse {
+    id: u32,
+}
+
+/// SchemaRegistryRequestError
+///
+/// Internal reasons a registry request failed
+///
+/// - ``Offline`` - the registry is unreachable, timed out or
+/// returned a server error so the static schema ids can be used
+/// - ``Rejected`` - the registry rejected the schema (incompatible,
+/// invalid, not registered, unauthorized, etc.)
+///
+enum SchemaRegistryRequestError {
+    Offline(String),
+    Rejected(String),
+}
+
+/// KafkaSchemaRegistryClient
+///
+/// Cloneable schema registry client where every clone shares the
+/// schema id cache
+///
+/// * `config` - [`KafkaSchemaRegistryConfig`] connectivity and
+/// fallback values
+///
+#[derive(Clone)]
+pub struct KafkaSchemaRegistryClient {
+    pub config: KafkaSchemaRegistryConfig,
+    http_client: reqwest::Client,
+    cache: Arc<RwLock<HashMap<(String, String), u32>>>,
+}
+
+impl KafkaSchemaRegistryClient {
+    /// new
+    ///
+    /// Create a schema registry client
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - [`KafkaSchemaRegistryConfig`] connectivity and
+    /// fallback values (the url can point at a local mock registry
+    /// for testing)
+    ///
+    /// # Errors
+    ///
+    /// [`KafkaThreadpoolError::ConfigInvalid`] if the HTTP client
+    /// cannot be created
+    ///
+    pub fn new(
+        config: KafkaSchemaRegistryConfig,
+    ) -> Result<Self, KafkaThreadpoolError> {
+        let http_client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .map_err(|e| {
+                KafkaThreadpoolError::config_invalid(
+                    "KAFKA_SCHEMA_REGISTRY_URL",
+                    &format!("failed to create http client with err={e}"),
+                )
+            })?;
+        info!("schema registry client - {config}");
+        Ok(KafkaSchemaRegistryClient {
+            config,
+            http_client,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+        })
+    }
+
+    /// cached_schema_id
+    ///
+    /// # Returns
+    ///
+    /// the cached schema id for the subject and schema definition
+    /// without calling the registry
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - [`KafkaSchema`] to look up
+    ///
+    pub fn cached_schema_id(&self, schema: &KafkaSchema) -> Option<u32> {
+        match self.cache.read() {
+            Ok(cache) => cache
+                .get(&(schema.subject.clone(), schema.schema.clone()))
+                .copied(),
+            Err(_) => None,
+        }
+    }
+
+    /// get_schema_id
+    ///
+    /// Get the schema id for a schema from the cache, the registry
+    /// (registering the schema if ``auto_register`` is ``true``)
+    /// or the static schema ids if the registry is offline
+    ///
+    /// Ids from the static schema ids are not cached so the next
+    /// call tries the registry again.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - [`KafkaSchema`] to register or look up
+    ///
+    /// # Errors
+    ///
+    /// [`KafkaThreadpoolError::SchemaRegistry`] if the registry
+    /// rejected the schema or is offline without a static schema
+    /// id for the subject
+    ///
+    pub async fn get_schema_id(
+        &self,
+        schema: &KafkaSchema,
+    ) -> Result<u32, KafkaThreadpoolError> {
+        if let Some(schema_id) = self.cached_schema_id(schema) {
+            return Ok(schema_id);
+        }
+        let offline_reason = if self.config.is_offline() {
+            "no schema registry url".to_string()
+        } else {
+            match self.request_schema_id(schema).await {
+                Ok(schema_id) => {
+                    if let Ok(mut cache) = self.cache.write() {
+                        cache.insert(
+                            (schema.subject.clone(), schema.schema.clone()),
+                            schema_id,
+                        );
+                    }
+                    return Ok(schema_id);
+                }
+                Err(SchemaRegistryRequestError::Rejected(reason)) => {
+                    return Err(KafkaThreadpoolError::SchemaRegistry {
+                        subject: schema.subject.clone(),
+                        reason,
+                    });
+                }
+                Err(SchemaRegistryRequestError::Offline(reason)) => reason,
+            }
+        };
+        match self.config.static_ids.get(&schema.subject) {
+            Some(schema_id) => {
+                warn!(
+                    "schema registry offline for subject={} \
+                    with err={offline_reason} - using static schema id={}",
+                    schema.subject, schema_id
+                );
+                Ok(*schema_id)
+            }
+            None => Err(KafkaThreadpoolError::SchemaRegistry {
+                subject: schema.subject.clone(),
+                reason: format!(
+                    "registry offline with err={offline_reason} \
+                    and no static schema id"
+                ),
+            }),
+        }
+    }
+
+    /// request_schema_id
+    ///
+    /// Internal helper for registering
+    /// (``POST /subjects/<subject>/versions``) or looking up
+    /// (``POST /subjects/<subject>``) a schema
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - [`KafkaSchema`] to register or look up
+    ///
+    async fn request_schema_id(
+        &self,
+        schema: &KafkaSchema,
+    ) -> Result<u32, SchemaRegistryRequestError> {
+        let mut url = reqwest::Url::parse(&self.config.url).map_err(|e| {
+            SchemaRegistryRequestError::Rejected(format!(
+                "invalid registry url={} with err={e}",
+                self.config.url
+            ))
+        })?;
+        {
+            let mut segments = url.path_segments_mut().map_err(|_| {
+                SchemaRegistryRequestError::Rejected(format!(
+                    "invalid registry url={}",
+                    self.config.url
+                ))
+            })?;
+            segments
+                .pop_if_empty()
+                .push("subjects")
+                .push(&schema.subject);
+            if self.config.auto_register {
+                segments.push("versions");
+            }
+        }
+        let body = SchemaRequest {
+            schema: &schema.schema,
+            schema_type: match schema.schema_type {
+                KafkaSchemaType::Avro => None,
+                _ => Some(schema.schema_type.as_registry_str()),
+            },
+        };
+        let mut request = self
+            .http_client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, SCHEMA_REGISTRY_CONTENT_TYPE)
+            .json(&body);
+        if !self.config.username.is_empty() {
+            request = request
+                .basic_auth(&self.config.username, Some(&self.config.password));
+        }
+        let response = request.send().await.map_err(|e| {
+            SchemaRegistryRequestError::Offline(format!(
+                "request failed with err={e}"
+            ))
+        })?;
+        let status = response.status();
+        if status.is_server_error() {
+            return Err(SchemaRegistryRequestError::Offline(format!(
+                "registry returned status={status}"
+            )));
+        }
+        if !status.is_success() {
+            let reason = response.text().await.unwrap_or_default();
+            return Err(SchemaRegistryRequestError::Rejected(format!(
+                "registry returned status={status} body={reason}"
+            )));
+        }
+        response
+            .json::<SchemaIdResponse>()
+            .await
+            .map(|res| res.id)
+            .map_err(|e| {
+                SchemaRegistryRequestError::Rejected(format!(
+                    "invalid registry response with err={e}"
+                ))
+            })
+    }
+
+    /// frame_payload
+    ///
+    /// Frame an already-encoded body (Avro binary, Protobuf or
+    /// JSON) with the Confluent wire format for the schema
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - [`KafkaSchema`] for the body
+    /// * `body` - encoded payload
+    ///
+    /// # Errors
+    ///
+    /// [`KafkaThreadpoolError::SchemaRegistry`] if the schema id
+    /// is not available
+    ///
+    pub async fn frame_payload(
+        &self,
+        schema: &KafkaSchema,
+        body: &[u8],
+    ) -> Result<Vec<u8>, KafkaThreadpoolError> {
+        let schema_id = self.get_schema_id(schema).await?;
+        Ok(frame_confluent_payload(
+            schema.schema_type,
+            schema_id,
+            &schema.message_indexes,
+            body,
+        ))
+    }
+
+    /// framed_serializer
+    ///
+    /// Resolve the schema id once and wrap a
+    /// [`PayloadSerializer`](crate::serialize::payload_serializer::PayloadSerializer)
+    /// so every serialized payload is framed without calling the
+    /// registry again
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - [`KafkaSchema`] for the serialized payloads
+    /// * `inner` - serializer that encodes the body
+    ///
+    /// # Errors
+    ///
+    /// [`KafkaThreadpoolError::SchemaRegistry`] if the schema id
+    /// is not available
+    ///
+    pub async fn framed_serializer<S: PayloadSerializer>(
+        &self,
+        schema: &KafkaSchema,
+        inner: S,
+    ) -> Result<KafkaSchemaFramedSerializer<S>, KafkaThreadpoolError> {
+        let schema_id = self.get_schema_id(schema).await?;
+        Ok(KafkaSchemaFramedSerializer::new(
+            inner,
+            schema.schema_type,
+            schema_id,
+            schema.message_indexes.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// MockRegistry
+    ///
+    /// Local HTTP server that answers every request with the same
+    /// status and body and records each request line and body
+    ///
+    struct MockRegistry {
+        url: String,
+        requests: Arc<Mutex<Vec<(String, String)>>>,
+    }
+
+    impl MockRegistry {
+        async fn start(status: u16, body: &'static str) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let recorded = requests.clone();
+            tokio::spawn(async move {
+                loop {
+                    let (mut stream, _) = match listener.accept().await {
+                        Ok(conn) => conn,
+                        Err(_) => return,
+                    };
+                    let request = read_request(&mut stream).await;
+                    recorded.lock().unwrap().push(request);
+                    let response = format!(
+                        "HTTP/1.1 {status} MOCK\r\n\
+                        Content-Type: application/json\r\n\
+                        Content-Length: {}\r\n\
+                        Connection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    let _ = stream.shutdown().await;
+                }
+            });
+            MockRegistry { url, requests }
+        }
+
+        fn requests(&self) -> Vec<(String, String)> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    /// read_request
+    ///
+    /// Read one HTTP request and return its request line and body
+    ///
+    async fn read_request(
+        stream: &mut tokio::net::TcpStream,
+    ) -> (String, String) {
+        let mut buf: Vec<u8> = Vec::new();
+        let mut chunk = [0u8; 1024];
+        let header_end = loop {
+            let num_read = stream.read(&mut chunk).await.unwrap();
+            if num_read == 0 {
+                break buf.len();
+            }
+            buf.extend_from_slice(&chunk[..num_read]);
+            if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos + 4;
+            }
+        };
+        let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+        let content_length = head
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+            .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        while buf.len() < header_end + content_length {
+            let num_read = stream.read(&mut chunk).await.unwrap();
+            if num_read == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..num_read]);
+        }
+        let request_line = head.lines().next().unwrap_or_default().to_string();
+        let body = String::from_utf8_lossy(&buf[header_end..]).to_string();
+        (request_line, body)
+    }
+
+    fn build_client(
+        url: &str,
+        auto_register: bool,
+    ) -> KafkaSchemaRegistryClient {
+        let mut config = KafkaSchemaRegistryConfig {
+            url: url.to_string(),
+            auto_register,
+            ..Default::default()
+        };
+        config.static_ids.insert("orders-value".to_string(), 42);
+        KafkaSchemaRegistryClient::new(config).unwrap()
+    }
+
+    fn orders_schema() -> KafkaSchema {
+        KafkaSchema::new(
+            "orders-value",
+            KafkaSchemaType::Protobuf,
+            "syntax = \"proto3\"; message Order { string id = 1; }",
+        )
+    }
+
+    #[tokio::test]
+    async fn get_schema_id_registers_the_schema() {
+        let registry = MockRegistry::start(200, r#"{"id":7}"#).await;
+        let client = build_client(&registry.url, true);
+        assert_eq!(client.get_schema_id(&orders_schema()).await.unwrap(), 7);
+        let requests = registry.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].0,
+            "POST /subjects/orders-value/versions HTTP/1.1"
+        );
+        assert!(requests[0].1.contains(r#""schemaType":"PROTOBUF""#));
+    }
+
+    #[tokio::test]
+    async fn get_schema_id_looks_up_the_schema() {
+        let registry = MockRegistry::start(200, r#"{"id":8}"#).await;
+        let client = build_client(&registry.url, false);
+        assert_eq!(client.get_schema_id(&orders_schema()).await.unwrap(), 8);
+        let requests = registry.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].0, "POST /subjects/orders-value HTTP/1.1");
+    }
+
+    #[tokio::test]
+    async fn get_schema_id_uses_the_cache() {
+        let registry = MockRegistry::start(200, r#"{"id":9}"#).await;
+        let client = build_client(&registry.url, true);
+        let schema = orders_schema();
+        assert_eq!(client.get_schema_id(&schema).await.unwrap(), 9);
+        assert_eq!(client.clone().get_schema_id(&schema).await.unwrap(), 9);
+        assert_eq!(client.cached_schema_id(&schema), Some(9));
+        assert_eq!(registry.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn get_schema_id_falls_back_to_static_ids_on_server_errors() {
+        let registry =
+            MockRegistry::start(503, r#"{"error_code":50301}"#).await;
+        let client = build_client(&registry.url, true);
+        let schema = orders_schema();
+        assert_eq!(client.get_schema_id(&schema).await.unwrap(), 42);
+        // static ids are not cached so the registry is tried again
+        assert_eq!(client.cached_schema_id(&schema), None);
+        assert_eq!(client.get_schema_id(&schema).await.unwrap(), 42);
+        assert_eq!(registry.requests().len(), 2);
+        let unknown = KafkaSchema::new(
+            "unknown-value",
+            KafkaSchemaType::Avro,
+            r#"{"type":"string"}"#,
+        );
+        assert!(matches!(
+            client.get_schema_id(&unknown).await,
+            Err(KafkaThreadpoolError::SchemaRegistry { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn get_schema_id_returns_client_errors() {
+        let registry = MockRegistry::start(
+            409,
+            r#"{"error_code":409,"message":"incompatible schema"}"#,
+        )
+        .await;
+        let client = build_client(&registry.url, true);
+        match client.get_schema_id(&orders_schema()).await {
+            Err(KafkaThreadpoolError::SchemaRegistry { subject, reason }) => {
+                assert_eq!(subject, "orders-value");
+                assert!(reason.contains("409"));
+                assert!(reason.contains("incompatible schema"));
+            }
+            res => panic!("expected a schema registry error got {res:?}"),
+        }
+        assert_eq!(registry.requests().len(), 1);
+    }
+}
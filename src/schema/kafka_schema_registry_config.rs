@@ -0,0 +1,181 @@
+//! Static configuration for the
+//! [`KafkaSchemaRegistryClient`](crate::schema::kafka_schema_registry_client::KafkaSchemaRegistryClient)
+//! where the values are set programmatically or read from the
+//! ``KAFKA_SCHEMA_REGISTRY_*`` environment variables
+//!
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
+
+/// KafkaSchemaRegistryConfig
+///
+/// Schema registry connectivity and fallback values
+///
+/// * `url` - schema registry base url (empty means offline where
+/// only ``static_ids`` are used)
+/// * `username` - optional - basic auth username
+/// * `password` - optional - basic auth password
+/// * `timeout` - max time per registry request
+/// * `auto_register` - register new schemas (``true``) or only
+/// look up already-registered schemas (``false``)
+/// * `static_ids` - fallback ``subject`` to schema id map used
+/// when the registry is offline
+///
+#[derive(Clone)]
+pub struct KafkaSchemaRegistryConfig {
+    pub url: String,
+    pub username: String,
+    pub password: String,
+    pub timeout: Duration,
+    pub auto_register: bool,
+    pub static_ids: HashMap<String, u32>,
+}
+
+impl Default for KafkaSchemaRegistryConfig {
+    fn default() -> Self {
+        KafkaSchemaRegistryConfig {
+            url: "".to_string(),
+            username: "".to_string(),
+            password: "".to_string(),
+            timeout: Duration::from_secs(5),
+            auto_register: true,
+            static_ids: HashMap::new(),
+        }
+    }
+}
+
+/// parse_static_ids
+///
+/// Internal helper for converting a comma-delimited list of
+/// ``subject=id`` pairs into a ``HashMap``
+///
+/// # Arguments
+///
+/// * `value` - environment variable value
+///
+fn parse_static_ids(
+    value: &str,
+) -> Result<HashMap<String, u32>, KafkaThreadpoolError> {
+    let mut static_ids: HashMap<String, u32> = HashMap::new();
+    for pair in value.split(',').filter(|pair| !pair.trim().is_empty()) {
+        let parsed = pair.split_once('=').and_then(|(subject, id)| {
+            id.trim()
+                .parse::<u32>()
+                .ok()
+                .map(|id| (subject.trim().to_string(), id))
+        });
+        match parsed {
+            Some((subject, id)) if !subject.is_empty() => {
+                static_ids.insert(subject, id);
+            }
+            _ => {
+                return Err(KafkaThreadpoolError::config_invalid(
+                    "KAFKA_SCHEMA_REGISTRY_STATIC_IDS",
+                    &format!(
+                        "invalid entry={pair} \
+                        please use: subject=id,subject=id"
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(static_ids)
+}
+
+impl KafkaSchemaRegistryConfig {
+    /// new
+    ///
+    /// Build a [`KafkaSchemaRegistryConfig`] from the
+    /// ``KAFKA_SCHEMA_REGISTRY_*`` environment variables
+    ///
+    /// # Errors
+    ///
+    /// [`KafkaThreadpoolError::ConfigInvalid`] if an environment
+    /// variable is invalid
+    ///
+    pub fn new() -> Result<Self, KafkaThreadpoolError> {
+        let url = std::env::var("KAFKA_SCHEMA_REGISTRY_URL")
+            .unwrap_or_else(|_| "".to_string());
+        let username = std::env::var("KAFKA_SCHEMA_REGISTRY_USERNAME")
+            .unwrap_or_else(|_| "".to_string());
+        let password = std::env::var("KAFKA_SCHEMA_REGISTRY_PASSWORD")
+            .unwrap_or_else(|_| "".to_string());
+        let timeout_s = std::env::var("KAFKA_SCHEMA_REGISTRY_TIMEOUT_SEC")
+            .unwrap_or_else(|_| "5".to_string());
+        let auto_register_s =
+            std::env::var("KAFKA_SCHEMA_REGISTRY_AUTO_REGISTER")
+                .unwrap_or_else(|_| "true".to_string())
+                .to_lowercase();
+        let static_ids_s = std::env::var("KAFKA_SCHEMA_REGISTRY_STATIC_IDS")
+            .unwrap_or_else(|_| "".to_string());
+
+        let timeout = match timeout_s.parse::<f64>() {
+            Ok(val) if val >= 0.001 => Duration::from_secs_f64(val),
+            _ => {
+                return Err(KafkaThreadpoolError::config_invalid(
+                    "KAFKA_SCHEMA_REGISTRY_TIMEOUT_SEC",
+                    &format!(
+                        "invalid interval={timeout_s} \
+                        please set to a positive float between [0.001, inf]"
+                    ),
+                ));
+            }
+        };
+        Ok(KafkaSchemaRegistryConfig {
+            url: url.trim_end_matches('/').to_string(),
+            username,
+            password,
+            timeout,
+            auto_register: auto_register_s == "true" || auto_register_s == "1",
+            static_ids: parse_static_ids(&static_ids_s)?,
+        })
+    }
+
+    /// is_offline
+    ///
+    /// # Returns
+    ///
+    /// ``true`` if no registry url is set so only the static
+    /// schema ids are used
+    ///
+    pub fn is_offline(&self) -> bool {
+        self.url.is_empty()
+    }
+}
+
+impl std::fmt::Debug for KafkaSchemaRegistryConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "DEBUG KafkaSchemaRegistryConfig url={} \
+            username={} \
+            timeout={:?} \
+            auto_register={} \
+            static_ids={:?}",
+            self.url,
+            self.username,
+            self.timeout,
+            self.auto_register,
+            self.static_ids
+        )
+    }
+}
+
+impl std::fmt::Display for KafkaSchemaRegistryConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "KafkaSchemaRegistryConfig url={} \
+            username={} \
+            timeout={:?} \
+            auto_register={} \
+            static_ids={:?}",
+            self.url,
+            self.username,
+            self.timeout,
+            self.auto_register,
+            self.static_ids
+        )
+    }
+}
@@ -0,0 +1,62 @@
+//! enum for the schema formats supported by the schema registry
+//!
+
+/// KafkaSchemaType
+///
+/// Supported schema formats
+///
+/// - ``Avro`` - Apache Avro schema (default)
+/// - ``Protobuf`` - Protocol Buffers schema (framed payloads
+/// include the message indexes)
+/// - ``Json`` - JSON Schema
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KafkaSchemaType {
+    Avro,
+    Protobuf,
+    Json,
+}
+
+impl Default for KafkaSchemaType {
+    fn default() -> Self {
+        KafkaSchemaType::Avro
+    }
+}
+
+impl KafkaSchemaType {
+    /// as_registry_str
+    ///
+    /// # Returns
+    ///
+    /// the ``schemaType`` value for the schema registry HTTP API
+    ///
+    pub fn as_registry_str(&self) -> &'static str {
+        match self {
+            KafkaSchemaType::Avro => "AVRO",
+            KafkaSchemaType::Protobuf => "PROTOBUF",
+            KafkaSchemaType::Json => "JSON",
+        }
+    }
+}
+
+impl std::str::FromStr for KafkaSchemaType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "avro" => Ok(KafkaSchemaType::Avro),
+            "protobuf" | "proto" => Ok(KafkaSchemaType::Protobuf),
+            "json" => Ok(KafkaSchemaType::Json),
+            _ => Err(format!(
+                "unsupported schema type={s} \
+                please use: avro, protobuf or json"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for KafkaSchemaType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_registry_str())
+    }
+}
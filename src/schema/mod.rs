@@ -0,0 +1,52 @@
+//! Schema-registry-aware payload encoding with the Confluent wire
+//! format (requires the ``schema-registry`` feature):
+//!
+//! ```text
+//! | magic byte 0 | schema id (4 bytes big-endian) | [protobuf message indexes] | body |
+//! ```
+//!
+//! The
+//! [`KafkaSchemaRegistryClient`](crate::schema::kafka_schema_registry_client::KafkaSchemaRegistryClient)
+//! registers (or looks up) schemas with the schema registry HTTP
+//! API, caches the schema ids and falls back to a static
+//! ``subject=id`` map when the registry is offline. Payloads are
+//! framed before they are added to the work vec so worker threads
+//! never call the registry.
+//!
+//! Supported environment variables for
+//! [`KafkaSchemaRegistryConfig::new()`](crate::schema::kafka_schema_registry_config::KafkaSchemaRegistryConfig::new):
+//!
+//! | Environment Variable Name           | Purpose / Value                                |
+//! | ----------------------------------- | ---------------------------------------------- |
+//! | KAFKA_SCHEMA_REGISTRY_URL           | optional - schema registry base url (``http://localhost:8081``) - an empty url only uses the static schema ids |
+//! | KAFKA_SCHEMA_REGISTRY_USERNAME      | optional - basic auth username |
+//! | KAFKA_SCHEMA_REGISTRY_PASSWORD      | optional - basic auth password |
+//! | KAFKA_SCHEMA_REGISTRY_TIMEOUT_SEC   | optional - max number of seconds per registry request (defaults to ``5``) |
+//! | KAFKA_SCHEMA_REGISTRY_AUTO_REGISTER | optional - set to ``false`` to only look up already-registered schemas (defaults to ``true``) |
+//! | KAFKA_SCHEMA_REGISTRY_STATIC_IDS    | optional - comma-delimited ``subject=id`` fallback schema ids used when the registry is offline |
+//!
+//! Example for publishing an Avro record encoded by the caller:
+//!
+//! ```rust
+//! use kafka_threadpool::schema::kafka_schema::KafkaSchema;
+//! use kafka_threadpool::schema::kafka_schema_registry_client::KafkaSchemaRegistryClient;
+//! use kafka_threadpool::schema::kafka_schema_registry_config::KafkaSchemaRegistryConfig;
+//! use kafka_threadpool::schema::kafka_schema_type::KafkaSchemaType;
+//! let registry = KafkaSchemaRegistryClient::new(
+//!     KafkaSchemaRegistryConfig::new().unwrap()).unwrap();
+//! let schema = KafkaSchema::new(
+//!     "billing-value",
+//!     KafkaSchemaType::Avro,
+//!     &invoice_avro_schema_json);
+//! my_kafka_publisher
+//!     .add_schema_msg(&registry, &schema, "billing", "customer-1", vec![], &avro_body)
+//!     .await
+//!     .unwrap();
+//! ```
+//!
+pub mod frame_confluent_payload;
+pub mod kafka_schema;
+pub mod kafka_schema_framed_serializer;
+pub mod kafka_schema_registry_client;
+pub mod kafka_schema_registry_config;
+pub mod kafka_schema_type;
@@ -0,0 +1,35 @@
+//! CBOR [`PayloadSerializer`](crate::serialize::payload_serializer::PayloadSerializer)
+//! (requires the ``cbor`` feature)
+//!
+use serde::Serialize;
+
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
+use crate::serialize::payload_serializer::PayloadSerializer;
+
+/// CborSerializer
+///
+/// Encode payloads as CBOR (RFC 8949) with ``ciborium`` and the
+/// ``content-type``: ``application/cbor``
+///
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CborSerializer;
+
+impl PayloadSerializer for CborSerializer {
+    fn content_type(&self) -> &str {
+        "application/cbor"
+    }
+
+    fn serialize<T: Serialize + ?Sized>(
+        &self,
+        value: &T,
+    ) -> Result<Vec<u8>, KafkaThreadpoolError> {
+        let mut payload: Vec<u8> = Vec::new();
+        ciborium::ser::into_writer(value, &mut payload).map_err(|e| {
+            KafkaThreadpoolError::Serialization {
+                content_type: self.content_type().to_string(),
+                reason: e.to_string(),
+            }
+        })?;
+        Ok(payload)
+    }
+}
@@ -0,0 +1,33 @@
+//! JSON [`PayloadSerializer`](crate::serialize::payload_serializer::PayloadSerializer)
+//! (requires the ``json`` feature)
+//!
+use serde::Serialize;
+
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
+use crate::serialize::payload_serializer::PayloadSerializer;
+
+/// JsonSerializer
+///
+/// Encode payloads as compact JSON with ``serde_json`` and the
+/// ``content-type``: ``application/json``
+///
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonSerializer;
+
+impl PayloadSerializer for JsonSerializer {
+    fn content_type(&self) -> &str {
+        "application/json"
+    }
+
+    fn serialize<T: Serialize + ?Sized>(
+        &self,
+        value: &T,
+    ) -> Result<Vec<u8>, KafkaThreadpoolError> {
+        serde_json::to_vec(value).map_err(|e| {
+            KafkaThreadpoolError::Serialization {
+                content_type: self.content_type().to_string(),
+                reason: e.to_string(),
+            }
+        })
+    }
+}
@@ -0,0 +1,22 @@
+//! Typed payload serialization for publishing any
+//! ``serde::Serialize`` value with a consistent ``content-type``
+//! header (requires the ``serde`` feature):
+//!
+//! | Feature   | Serializer                | content-type          |
+//! | --------- | ------------------------- | --------------------- |
+//! | ``json``    | [`JsonSerializer`](crate::serialize::json_serializer::JsonSerializer)       | ``application/json``    |
+//! | ``msgpack`` | [`MsgPackSerializer`](crate::serialize::msgpack_serializer::MsgPackSerializer) | ``application/msgpack`` |
+//! | ``cbor``    | [`CborSerializer`](crate::serialize::cbor_serializer::CborSerializer)       | ``application/cbor``    |
+//!
+//! Custom encodings can implement the
+//! [`PayloadSerializer`](crate::serialize::payload_serializer::PayloadSerializer)
+//! trait.
+//!
+#[cfg(feature = "cbor")]
+pub mod cbor_serializer;
+#[cfg(feature = "json")]
+pub mod json_serializer;
+#[cfg(feature = "msgpack")]
+pub mod msgpack_serializer;
+pub mod payload_serializer;
+pub mod serialize_kafka_publish_message;
@@ -0,0 +1,37 @@
+//! MessagePack [`PayloadSerializer`](crate::serialize::payload_serializer::PayloadSerializer)
+//! (requires the ``msgpack`` feature)
+//!
+use serde::Serialize;
+
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
+use crate::serialize::payload_serializer::PayloadSerializer;
+
+/// MsgPackSerializer
+///
+/// Encode payloads as MessagePack with ``rmp-serde`` and the
+/// ``content-type``: ``application/msgpack``
+///
+/// Structs are encoded as maps with field names (not positional
+/// arrays) so consumers in other languages can decode them
+/// without the rust struct definition.
+///
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MsgPackSerializer;
+
+impl PayloadSerializer for MsgPackSerializer {
+    fn content_type(&self) -> &str {
+        "application/msgpack"
+    }
+
+    fn serialize<T: Serialize + ?Sized>(
+        &self,
+        value: &T,
+    ) -> Result<Vec<u8>, KafkaThreadpoolError> {
+        rmp_serde::to_vec_named(value).map_err(|e| {
+            KafkaThreadpoolError::Serialization {
+                content_type: self.content_type().to_string(),
+                reason: e.to_string(),
+            }
+        })
+    }
+}
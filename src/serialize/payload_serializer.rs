@@ -0,0 +1,71 @@
+//! Trait for encoding a typed value into the bytes of a
+//! [`KafkaPublishMessage`](crate::api::kafka_publish_message::KafkaPublishMessage)
+//! payload
+//!
+use serde::Serialize;
+
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
+
+/// CONTENT_TYPE_HEADER
+///
+/// Header name set on every serialized message so consumers can
+/// pick the matching deserializer
+///
+pub const CONTENT_TYPE_HEADER: &str = "content-type";
+
+/// PayloadSerializer
+///
+/// Pluggable payload encoding used by
+/// [`KafkaPublisher::add_serialized_msg`](crate::kafka_publisher::KafkaPublisher::add_serialized_msg)
+///
+/// # Examples
+///
+/// ```rust
+/// use serde::Serialize;
+/// use kafka_threadpool::api::kafka_threadpool_error::KafkaThreadpoolError;
+/// use kafka_threadpool::serialize::payload_serializer::PayloadSerializer;
+///
+/// struct DebugSerializer;
+///
+/// impl PayloadSerializer for DebugSerializer {
+///     fn content_type(&self) -> &str {
+///         "text/plain"
+///     }
+///
+///     fn serialize<T: Serialize + ?Sized>(
+///         &self,
+///         value: &T,
+///     ) -> Result<Vec<u8>, KafkaThreadpoolError> {
+///         // encode the value here
+///         Ok(Vec::new())
+///     }
+/// }
+/// ```
+///
+pub trait PayloadSerializer: Send + Sync {
+    /// content_type
+    ///
+    /// # Returns
+    ///
+    /// the MIME type stored in the ``content-type`` header
+    ///
+    fn content_type(&self) -> &str;
+
+    /// serialize
+    ///
+    /// Encode ``value`` into the message payload bytes
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - any ``serde::Serialize`` value
+    ///
+    /// # Errors
+    ///
+    /// [`KafkaThreadpoolError::Serialization`] if ``value``
+    /// cannot be encoded
+    ///
+    fn serialize<T: Serialize + ?Sized>(
+        &self,
+        value: &T,
+    ) -> Result<Vec<u8>, KafkaThreadpoolError>;
+}
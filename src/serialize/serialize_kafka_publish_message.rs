@@ -0,0 +1,99 @@
+//! helper for building a
+//! [`KafkaPublishMessage`](crate::api::kafka_publish_message::KafkaPublishMessage)
+//! from a typed value with a
+//! [`PayloadSerializer`](crate::serialize::payload_serializer::PayloadSerializer)
+//!
+use serde::Serialize;
+
+use crate::api::kafka_header::KafkaHeader;
+use crate::api::kafka_publish_message::KafkaPublishMessage;
+use crate::api::kafka_publish_message_type::KafkaPublishMessageType;
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
+use crate::serialize::payload_serializer::PayloadSerializer;
+use crate::serialize::payload_serializer::CONTENT_TYPE_HEADER;
+
+/// serialize_kafka_publish_message
+///
+/// Create a publishable kafka message where the payload is
+/// ``value`` encoded with ``serializer`` and the
+/// ``content-type`` header is set to
+/// ``serializer.content_type()`` (unless ``headers`` already
+/// has a ``content-type``)
+///
+/// # Arguments
+///
+/// * `serializer` - payload encoding
+/// * `msg_type` - request type of message [`KafkaPublishMessageType`]
+/// (``Data`` or ``Sensitive``)
+/// * `topic` - kafka topic to publish the message into
+/// * `key` - kafka partition key (an empty ``key`` publishes
+/// a null key)
+/// * `headers` - ordered
+/// [`KafkaHeader`](crate::api::kafka_header::KafkaHeader) list
+/// for the kafka message
+/// * `value` - any ``serde::Serialize`` value for the payload
+///
+/// # Errors
+///
+/// [`KafkaThreadpoolError::Serialization`] if ``value`` cannot
+/// be encoded
+///
+/// # Examples
+///
+/// ```rust
+/// use serde::Serialize;
+/// use kafka_threadpool::api::kafka_publish_message_type::KafkaPublishMessageType;
+/// use kafka_threadpool::serialize::json_serializer::JsonSerializer;
+/// use kafka_threadpool::serialize::serialize_kafka_publish_message::serialize_kafka_publish_message;
+///
+/// #[derive(Serialize)]
+/// struct Invoice {
+///     id: u64,
+///     amount_cents: i64,
+/// }
+///
+/// let msg = serialize_kafka_publish_message(
+///     &JsonSerializer,
+///     KafkaPublishMessageType::Data,
+///     "billing",
+///     "customer-1",
+///     vec![],
+///     &Invoice { id: 1, amount_cents: 2500 }).unwrap();
+/// println!("{msg}");
+/// ```
+///
+pub fn serialize_kafka_publish_message<S, T>(
+    serializer: &S,
+    msg_type: KafkaPublishMessageType,
+    topic: &str,
+    key: &str,
+    mut headers: Vec<KafkaHeader>,
+    value: &T,
+) -> Result<KafkaPublishMessage, KafkaThreadpoolError>
+where
+    S: PayloadSerializer,
+    T: Serialize + ?Sized,
+{
+    let payload = serializer.serialize(value)?;
+    if !headers
+        .iter()
+        .any(|header| header.key.eq_ignore_ascii_case(CONTENT_TYPE_HEADER))
+    {
+        headers.push(KafkaHeader::from_str_value(
+            CONTENT_TYPE_HEADER,
+            serializer.content_type(),
+        ));
+    }
+    let key = if key.is_empty() {
+        None
+    } else {
+        Some(key.as_bytes().to_vec())
+    };
+    Ok(KafkaPublishMessage::new_from_bytes(
+        msg_type,
+        topic,
+        key,
+        headers,
+        Some(payload),
+    ))
+}
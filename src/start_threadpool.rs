@@ -1,10 +1,12 @@
 //! Start the threadpool and return a
 //! [`KafkaPublisher`](crate::kafka_publisher::KafkaPublisher)
 //!
+use log::error;
 use log::info;
 use log::trace;
 
 use crate::api::build_kafka_client_config::build_kafka_client_config;
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
 use crate::kafka_publisher::KafkaPublisher;
 use crate::pool::start_threads_from_config::start_threads_from_config;
 
@@ -15,15 +17,22 @@ use crate::pool::start_threads_from_config::start_threads_from_config;
 /// * `label` - optional tracking log label
 /// (``ktp`` is the default if not set)
 ///
+/// # Errors
+///
+/// [`KafkaThreadpoolError`] if the environment configuration is
+/// invalid or the worker threads cannot start
+///
 /// # Examples
 ///
 /// ```rust
 /// use kafka_threadpool::start_threadpool::start_threadpool;
 /// let log_label = "ktp";
-/// let kafka_publisher = start_threadpool(log_label).await;
+/// let kafka_publisher = start_threadpool(Some(log_label)).await.unwrap();
 /// ```
 ///
-pub async fn start_threadpool(label: Option<&str>) -> KafkaPublisher {
+pub async fn start_threadpool(
+    label: Option<&str>,
+) -> Result<KafkaPublisher, KafkaThreadpoolError> {
     trace!("start_threadpool - building config");
     let ll =
         std::env::var("KAFKA_LOG_LABEL").unwrap_or_else(|_| "ktp".to_string());
@@ -31,25 +40,25 @@ pub async fn start_threadpool(label: Option<&str>) -> KafkaPublisher {
         Some(in_label) => in_label,
         None => &ll,
     };
-    let config = build_kafka_client_config(use_label);
+    let config = build_kafka_client_config(use_label)?;
     if config.is_enabled {
         trace!("start_threadpool - starting threads");
+        let config_details = config.to_string();
         match start_threads_from_config(config).await {
             Ok(kafka_publisher) => {
                 info!(
                     "{use_label} - started {} kafka publish threads",
                     kafka_publisher.config.num_threads
                 );
-                kafka_publisher
+                Ok(kafka_publisher)
             }
             Err(e) => {
-                // rebuild the config after using (move-ing) it
-                let err_config = build_kafka_client_config(use_label);
-                panic!(
+                error!(
                     "{use_label} \
                     failed to kafka publish threads with start_threads_from_config \
-                    config={} err={e} - stopping",
-                    err_config);
+                    config={config_details} err={e} - stopping"
+                );
+                Err(e)
             }
         }
     } else {
@@ -57,6 +66,6 @@ pub async fn start_threadpool(label: Option<&str>) -> KafkaPublisher {
             "kafka threadpool disabled KAFKA_ENABLED={}",
             config.is_enabled
         );
-        KafkaPublisher::new()
+        start_threads_from_config(config).await
     }
 }
@@ -0,0 +1,73 @@
+//! class definition for the per-broker statistics in a
+//! [`KafkaProducerStats`](crate::stats::kafka_producer_stats::KafkaProducerStats)
+//! snapshot
+//!
+use rdkafka::statistics::Broker;
+
+/// KafkaBrokerStats
+///
+/// Connection and request statistics for one broker
+///
+/// * `name` - broker name (``host:port/nodeid``)
+/// * `nodeid` - broker node id (``-1`` for bootstrap brokers)
+/// * `state` - connection state (``UP``, ``DOWN``, ``INIT``, etc.)
+/// * `outbuf_cnt` - requests waiting to be sent
+/// * `outbuf_msg_cnt` - messages waiting to be sent
+/// * `waitresp_cnt` - requests in flight waiting for a response
+/// * `waitresp_msg_cnt` - messages in flight waiting for a response
+/// * `txretries` - number of request retries
+/// * `req_timeouts` - number of timed out requests
+/// * `rtt_avg_us` - optional - average round-trip time in
+/// microseconds
+/// * `rtt_p99_us` - optional - 99th percentile round-trip time in
+/// microseconds
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KafkaBrokerStats {
+    pub name: String,
+    pub nodeid: i32,
+    pub state: String,
+    pub outbuf_cnt: i64,
+    pub outbuf_msg_cnt: i64,
+    pub waitresp_cnt: i64,
+    pub waitresp_msg_cnt: i64,
+    pub txretries: u64,
+    pub req_timeouts: u64,
+    pub rtt_avg_us: Option<i64>,
+    pub rtt_p99_us: Option<i64>,
+}
+
+impl From<&Broker> for KafkaBrokerStats {
+    fn from(broker: &Broker) -> Self {
+        KafkaBrokerStats {
+            name: broker.name.clone(),
+            nodeid: broker.nodeid,
+            state: broker.state.clone(),
+            outbuf_cnt: broker.outbuf_cnt,
+            outbuf_msg_cnt: broker.outbuf_msg_cnt,
+            waitresp_cnt: broker.waitresp_cnt,
+            waitresp_msg_cnt: broker.waitresp_msg_cnt,
+            txretries: broker.txretries,
+            req_timeouts: broker.req_timeouts,
+            rtt_avg_us: broker.rtt.as_ref().map(|rtt| rtt.avg),
+            rtt_p99_us: broker.rtt.as_ref().map(|rtt| rtt.p99),
+        }
+    }
+}
+
+impl std::fmt::Display for KafkaBrokerStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "broker={} state={} outbuf={} waitresp={} \
+            retries={} timeouts={} rtt_avg_us={:?}",
+            self.name,
+            self.state,
+            self.outbuf_msg_cnt,
+            self.waitresp_msg_cnt,
+            self.txretries,
+            self.req_timeouts,
+            self.rtt_avg_us
+        )
+    }
+}
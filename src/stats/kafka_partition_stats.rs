@@ -0,0 +1,72 @@
+//! class definition for the per-partition statistics in a
+//! [`KafkaProducerStats`](crate::stats::kafka_producer_stats::KafkaProducerStats)
+//! snapshot
+//!
+use rdkafka::statistics::Partition;
+
+/// KafkaPartitionStats
+///
+/// Producer queue and throughput statistics for one topic
+/// partition
+///
+/// * `topic` - kafka topic
+/// * `partition` - partition id
+/// * `leader` - leader broker id
+/// * `msgq_cnt` - messages waiting in the partition queue
+/// * `xmit_msgq_cnt` - messages ready to be sent to the broker
+/// * `msgs_inflight` - messages in flight to the broker
+/// * `txmsgs` - number of messages sent
+/// * `txbytes` - number of message bytes sent
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KafkaPartitionStats {
+    pub topic: String,
+    pub partition: i32,
+    pub leader: i32,
+    pub msgq_cnt: i64,
+    pub xmit_msgq_cnt: i64,
+    pub msgs_inflight: i64,
+    pub txmsgs: u64,
+    pub txbytes: u64,
+}
+
+impl KafkaPartitionStats {
+    /// from_partition
+    ///
+    /// Convert the ``librdkafka`` partition statistics
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - kafka topic for the partition
+    /// * `partition` - ``rdkafka`` partition statistics
+    ///
+    pub fn from_partition(topic: &str, partition: &Partition) -> Self {
+        KafkaPartitionStats {
+            topic: topic.to_string(),
+            partition: partition.partition,
+            leader: partition.leader,
+            msgq_cnt: partition.msgq_cnt,
+            xmit_msgq_cnt: partition.xmit_msgq_cnt,
+            msgs_inflight: partition.msgs_inflight,
+            txmsgs: partition.txmsgs,
+            txbytes: partition.txbytes,
+        }
+    }
+}
+
+impl std::fmt::Display for KafkaPartitionStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "topic={} partition={} leader={} msgq={} xmit_msgq={} \
+            inflight={} txmsgs={}",
+            self.topic,
+            self.partition,
+            self.leader,
+            self.msgq_cnt,
+            self.xmit_msgq_cnt,
+            self.msgs_inflight,
+            self.txmsgs
+        )
+    }
+}
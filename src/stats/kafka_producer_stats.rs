@@ -0,0 +1,112 @@
+//! class definition for a worker thread's producer statistics
+//! snapshot
+//!
+use rdkafka::statistics::Statistics;
+
+use crate::stats::kafka_broker_stats::KafkaBrokerStats;
+use crate::stats::kafka_partition_stats::KafkaPartitionStats;
+
+/// KafkaProducerStats
+///
+/// Latest ``librdkafka`` statistics for one worker thread's
+/// producer
+///
+/// * `worker_label` - worker thread log label (``ktp-tid-1``)
+/// * `client_name` - ``librdkafka`` client instance name
+/// * `time` - when the statistics were emitted in seconds since
+/// the unix epoch
+/// * `msg_cnt` - messages waiting in the producer queues
+/// * `msg_size` - bytes waiting in the producer queues
+/// * `msg_max` - max number of messages allowed in the producer
+/// queues
+/// * `txmsgs` - number of messages sent to the brokers
+/// * `txmsg_bytes` - number of message bytes sent to the brokers
+/// * `brokers` - per-broker statistics sorted by broker name
+/// * `partitions` - per-partition statistics sorted by topic and
+/// partition (the internal unassigned partition is skipped)
+/// * `raw` - the complete ``rdkafka`` statistics
+///
+#[derive(Debug, Clone, Default)]
+pub struct KafkaProducerStats {
+    pub worker_label: String,
+    pub client_name: String,
+    pub time: i64,
+    pub msg_cnt: u64,
+    pub msg_size: u64,
+    pub msg_max: u64,
+    pub txmsgs: i64,
+    pub txmsg_bytes: i64,
+    pub brokers: Vec<KafkaBrokerStats>,
+    pub partitions: Vec<KafkaPartitionStats>,
+    pub raw: Statistics,
+}
+
+impl KafkaProducerStats {
+    /// from_statistics
+    ///
+    /// Convert the ``librdkafka`` statistics for a worker
+    ///
+    /// # Arguments
+    ///
+    /// * `worker_label` - worker thread log label
+    /// * `statistics` - parsed ``rdkafka`` statistics
+    ///
+    pub fn from_statistics(worker_label: &str, statistics: Statistics) -> Self {
+        let mut brokers: Vec<KafkaBrokerStats> = statistics
+            .brokers
+            .values()
+            .map(KafkaBrokerStats::from)
+            .collect();
+        brokers.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut partitions: Vec<KafkaPartitionStats> = statistics
+            .topics
+            .iter()
+            .flat_map(|(topic, topic_stats)| {
+                topic_stats
+                    .partitions
+                    .values()
+                    // -1 is the internal unassigned partition
+                    .filter(|partition| partition.partition >= 0)
+                    .map(move |partition| {
+                        KafkaPartitionStats::from_partition(topic, partition)
+                    })
+            })
+            .collect();
+        partitions.sort_by(|a, b| {
+            a.topic.cmp(&b.topic).then(a.partition.cmp(&b.partition))
+        });
+        KafkaProducerStats {
+            worker_label: worker_label.to_string(),
+            client_name: statistics.name.clone(),
+            time: statistics.time,
+            msg_cnt: statistics.msg_cnt,
+            msg_size: statistics.msg_size,
+            msg_max: statistics.msg_max,
+            txmsgs: statistics.txmsgs,
+            txmsg_bytes: statistics.txmsg_bytes,
+            brokers,
+            partitions,
+            raw: statistics,
+        }
+    }
+}
+
+impl std::fmt::Display for KafkaProducerStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "KafkaProducerStats worker={} client={} time={} \
+            msg_cnt={} msg_size={} txmsgs={} txmsg_bytes={} \
+            brokers={} partitions={}",
+            self.worker_label,
+            self.client_name,
+            self.time,
+            self.msg_cnt,
+            self.msg_size,
+            self.txmsgs,
+            self.txmsg_bytes,
+            self.brokers.len(),
+            self.partitions.len()
+        )
+    }
+}
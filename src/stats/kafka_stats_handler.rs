@@ -0,0 +1,41 @@
+//! trait for user callbacks that receive every
+//! [`KafkaProducerStats`](crate::stats::kafka_producer_stats::KafkaProducerStats)
+//! snapshot
+//!
+use crate::stats::kafka_producer_stats::KafkaProducerStats;
+
+/// KafkaStatsHandler
+///
+/// Implement this trait (or pass a closure) to receive each
+/// worker's statistics every ``statistics.interval.ms``.
+/// ``librdkafka`` calls the handler from its background thread so
+/// it should not block for long.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use kafka_threadpool::config::kafka_client_config::KafkaClientConfig;
+/// use kafka_threadpool::stats::kafka_producer_stats::KafkaProducerStats;
+/// let config = KafkaClientConfig::builder()
+///     .brokers(vec!["localhost:9092".to_string()])
+///     .statistics_interval_ms(5000)
+///     .stats_handler(Arc::new(|stats: &KafkaProducerStats| {
+///         println!("{stats}");
+///     }))
+///     .build()
+///     .unwrap();
+/// ```
+///
+pub trait KafkaStatsHandler: Send + Sync {
+    fn handle_stats(&self, stats: &KafkaProducerStats);
+}
+
+impl<F> KafkaStatsHandler for F
+where
+    F: Fn(&KafkaProducerStats) + Send + Sync,
+{
+    fn handle_stats(&self, stats: &KafkaProducerStats) {
+        self(stats)
+    }
+}
@@ -0,0 +1,59 @@
+//! Thread-safe store for the latest
+//! [`KafkaProducerStats`](crate::stats::kafka_producer_stats::KafkaProducerStats)
+//! snapshot per worker thread
+//!
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::stats::kafka_producer_stats::KafkaProducerStats;
+
+/// KafkaStatsStore
+///
+/// Latest statistics snapshot keyed by the worker thread log label
+///
+#[derive(Debug, Default)]
+pub struct KafkaStatsStore {
+    snapshots: RwLock<HashMap<String, KafkaProducerStats>>,
+}
+
+impl KafkaStatsStore {
+    /// new
+    ///
+    /// Create an empty store
+    ///
+    pub fn new() -> Self {
+        KafkaStatsStore {
+            snapshots: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// update
+    ///
+    /// Replace the worker's snapshot
+    ///
+    /// # Arguments
+    ///
+    /// * `stats` - latest statistics for ``stats.worker_label``
+    ///
+    pub fn update(&self, stats: KafkaProducerStats) {
+        if let Ok(mut snapshots) = self.snapshots.write() {
+            snapshots.insert(stats.worker_label.clone(), stats);
+        }
+    }
+
+    /// snapshot
+    ///
+    /// # Returns
+    ///
+    /// ``Vec<KafkaProducerStats>`` with the latest statistics for
+    /// each worker sorted by the worker thread log label
+    ///
+    pub fn snapshot(&self) -> Vec<KafkaProducerStats> {
+        let mut stats: Vec<KafkaProducerStats> = match self.snapshots.read() {
+            Ok(snapshots) => snapshots.values().cloned().collect(),
+            Err(_) => Vec::new(),
+        };
+        stats.sort_by(|a, b| a.worker_label.cmp(&b.worker_label));
+        stats
+    }
+}
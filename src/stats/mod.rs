@@ -0,0 +1,17 @@
+//! Structured ``librdkafka`` statistics for each worker thread's
+//! producer
+//!
+//! Set ``KAFKA_STATISTICS_INTERVAL_MS`` (or
+//! [`KafkaClientConfigBuilder::statistics_interval_ms`](crate::config::kafka_client_config_builder::KafkaClientConfigBuilder::statistics_interval_ms))
+//! to have ``librdkafka`` emit statistics. The latest
+//! [`KafkaProducerStats`](crate::stats::kafka_producer_stats::KafkaProducerStats)
+//! snapshot for each worker is available with
+//! [`KafkaPublisher::stats()`](crate::kafka_publisher::KafkaPublisher::stats)
+//! and every snapshot is passed to the optional
+//! [`KafkaStatsHandler`](crate::stats::kafka_stats_handler::KafkaStatsHandler).
+//!
+pub mod kafka_broker_stats;
+pub mod kafka_partition_stats;
+pub mod kafka_producer_stats;
+pub mod kafka_stats_handler;
+pub mod kafka_stats_store;
@@ -2,24 +2,52 @@
 //! function is the thread context state machine.
 //!
 use std::sync::Arc;
-use std::sync::Mutex;
 
 use log::error;
 use log::info;
 use log::trace;
 
-use rdkafka::message::OwnedHeaders;
+use rdkafka::client::ClientContext;
+use rdkafka::producer::FutureProducer;
+use rdkafka::producer::Producer;
+use rdkafka::util::Timeout;
 
-use crate::api::add_messages_to_locked_work_vec::add_messages_to_locked_work_vec;
-use crate::api::drain_messages_from_locked_work_vec::drain_messages_from_locked_work_vec;
-use crate::api::get_kafka_consumer::get_kafka_consumer;
-use crate::api::get_kafka_producer::get_kafka_producer;
+use crate::api::build_rdkafka_client_config::build_kafka_worker_context;
+use crate::api::get_kafka_producer::get_kafka_producer_with_context;
+use crate::api::get_kafka_transactional_producer::get_kafka_transactional_producer;
+use crate::api::kafka_client_context::KafkaClientContext;
+use crate::api::kafka_delivery_error::KafkaDeliveryError;
+use crate::api::kafka_oauth_client_context::KafkaOAuthClientContext;
+use crate::api::kafka_ordering_mode::KafkaOrderingMode;
 use crate::api::kafka_publish_message::KafkaPublishMessage;
 use crate::api::kafka_publish_message_type::KafkaPublishMessageType;
+use crate::api::kafka_threadpool_error::KafkaThreadpoolError;
 use crate::config::kafka_client_config::KafkaClientConfig;
-use crate::metadata::get_kafka_metadata::get_kafka_metadata;
-use crate::msg::publish_message::convert_hashmap_headers_to_ownedheaders;
-use crate::msg::publish_message::publish_message;
+use crate::health::kafka_worker_state::KafkaWorkerState;
+use crate::metadata::get_kafka_metadata::get_kafka_metadata_from_config;
+use crate::metadata::kafka_cluster_metadata::KafkaClusterMetadata;
+use crate::metadata::log_kafka_cluster_metadata::log_kafka_cluster_metadata;
+#[cfg(feature = "metrics")]
+use crate::metrics::record_metrics::record_abandoned;
+#[cfg(feature = "metrics")]
+use crate::metrics::record_metrics::record_failed;
+#[cfg(feature = "metrics")]
+use crate::metrics::record_metrics::set_queue_depth;
+use crate::msg::publish_message_batch::publish_message_batch;
+use crate::msg::publish_transaction::publish_transaction;
+use crate::msg::publish_transaction::KAFKA_TRANSACTION_TIMEOUT;
+use crate::pool::kafka_pool_state::KafkaPoolState;
+use crate::pool::kafka_work_queues::KafkaWorkQueues;
+
+/// is_publishable
+///
+/// Internal helper for finding the ``Data`` and ``Sensitive``
+/// messages that are published to kafka
+///
+fn is_publishable(msg: &KafkaPublishMessage) -> bool {
+    msg.msg_type == KafkaPublishMessageType::Data
+        || msg.msg_type == KafkaPublishMessageType::Sensitive
+}
 
 /// thread_process_messages_handler
 ///
@@ -30,18 +58,235 @@ use crate::msg::publish_message::publish_message;
 /// * `cur_thread_num` - thread counter assigned by
 /// [`start_threads_from_config`]
 /// * `config` - initialized [`KafkaClientConfig`] for this thread
-/// * `lockable_work_vec` - shared work vec of
-/// [`KafkaPublishMessage`] messages to process within a lockable
-/// [`Arc<Mutex<lockable_work_vec>>`] thread-safe object
+/// * `work_queues` - shared [`KafkaWorkQueues`] with the lockable
+/// work vecs of [`KafkaPublishMessage`] messages to process
+/// * `pool_state` - shared [`KafkaPoolState`] signals for
+/// waking idle worker threads and callers blocked on a full
+/// work vec
+///
+/// Once the thread exits, the next running worker thread drains
+/// its work vec. The last worker thread to exit fails any queued
+/// messages unless the threadpool is shutting down (the shutdown
+/// drops them).
 ///
 pub async fn thread_process_messages_handler(
     cur_thread_num: u8,
     config: KafkaClientConfig,
-    lockable_work_vec: Arc<Mutex<Vec<KafkaPublishMessage>>>,
+    work_queues: Arc<KafkaWorkQueues>,
+    pool_state: Arc<KafkaPoolState>,
 ) {
+    let label = config.label.clone();
+    let log_label = format!("{label}-tid-{}", cur_thread_num + 1);
+    // only install the OAUTHBEARER token refresh callback for a
+    // token provider so librdkafka can handle OAUTHBEARER otherwise
+    match config.oauth_token_provider.clone() {
+        Some(token_provider) => {
+            process_messages(
+                cur_thread_num,
+                config,
+                work_queues.clone(),
+                pool_state.clone(),
+                move |context| {
+                    KafkaOAuthClientContext::new(
+                        context,
+                        token_provider.clone(),
+                    )
+                },
+            )
+            .await
+        }
+        None => {
+            process_messages(
+                cur_thread_num,
+                config,
+                work_queues.clone(),
+                pool_state.clone(),
+                |context| context,
+            )
+            .await
+        }
+    }
+    if work_queues.mark_exited(cur_thread_num) && !pool_state.is_shutting_down()
+    {
+        fail_queued_msgs(&label, &log_label, &work_queues, &pool_state);
+    }
+    // wake the worker thread that takes over this thread's work vec
+    // and any callers blocked on a full work vec
+    pool_state.work_available.notify_waiters();
+    pool_state.space_available.notify_waiters();
+}
+
+/// fail_queued_msgs
+///
+/// Internal helper for failing every queued message once no worker
+/// thread is left to publish it
+///
+/// # Arguments
+///
+/// * `label` - threadpool log label for the metrics
+/// * `log_label` - worker thread log label
+/// * `work_queues` - shared [`KafkaWorkQueues`]
+/// * `pool_state` - shared [`KafkaPoolState`] for the counters
+///
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+fn fail_queued_msgs(
+    label: &str,
+    log_label: &str,
+    work_queues: &KafkaWorkQueues,
+    pool_state: &KafkaPoolState,
+) {
+    let msgs = work_queues.drain_all();
+    if msgs.is_empty() {
+        return;
+    }
+    error!(
+        "{log_label} - every worker thread exited - \
+        failing queued msgs={}",
+        msgs.len()
+    );
+    let err = KafkaDeliveryError::Broker {
+        code: None,
+        reason: "every worker thread exited".to_string(),
+    };
+    for msg in msgs {
+        if let Some(delivery) = &msg.delivery {
+            delivery.notify(Err(err.clone()));
+        }
+        if is_publishable(&msg) {
+            pool_state.record_failed();
+            #[cfg(feature = "metrics")]
+            record_failed(label, log_label, &msg.topic);
+        }
+        if let Some(transaction) = &msg.transaction {
+            for txn_msg in transaction.msgs.iter() {
+                if let Some(delivery) = &txn_msg.delivery {
+                    delivery.notify(Err(err.clone()));
+                }
+                pool_state.record_failed();
+                #[cfg(feature = "metrics")]
+                record_failed(label, log_label, &txn_msg.topic);
+            }
+            if let Some(notifier) = &transaction.notifier {
+                notifier.notify(Err(err.clone()));
+            }
+        }
+    }
+    #[cfg(feature = "metrics")]
+    set_queue_depth(label, 0);
+}
+
+/// create_txn_producer
+///
+/// Internal helper for creating the worker thread's transactional
+/// producer without blocking the executor
+///
+/// # Arguments
+///
+/// * `cur_thread_num` - thread counter assigned by
+/// [`start_threads_from_config`]
+/// * `config` - initialized [`KafkaClientConfig`] for this thread
+/// * `context` - client context for the producer
+///
+/// # Errors
+///
+/// [`KafkaThreadpoolError::Broker`] if the producer cannot be
+/// created or the transactions cannot be initialized
+///
+async fn create_txn_producer<C: ClientContext + 'static>(
+    cur_thread_num: u8,
+    config: &KafkaClientConfig,
+    context: C,
+) -> Result<FutureProducer<C>, KafkaThreadpoolError> {
+    let txn_config = config.clone();
+    match tokio::task::spawn_blocking(move || {
+        get_kafka_transactional_producer(
+            &txn_config,
+            cur_thread_num,
+            context,
+            KAFKA_TRANSACTION_TIMEOUT,
+        )
+    })
+    .await
+    {
+        Ok(res) => res,
+        Err(e) => Err(KafkaThreadpoolError::Broker {
+            code: None,
+            reason: e.to_string(),
+        }),
+    }
+}
+
+/// get_metadata
+///
+/// Internal helper for fetching the cluster metadata on the
+/// blocking threadpool so the metadata and watermarks calls do not
+/// block the executor
+///
+/// # Arguments
+///
+/// * `config` - initialized [`KafkaClientConfig`] for this thread
+/// * `fetch_offsets` - count the messages in each partition
+/// * `topic` - If set, only fetch the metadata for this topic
+///
+/// # Errors
+///
+/// [`KafkaThreadpoolError::Broker`] if the consumer cannot be
+/// created or the metadata cannot be fetched
+///
+async fn get_metadata(
+    config: &KafkaClientConfig,
+    fetch_offsets: bool,
+    topic: Option<String>,
+) -> Result<KafkaClusterMetadata, KafkaThreadpoolError> {
+    let metadata_config = config.clone();
+    match tokio::task::spawn_blocking(move || {
+        get_kafka_metadata_from_config(
+            &metadata_config,
+            fetch_offsets,
+            topic.as_deref(),
+        )
+    })
+    .await
+    {
+        Ok(res) => res,
+        Err(e) => Err(KafkaThreadpoolError::Broker {
+            code: None,
+            reason: e.to_string(),
+        }),
+    }
+}
+
+/// process_messages
+///
+/// Internal state machine for [`thread_process_messages_handler`]
+/// with producers that use the client context from
+/// ``wrap_context``
+///
+/// # Arguments
+///
+/// * `cur_thread_num` - thread counter assigned by
+/// [`start_threads_from_config`]
+/// * `config` - initialized [`KafkaClientConfig`] for this thread
+/// * `work_queues` - shared [`KafkaWorkQueues`] to drain
+/// * `pool_state` - shared [`KafkaPoolState`] for the threadpool
+/// * `wrap_context` - converts the worker's [`KafkaClientContext`]
+/// into the producers' client context
+///
+async fn process_messages<C, F>(
+    cur_thread_num: u8,
+    config: KafkaClientConfig,
+    work_queues: Arc<KafkaWorkQueues>,
+    pool_state: Arc<KafkaPoolState>,
+    wrap_context: F,
+) where
+    C: ClientContext + 'static,
+    F: Fn(KafkaClientContext) -> C + Send + Sync,
+{
     // THREAD CONTEXT - start
-    let mut work_vec: Vec<KafkaPublishMessage> = Vec::with_capacity(20);
+    let mut work_vec: Vec<KafkaPublishMessage> =
+        Vec::with_capacity(config.publish_batch_size);
     let log_label = format!("{}-tid-{}", config.label, cur_thread_num + 1);
+    pool_state.health.register_worker(&log_label);
     // connect to the kafka cluster before starting
     if config.broker_list.is_empty() {
         error!(
@@ -49,6 +294,9 @@ pub async fn thread_process_messages_handler(
             no brokers to connect to KAFKA_BROKERS={:?} - stopping thread",
             config.broker_list
         );
+        pool_state
+            .health
+            .set_state(&log_label, KafkaWorkerState::Exited);
         return;
     }
     if config.broker_list[0].is_empty() {
@@ -57,6 +305,9 @@ pub async fn thread_process_messages_handler(
             no brokers to connect to KAFKA_BROKERS={:?} - stopping thread",
             config.broker_list
         );
+        pool_state
+            .health
+            .set_state(&log_label, KafkaWorkerState::Exited);
         return;
     }
     if cur_thread_num == 0 {
@@ -72,152 +323,341 @@ pub async fn thread_process_messages_handler(
             work_vec.capacity()
         );
     }
-    let producer = get_kafka_producer(&config);
+    let producer = match get_kafka_producer_with_context(
+        &config,
+        wrap_context(build_kafka_worker_context(
+            &config,
+            &log_label,
+            &log_label,
+            &pool_state,
+        )),
+    ) {
+        Ok(producer) => producer,
+        Err(e) => {
+            error!(
+                "{log_label} - \
+                failed to create producer with err={e} - stopping thread"
+            );
+            pool_state
+                .health
+                .set_state(&log_label, KafkaWorkerState::Exited);
+            return;
+        }
+    };
+    // transactions use a dedicated producer because a transactional
+    // producer cannot publish outside of a transaction
+    let mut txn_producer = if config.transactional_id_prefix.is_empty() {
+        None
+    } else {
+        match create_txn_producer(
+            cur_thread_num,
+            &config,
+            wrap_context(build_kafka_worker_context(
+                &config,
+                &format!("{log_label}-txn"),
+                &log_label,
+                &pool_state,
+            )),
+        )
+        .await
+        {
+            Ok(txn_producer) => Some(txn_producer),
+            Err(e) => {
+                error!(
+                    "{log_label} - \
+                    failed to create transactional producer with err={e} \
+                    - stopping thread"
+                );
+                pool_state
+                    .health
+                    .set_state(&log_label, KafkaWorkerState::Exited);
+                return;
+            }
+        }
+    };
     trace!("{log_label} - start");
+    // set when the thread cannot replace a failed transactional
+    // producer
+    let mut stop_thread = false;
     // In a loop, read data from the socket and write the data back.
     loop {
-        let mut should_shutdown = false;
-        work_vec = drain_messages_from_locked_work_vec(&lockable_work_vec);
+        // register for the wakeup before checking for work so
+        // messages added between the drain and the wait are not missed
+        let work_available = pool_state.work_available.notified();
+        // with an ordering mode this only drains the work vecs of
+        // this thread's shards so each key (or partition) is
+        // published in enqueue order
+        work_vec = work_queues.drain(cur_thread_num, config.publish_batch_size);
         if work_vec.is_empty() {
+            // everything queued for this thread before the shutdown
+            // is published
+            if pool_state.is_shutting_down() {
+                trace!("{log_label} - shutting down with empty work vec");
+                break;
+            }
             trace!("{log_label} - idle");
-            std::thread::sleep(std::time::Duration::from_millis(
-                config.idle_sleep_sec,
-            ));
+            pool_state
+                .health
+                .set_state(&log_label, KafkaWorkerState::Idle);
+            // wait without blocking the executor until the publisher
+            // adds messages (the idle interval is only a fallback for
+            // messages added directly to the lockable work vec)
+            let _ = tokio::time::timeout(
+                std::time::Duration::from_millis(config.idle_sleep_sec),
+                work_available,
+            )
+            .await;
             continue;
-        } else {
-            trace!("{log_label} - processing {} msgs", work_vec.len());
-            // publish the messages with a retry timer
-            while !work_vec.is_empty() {
-                let msg = work_vec.remove(0);
-                if msg.msg_type == KafkaPublishMessageType::Shutdown {
-                    should_shutdown = true;
-                    // requeue shutdown message for other threads
-                    let requeue_vec: Vec<KafkaPublishMessage> =
-                        vec![msg.clone()];
-                    match add_messages_to_locked_work_vec(
-                        &lockable_work_vec,
-                        requeue_vec,
-                    ) {
-                        Ok(num_msgs_in_vec) => {
-                            trace!(
-                                "{log_label} - requeue shutdown message \
-                                success with total in vec={num_msgs_in_vec}"
-                            );
-                        }
-                        Err(e) => {
-                            error!(
-                                "{log_label} - failed to requeue shutdown \
-                                message into vec with err={e}"
-                            );
-                        }
+        }
+        // wake up any callers blocked on a full work vec
+        pool_state.space_available.notify_waiters();
+        #[cfg(feature = "metrics")]
+        set_queue_depth(&config.label, work_queues.len());
+        trace!("{log_label} - processing {} msgs", work_vec.len());
+        pool_state
+            .health
+            .set_state(&log_label, KafkaWorkerState::Running);
+        // publish the messages with a retry timer
+        while !work_vec.is_empty() {
+            let msg = work_vec.remove(0);
+            if pool_state.is_abandoning() {
+                // the shutdown deadline expired
+                if let Some(delivery) = &msg.delivery {
+                    delivery.notify(Err(KafkaDeliveryError::Dropped));
+                }
+                if is_publishable(&msg) {
+                    pool_state.record_abandoned();
+                    #[cfg(feature = "metrics")]
+                    record_abandoned(&config.label, &log_label, 1);
+                }
+                if let Some(transaction) = &msg.transaction {
+                    transaction.notify_dropped();
+                    for _ in transaction.msgs.iter() {
+                        pool_state.record_abandoned();
                     }
-                    // success ends the retry loop
-                    break;
-                } else if msg.msg_type == KafkaPublishMessageType::Data {
-                    let payload_sub = msg.payload[..10].to_string();
-                    trace!(
-                        "{log_label} pub \
-                        topic={} data='{}'",
-                        msg.topic,
-                        payload_sub
+                    #[cfg(feature = "metrics")]
+                    record_abandoned(
+                        &config.label,
+                        &log_label,
+                        transaction.msgs.len(),
                     );
-                    let topic = msg.topic.clone();
-                    let mut owned_headers: OwnedHeaders = OwnedHeaders::new();
-                    if msg.headers.is_some() {
-                        owned_headers = convert_hashmap_headers_to_ownedheaders(
-                            msg.headers.clone().unwrap(),
-                            owned_headers,
+                }
+            } else if msg.msg_type == KafkaPublishMessageType::Shutdown {
+                // stop accepting messages and wake the other threads
+                // so every thread exits once the work vec is empty
+                trace!("{log_label} - received shutdown message");
+                pool_state.start_shutdown();
+            } else if is_publishable(&msg) {
+                // publish consecutive data messages as one concurrent
+                // batch (one at a time when ordering is required so a
+                // retry cannot reorder messages)
+                let max_batch_size =
+                    if config.ordering_mode == KafkaOrderingMode::Unordered {
+                        config.publish_batch_size
+                    } else {
+                        1
+                    };
+                let mut batch: Vec<KafkaPublishMessage> = vec![msg];
+                while batch.len() < max_batch_size
+                    && !work_vec.is_empty()
+                    && is_publishable(&work_vec[0])
+                {
+                    batch.push(work_vec.remove(0));
+                }
+                let results = publish_message_batch(
+                    &log_label,
+                    &producer,
+                    &config,
+                    &pool_state,
+                    batch,
+                )
+                .await;
+                let num_delivered =
+                    results.iter().filter(|res| res.is_ok()).count();
+                trace!(
+                    "{log_label} - published batch delivered={num_delivered} \
+                    failed={}",
+                    results.len() - num_delivered
+                );
+            } else if msg.msg_type == KafkaPublishMessageType::Transaction {
+                let transaction = msg.transaction.unwrap_or_default();
+                match &txn_producer {
+                    Some(cur_txn_producer) => {
+                        let num_msgs = transaction.msgs.len();
+                        let (res, fatal) = publish_transaction(
+                            &log_label,
+                            cur_txn_producer,
+                            &config,
+                            &pool_state,
+                            transaction,
+                        )
+                        .await;
+                        trace!(
+                            "{log_label} - transaction msgs={num_msgs} \
+                            committed={}",
+                            res.is_ok()
                         );
-                    }
-                    // success ends the retry loop
-                    loop {
-                        let delivery_status =
-                            publish_message(&producer, &msg, &owned_headers)
-                                .await;
-                        if delivery_status != -1 {
-                            trace!("published message topic={topic}");
-                            break;
-                        } else {
+                        if fatal {
+                            // the producer cannot start another
+                            // transaction
                             error!(
-                                "failed to publish delivery status={} \
-                                retrying msg={:?}",
-                                delivery_status, msg
-                            );
-                            std::thread::sleep(
-                                std::time::Duration::from_millis(
-                                    config.retry_sleep_sec,
-                                ),
+                                "{log_label} - \
+                                transactional producer failed - recreating"
                             );
+                            match create_txn_producer(
+                                cur_thread_num,
+                                &config,
+                                wrap_context(build_kafka_worker_context(
+                                    &config,
+                                    &format!("{log_label}-txn"),
+                                    &log_label,
+                                    &pool_state,
+                                )),
+                            )
+                            .await
+                            {
+                                Ok(new_txn_producer) => {
+                                    txn_producer = Some(new_txn_producer);
+                                }
+                                Err(e) => {
+                                    error!(
+                                        "{log_label} - \
+                                        failed to recreate transactional \
+                                        producer with err={e} \
+                                        - stopping thread"
+                                    );
+                                    // the next running worker thread
+                                    // publishes the rest of the batch
+                                    work_queues.requeue(
+                                        cur_thread_num,
+                                        std::mem::take(&mut work_vec),
+                                    );
+                                    stop_thread = true;
+                                }
+                            }
                         }
                     }
-                } else if msg.msg_type
-                    == KafkaPublishMessageType::LogBrokerDetails
-                {
-                    info!(
-                        "{log_label} - get all broker config={} information",
-                        config
-                    );
-                    {
-                        let consumer = get_kafka_consumer(&config);
-                        let count_msgs =
-                            std::env::var("KAFKA_METADATA_COUNT_MSG_OFFSETS")
-                                .unwrap_or_else(|_| "true".to_string())
-                                == *"true";
-                        get_kafka_metadata(&config, consumer, count_msgs, None);
-                    }
-                    break;
-                } else if msg.msg_type
-                    == KafkaPublishMessageType::LogBrokerTopicDetails
-                {
-                    if msg.payload.is_empty() {
+                    None => {
                         error!(
                             "{log_label} - \
-                            unable to get broker config={} \
-                            missing topic={} in msg.payload",
-                            config, msg.payload
+                            unable to publish transaction without \
+                            KAFKA_TRANSACTIONAL_ID_PREFIX"
                         );
-                    } else {
-                        info!(
+                        let err = KafkaDeliveryError::Broker {
+                            code: None,
+                            reason: "transactions are not enabled".to_string(),
+                        };
+                        for txn_msg in transaction.msgs.iter() {
+                            if let Some(delivery) = &txn_msg.delivery {
+                                delivery.notify(Err(err.clone()));
+                            }
+                            pool_state.record_failed();
+                        }
+                        if let Some(notifier) = &transaction.notifier {
+                            notifier.notify(Err(err));
+                        }
+                    }
+                }
+            } else if msg.msg_type == KafkaPublishMessageType::LogBrokerDetails
+            {
+                info!(
+                    "{log_label} - get all broker config={} information",
+                    config
+                );
+                {
+                    let count_msgs =
+                        std::env::var("KAFKA_METADATA_COUNT_MSG_OFFSETS")
+                            .unwrap_or_else(|_| "true".to_string())
+                            == *"true";
+                    match get_metadata(&config, count_msgs, None).await {
+                        Ok(metadata) => log_kafka_cluster_metadata(&metadata),
+                        Err(e) => error!(
                             "{log_label} - \
-                            get broker config={} topic={} information",
-                            config, msg.payload
-                        );
-                        let consumer = get_kafka_consumer(&config);
-                        let count_msgs =
-                            std::env::var("KAFKA_METADATA_COUNT_MSG_OFFSETS")
-                                .unwrap_or_else(|_| "true".to_string())
-                                == *"true";
-                        get_kafka_metadata(
-                            &config,
-                            consumer,
-                            count_msgs,
-                            Some(&msg.payload),
-                        );
+                            failed to get metadata with err={e}"
+                        ),
                     }
-                    break;
-                } else {
+                }
+            } else if msg.msg_type
+                == KafkaPublishMessageType::LogBrokerTopicDetails
+            {
+                let topic = msg.payload_as_string().unwrap_or_default();
+                if topic.is_empty() {
                     error!(
                         "{log_label} - \
-                        unsupported KafkaPublishMessageType={:?}",
-                        msg.msg_type
+                        unable to get broker config={} \
+                        missing topic={} in msg.payload",
+                        config, topic
                     );
-                    break;
-                }
-            }
-            // after processing everything in the vec - break the main thread loop if shutting down
-            if should_shutdown {
-                let num_left = work_vec.len();
-                if num_left == 0 {
-                    trace!("{log_label} - work vec empty={num_left}");
                 } else {
-                    error!("{log_label} - work vec NOT empty={num_left}");
+                    info!(
+                        "{log_label} - \
+                        get broker config={} topic={} information",
+                        config, topic
+                    );
+                    let count_msgs =
+                        std::env::var("KAFKA_METADATA_COUNT_MSG_OFFSETS")
+                            .unwrap_or_else(|_| "true".to_string())
+                            == *"true";
+                    match get_metadata(&config, count_msgs, Some(topic)).await {
+                        Ok(metadata) => log_kafka_cluster_metadata(&metadata),
+                        Err(e) => error!(
+                            "{log_label} - \
+                            failed to get topic metadata with err={e}"
+                        ),
+                    }
                 }
-                break;
+            } else {
+                error!(
+                    "{log_label} - \
+                    unsupported KafkaPublishMessageType={:?}",
+                    msg.msg_type
+                );
+            }
+        }
+        if stop_thread {
+            break;
+        }
+    }
+    // make sure librdkafka sent everything before the thread exits
+    let flush_producer = producer.clone();
+    // never flush past the shutdown deadline
+    let flush_timeout = pool_state.flush_timeout();
+    match tokio::task::spawn_blocking(move || {
+        flush_producer.flush(Timeout::After(flush_timeout))
+    })
+    .await
+    {
+        Ok(Ok(())) => trace!("{log_label} - flushed producer"),
+        Ok(Err(e)) => {
+            error!("{log_label} - failed to flush producer with err={e}")
+        }
+        Err(e) => {
+            error!("{log_label} - failed to flush producer with err={e}")
+        }
+    }
+    if let Some(txn_producer) = txn_producer {
+        let flush_timeout = pool_state.flush_timeout();
+        match tokio::task::spawn_blocking(move || {
+            txn_producer.flush(Timeout::After(flush_timeout))
+        })
+        .await
+        {
+            Ok(Ok(())) => trace!("{log_label} - flushed txn producer"),
+            Ok(Err(e)) => {
+                error!(
+                    "{log_label} - failed to flush txn producer with err={e}"
+                )
+            }
+            Err(e) => {
+                error!(
+                    "{log_label} - failed to flush txn producer with err={e}"
+                )
             }
-            // if everything published, clear the temp drained vec
-            work_vec.clear();
         }
     }
+    pool_state
+        .health
+        .set_state(&log_label, KafkaWorkerState::Exited);
     info!("{log_label} - done exiting thread");
     // THREAD CONTEXT - end
 }
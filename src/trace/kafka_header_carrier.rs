@@ -0,0 +1,47 @@
+//! OpenTelemetry propagation carriers for the ordered
+//! [`KafkaHeader`](crate::api::kafka_header::KafkaHeader) list
+//!
+use opentelemetry::propagation::Extractor;
+use opentelemetry::propagation::Injector;
+
+use crate::api::kafka_header::KafkaHeader;
+
+/// KafkaHeaderInjector
+///
+/// Writes propagation fields into a message's headers. Setting a
+/// field replaces any existing headers with the same key so a
+/// message never carries two ``traceparent`` headers.
+///
+pub struct KafkaHeaderInjector<'a>(pub &'a mut Vec<KafkaHeader>);
+
+impl<'a> Injector for KafkaHeaderInjector<'a> {
+    fn set(&mut self, key: &str, value: String) {
+        self.0.retain(|header| header.key != key);
+        self.0.push(KafkaHeader::from_str_value(key, &value));
+    }
+}
+
+/// KafkaHeaderExtractor
+///
+/// Reads propagation fields from a message's headers. The first
+/// header with a UTF-8 value wins when a key repeats.
+///
+pub struct KafkaHeaderExtractor<'a>(pub &'a [KafkaHeader]);
+
+impl<'a> Extractor for KafkaHeaderExtractor<'a> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .filter(|header| header.key == key)
+            .find_map(|header| {
+                header
+                    .value
+                    .as_deref()
+                    .and_then(|value| std::str::from_utf8(value).ok())
+            })
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.iter().map(|header| header.key.as_str()).collect()
+    }
+}
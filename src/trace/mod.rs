@@ -0,0 +1,15 @@
+//! ``tracing`` spans for every publish with W3C trace context
+//! propagation through the kafka message headers (requires the
+//! ``tracing`` feature)
+//!
+//! The caller's current span is captured when a message is added
+//! to the work vec and becomes the parent of the worker thread's
+//! ``kafka.publish`` span. The ``kafka.publish`` span context is
+//! injected into the outgoing record as the ``traceparent`` and
+//! ``tracestate`` headers when an OpenTelemetry layer is installed
+//! so downstream consumers can continue the trace with
+//! [`extract_trace_context`](crate::trace::trace_context::extract_trace_context).
+//!
+pub mod kafka_header_carrier;
+pub mod publish_span;
+pub mod trace_context;
@@ -0,0 +1,90 @@
+//! Create and record the per-message ``kafka.publish`` span
+//!
+use tracing::field::Empty;
+use tracing::Span;
+
+use crate::api::kafka_delivery_notifier::KafkaDeliveryResult;
+use crate::api::kafka_publish_message::KafkaPublishMessage;
+
+/// attach_current_span
+///
+/// Capture the caller's current span on every message (and every
+/// message in a transaction) that does not already carry one so
+/// the span stays open while the message waits in the work vec
+///
+/// # Arguments
+///
+/// * `msgs` - messages about to be added to the work vec
+///
+pub fn attach_current_span(msgs: &mut [KafkaPublishMessage]) {
+    let current = Span::current();
+    if current.is_disabled() {
+        return;
+    }
+    for msg in msgs.iter_mut() {
+        if msg.span.is_none() {
+            msg.span = Some(current.clone());
+        }
+        if let Some(transaction) = msg.transaction.as_mut() {
+            for txn_msg in transaction.msgs.iter_mut() {
+                if txn_msg.span.is_none() {
+                    txn_msg.span = Some(current.clone());
+                }
+            }
+        }
+    }
+}
+
+/// start_publish_span
+///
+/// Create the ``kafka.publish`` span for a message as a child of
+/// the caller's span captured by [`attach_current_span`]
+///
+/// The ``partition`` and ``offset`` fields are recorded with
+/// [`record_publish_result`] and ``attempt`` is recorded before
+/// each send.
+///
+/// # Arguments
+///
+/// * `log_label` - worker thread log label
+/// * `msg` - message being published
+///
+pub fn start_publish_span(log_label: &str, msg: &KafkaPublishMessage) -> Span {
+    tracing::info_span!(
+        parent: msg.span.as_ref().and_then(|span| span.id()),
+        "kafka.publish",
+        otel.name = %format!("{} publish", msg.topic),
+        otel.kind = "producer",
+        otel.status_code = Empty,
+        messaging.system = "kafka",
+        worker = %log_label,
+        topic = %msg.topic,
+        partition = Empty,
+        offset = Empty,
+        attempt = 0u32,
+        error = Empty,
+    )
+}
+
+/// record_publish_result
+///
+/// Record the delivered ``partition`` and ``offset`` or the
+/// delivery error on a ``kafka.publish`` span
+///
+/// # Arguments
+///
+/// * `span` - span created with [`start_publish_span`]
+/// * `result` - final outcome for the message
+///
+pub fn record_publish_result(span: &Span, result: &KafkaDeliveryResult) {
+    match result {
+        Ok(report) => {
+            span.record("partition", report.partition);
+            span.record("offset", report.offset);
+        }
+        Err(e) => {
+            span.record("otel.status_code", "ERROR");
+            span.record("error", tracing::field::display(e));
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Inject and extract the W3C ``traceparent`` and ``tracestate``
+//! headers
+//!
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry::sdk::propagation::TraceContextPropagator;
+use opentelemetry::Context;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::api::kafka_header::KafkaHeader;
+use crate::trace::kafka_header_carrier::KafkaHeaderExtractor;
+use crate::trace::kafka_header_carrier::KafkaHeaderInjector;
+
+/// inject_trace_headers
+///
+/// Add the W3C ``traceparent`` (and ``tracestate`` if set) headers
+/// for the ``span`` to the message headers. Nothing is added
+/// unless a ``tracing_opentelemetry`` layer is recording the span.
+///
+/// # Arguments
+///
+/// * `span` - span the downstream consumers continue from
+/// * `headers` - message headers to update
+///
+pub fn inject_trace_headers(span: &Span, headers: &mut Vec<KafkaHeader>) {
+    TraceContextPropagator::new()
+        .inject_context(&span.context(), &mut KafkaHeaderInjector(headers));
+}
+
+/// extract_trace_context
+///
+/// Read the W3C ``traceparent`` and ``tracestate`` headers from a
+/// consumed message
+///
+/// # Arguments
+///
+/// * `headers` - consumed message headers
+///
+/// # Returns
+///
+/// OpenTelemetry ``Context`` for the producer's ``kafka.publish``
+/// span (an empty ``Context`` if the headers are missing or invalid)
+///
+/// # Examples
+///
+/// ```rust
+/// use tracing_opentelemetry::OpenTelemetrySpanExt;
+/// use kafka_threadpool::trace::trace_context::extract_trace_context;
+/// let span = tracing::info_span!("kafka.consume");
+/// span.set_parent(extract_trace_context(&consumed_headers));
+/// ```
+///
+pub fn extract_trace_context(headers: &[KafkaHeader]) -> Context {
+    TraceContextPropagator::new().extract(&KafkaHeaderExtractor(headers))
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::sdk::trace::TracerProvider;
+    use opentelemetry::trace::TraceContextExt;
+    use opentelemetry::trace::TracerProvider as _;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    // record spans with an OpenTelemetry tracer for the closure (the
+    // provider must outlive the spans)
+    fn with_tracer<T>(f: impl FnOnce() -> T) -> T {
+        let provider = TracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry().with(
+            tracing_opentelemetry::layer().with_tracer(provider.tracer("test")),
+        );
+        tracing::subscriber::with_default(subscriber, f)
+    }
+
+    fn header_value(headers: &[KafkaHeader], key: &str) -> Option<String> {
+        headers
+            .iter()
+            .find(|header| header.key == key)
+            .and_then(|header| header.value_as_string())
+    }
+
+    #[test]
+    fn inject_adds_a_w3c_traceparent() {
+        let headers = with_tracer(|| {
+            let span = tracing::info_span!("kafka.publish");
+            let mut headers =
+                vec![KafkaHeader::from_str_value("app", "billing")];
+            inject_trace_headers(&span, &mut headers);
+            headers
+        });
+        assert_eq!(headers[0].key, "app");
+        let traceparent = header_value(&headers, "traceparent").unwrap();
+        // version-trace id-span id-flags
+        let fields: Vec<&str> = traceparent.split('-').collect();
+        assert_eq!(fields.len(), 4, "traceparent={traceparent}");
+        assert_eq!(fields[0], "00");
+        assert_eq!(fields[1].len(), 32);
+        assert_eq!(fields[2].len(), 16);
+        assert_eq!(fields[3], "01");
+        assert!(fields[1..3]
+            .iter()
+            .all(|field| field.chars().all(|c| c.is_ascii_hexdigit())));
+    }
+
+    #[test]
+    fn inject_replaces_an_existing_traceparent() {
+        let stale = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01";
+        let headers = with_tracer(|| {
+            let span = tracing::info_span!("kafka.publish");
+            let mut headers = vec![
+                KafkaHeader::from_str_value("traceparent", stale),
+                KafkaHeader::from_str_value("app", "billing"),
+                KafkaHeader::from_str_value("traceparent", stale),
+            ];
+            inject_trace_headers(&span, &mut headers);
+            headers
+        });
+        let traceparents: Vec<&KafkaHeader> = headers
+            .iter()
+            .filter(|header| header.key == "traceparent")
+            .collect();
+        assert_eq!(traceparents.len(), 1);
+        assert_ne!(traceparents[0].value_as_string().unwrap(), stale);
+        assert_eq!(headers[0].key, "app");
+    }
+
+    #[test]
+    fn inject_without_a_recording_span_adds_nothing() {
+        let span = tracing::info_span!("kafka.publish");
+        let mut headers = vec![KafkaHeader::from_str_value("app", "billing")];
+        inject_trace_headers(&span, &mut headers);
+        assert_eq!(headers.len(), 1);
+    }
+
+    #[test]
+    fn extract_continues_the_injected_trace() {
+        let (trace_id, headers) = with_tracer(|| {
+            let span = tracing::info_span!("kafka.publish");
+            let mut headers = Vec::new();
+            inject_trace_headers(&span, &mut headers);
+            (span.context().span().span_context().trace_id(), headers)
+        });
+        let context = extract_trace_context(&headers);
+        let span_context = context.span().span_context().clone();
+        assert!(span_context.is_remote());
+        assert_eq!(span_context.trace_id(), trace_id);
+    }
+
+    #[test]
+    fn extract_ignores_invalid_headers() {
+        let context = extract_trace_context(&[KafkaHeader::new(
+            "traceparent",
+            Some(vec![0xff, 0xfe]),
+        )]);
+        assert!(!context.span().span_context().is_valid());
+    }
+}